@@ -1,689 +1,10572 @@
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, BankMsg, Coin, Deps, DepsMut, Env, MessageInfo, QueryResponse,
-    Response, StdError, StdResult, Uint128,
+    entry_point, to_binary, Addr, BankMsg, Binary, CanonicalAddr, Coin, Decimal, Deps, DepsMut, DistributionMsg, Env, MessageInfo,
+    QueryRequest, QueryResponse, Response, StakingMsg, StdError, StdResult, Storage, Uint128, WasmMsg, WasmQuery,
 };
 
 use hex;
+use std::collections::{BTreeMap, BTreeSet};
 
-use rsa::{PublicKey, RsaPublicKey, pkcs8::DecodePublicKey, PaddingScheme};
-use rand::{SeedableRng};
+use aes_gcm_siv::aead::{Aead, KeyInit};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce};
+use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaChaRng;
+use sha2::{Digest, Sha256};
+use bech32::{ToBase32, Variant};
+use ripemd::Ripemd160;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use x25519_dalek::{PublicKey, StaticSecret};
 
+use crate::crypto::{derive_event_seed, derive_ticket_seed, derive_ticket_secret};
+use crate::error::ContractError;
 use crate::msg::{
-    BalanceResponse, EventsResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SoldOutResponse,
-    TicketsResponse,
+    BalanceResponse, BalancesResponse, AccountBalance, BuyTicketMsg, InsurancePoolResponse, EventRevenueResponse, PublicKeyType, QrPayloadResponse, TicketCodeResponse, ViewingKeyResponse, CreateEventResponse, TicketIdResponse, TicketIdsResponse, VerifyTicketResponse, VerifyTicketsResponse, Permit, PermitCoin, PermitFee, PermitParams, PermitSignDoc, PermitSignMsg, Permission, QueryWithPermit, PrivateMetadataResponse, AttendanceProofResponse, VerifyAttendanceResponse, CheckInReceipt, CheckInReceiptsResponse, TicketAction, TicketHistoryEntry, TicketHistoryResponse, CheckInResponse, VerifyCheckInReceiptResponse, OfflineCheckin, OfflineCheckinsResponse, OfflineCodeResponse, OfflineCodesResponse, InstallmentsResponse, ContractStatusLevel, DisputeOutcome, ContractStatusResponse, ContractInfoResponse, FeeConfigResponse, LimitsResponse, SolvencyResponse, StakingResponse, ExchangeRateResponse, PendingExchangeRate, OracleContract, OracleQueryMsg, ReferenceData, BuyTicketsMsg, CreateEventMsg, CreatePassMsg, RedeemPassMsg, OpenGroupOrderMsg, GroupOrderIdResponse, GroupOrderResponse, PassIdResponse, PassResponse, PassesResponse, PassHoldingInfo, IssueCompTicketMsg, PriceStepMsg, EventsResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, PayoutInfo, PayoutsResponse, SeatsResponse, SoldOutResponse, TierAvailability, EventsSoldOutResponse, EventAvailability, ListingInfo, ListingsResponse, AttendeeInfo, AttendeesResponse, TicketValidityResponse, OrganiserProfileResponse, EventSummary, TicketInfo, TicketState, WaitlistEntryInfo, WaitlistPositionResponse, WaitlistResponse, CheckInBucket, CheckInStatsResponse, EventInfoResponse, EventStatus, ListEventsResponse, TierInfo,
+    TicketsResponse, AllowanceResponse, AllowancesResponse, ReceiverHandleMsg, Snip20Msg, TokenInfo, TokenInfoResponse,
 };
 use crate::state::{
-    get_config, Balances, Config, Event, Events, GuestsTickets, OrganisersEvents, ReadonlyBalances,
-    ReadonlyEvents, ReadonlyGuestsTickets, ReadonlyOrganisersEvents, ReadonlyTickets, Ticket,
-    Tickets,
+    get_config, get_config_readonly, get_schema_version, get_schema_version_readonly, upgrade_entries, bps_of, ct_eq, get_legacy_config_readonly, LegacyEvent, LegacyTicket, SCHEMA_VERSION, PREFIX_EVENTS, PREFIX_TICKETS, DEFAULT_DENOM, Allowlists, CheckIn, CheckIns, ReadonlyCheckIns, GuestKey, KEY_ED25519, KEY_RSA, KEY_SECP256K1, KEY_X25519, Dispute, Disputes, Organiser, Organisers, ReadonlyOrganisers, CODE_DIGITS, CODE_WINDOW, DEFAULT_CHALLENGE_TTL, DEFAULT_ORGANISER_BOND, Balances, BannedGuests, Config, InstallmentPlan, DutchAuction, Event, EventSeats, Events, GuestsTickets, Listing, Listings,
+    OrganisersEvents, Pass, PassHolding, Passes, ReadonlyPasses, GuestsPasses, ReadonlyGuestsPasses, GroupOrder, GroupOrders, ReadonlyGroupOrders, Payee, Payout, Payouts, PriceStep, PromoCode, PromoCodes, Session, ReadonlyAllowlists, ReadonlyBalances, ReadonlyPayouts, ReadonlyBannedGuests, ReadonlyEventSeats, ReadonlyEvents, ReadonlyListings,
+    ReadonlyWaitlists, WaitlistEntry, Waitlists, ReadonlyGuestsTickets, ReadonlyOrganisersEvents, ReadonlyTickets, Ticket,
+    Tickets, Tier, EventsTickets, ReadonlyEventsTickets, TicketHistories, ReadonlyTicketHistories, TicketRecord, ACTION_PURCHASED, ACTION_CHALLENGED, ACTION_CHECKED_IN, ACTION_TRANSFERRED, ACTION_REVOKED, ACTION_REFUNDED, ReadonlyViewingKeys, ViewingKeys, RevokedPermits, ReadonlyRevokedPermits, PrivateMetadata, ReadonlyPrivateMetadata, Receivers, ReadonlyReceivers, OrganiserProfile, OrganiserProfiles, ReadonlyOrganiserProfiles, Allowance, Allowances, ReadonlyAllowances, AllowanceIndexes, ReadonlyAllowanceIndexes, Asset, Fundings, ReadonlyFundings, Reserves, ReadonlyReserves, Staking, ExchangeRate, ADDRESS_PREFIX, CONTRACT_NAME, CONTRACT_VERSION, MAX_ORACLE_AGE, ORACLE_BASE_SYMBOL, ORACLE_QUOTE_SYMBOL, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT, DEFAULT_STATS_BUCKET, MIN_STATS_BUCKET, TOKEN_DECIMALS, TOKEN_NAME, TOKEN_SYMBOL, VIEWING_KEY_PREFIX, STATUS_NORMAL, STATUS_STOP_ALL, STATUS_STOP_PURCHASES, TICKET_REFUNDED, TICKET_RESERVED, TICKET_REVOKED, TICKET_USED, TICKET_VALIDATING, TICKET_VALID,
 };
 
-use extprim::u128;
 
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    // Seed contract randomness from the given entropy and instantiation context
+    let entropy_raw = match hex::decode(&msg.entropy) {
+        Ok(entropy) => entropy,
+        Err(_) => return Err(StdError::generic_err("Entropy is not a valid hex string")),
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(&entropy_raw);
+    hasher.update(env.contract.address.as_bytes());
+    hasher.update(info.sender.as_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    let prng_seed = hasher.finalize().into();
+
     // Construct contract config
-    let owner_addr_canon = deps.api.addr_canonicalize(info.sender.as_str());
-    let config = Config::new(owner_addr_canon.unwrap()); // Can we call unwrap safely here?
+    let owner_addr_canon = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let organiser_bond = msg.organiser_bond.map_or(DEFAULT_ORGANISER_BOND, |bond| bond.u128());
+    let mut config = Config::new(owner_addr_canon, organiser_bond, prng_seed);
+    if let Some(denoms) = msg.denoms {
+        config.set_denoms(denoms)?;
+    }
+
+    // Register to receive sSCRT deposits, if configured
+    let mut response = Response::new();
+    if let Some(sscrt) = msg.sscrt {
+        let sscrt_addr = deps.api.addr_validate(&sscrt.address)?;
+        config.set_sscrt(Some((deps.api.addr_canonicalize(sscrt_addr.as_str())?, sscrt.code_hash.clone())));
+        let register_msg = Snip20Msg::RegisterReceive { code_hash: env.contract.code_hash, padding: None };
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: sscrt_addr.into_string(),
+            code_hash: sscrt.code_hash,
+            msg: to_binary(&register_msg)?,
+            funds: vec![],
+        });
+    }
 
-    // Save config
+    // Save config and the storage layout it is created with
     get_config(deps.storage).save(&config)?;
+    get_schema_version(deps.storage).save(&SCHEMA_VERSION)?;
 
-    Ok(Response::default())
+    Ok(response)
 }
 
 #[entry_point]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response, StdError> {
+) -> Result<Response, ContractError> {
+    // Fold block data and sender into the contract seed on every call
+    let mut config = get_config(deps.storage).load()?;
+    let mut hasher = Sha256::new();
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    hasher.update(info.sender.as_bytes());
+    config.add_entropy(&hasher.finalize());
+    get_config(deps.storage).save(&config)?;
+
+    // Ensure contract status allows this message
+    match (config.get_status(), &msg) {
+        (_, ExecuteMsg::SetContractStatus { .. }) => (),
+        (STATUS_STOP_ALL, _) => return Err(StdError::generic_err("Contract is halted").into()),
+        (
+            STATUS_STOP_PURCHASES,
+            ExecuteMsg::Withdraw { .. }
+            | ExecuteMsg::Transfer { .. }
+            | ExecuteMsg::Send { .. }
+            | ExecuteMsg::TransferFrom { .. }
+            | ExecuteMsg::BuyTicket(_)
+            | ExecuteMsg::BuyTickets(_)
+            | ExecuteMsg::BuyPass { .. }
+            | ExecuteMsg::RedeemPass(_)
+            | ExecuteMsg::OpenGroupOrder(_)
+            | ExecuteMsg::ContributeGroupOrder { .. }
+            | ExecuteMsg::PayInstallment { .. }
+            | ExecuteMsg::BuyResaleTicket { .. }
+            | ExecuteMsg::JoinWaitlist { .. }
+            | ExecuteMsg::UnregisterOrganiser {},
+        ) => return Err(StdError::generic_err("Purchases and withdrawals are halted").into()),
+        _ => (),
+    }
+
     match msg {
-        ExecuteMsg::Deposit {} => try_deposit(deps, info),
-        ExecuteMsg::Withdraw { amount } => try_withdraw(deps, info, amount),
-        ExecuteMsg::CreateEvent { price, max_tickets, entropy } => {
-            try_create_event(deps, info, price, max_tickets, entropy)
+        ExecuteMsg::Deposit {} => try_deposit(deps, env, info),
+        ExecuteMsg::RegisterOrganiser {} => try_register_organiser(deps, info),
+        ExecuteMsg::UnregisterOrganiser {} => try_unregister_organiser(deps, env, info),
+        ExecuteMsg::SetOrganiserProfile { display_name, contact_uri, payout_address } => {
+            try_set_organiser_profile(deps, info, display_name, contact_uri, payout_address)
+        }
+        ExecuteMsg::Withdraw { amount, denom } => try_withdraw(deps, env, info, amount, denom),
+        ExecuteMsg::Transfer { recipient, amount, .. } => try_transfer(deps, info, recipient, amount),
+        ExecuteMsg::Send { recipient, recipient_code_hash, amount, msg, memo } => {
+            try_send(deps, info, recipient, recipient_code_hash, amount, msg, memo)
+        }
+        ExecuteMsg::IncreaseAllowance { spender, amount, expiration } => {
+            try_change_allowance(deps, info, spender, amount, expiration, true)
+        }
+        ExecuteMsg::DecreaseAllowance { spender, amount, expiration } => {
+            try_change_allowance(deps, info, spender, amount, expiration, false)
+        }
+        ExecuteMsg::TransferFrom { owner, recipient, amount, .. } => {
+            try_transfer_from(deps, env, info, owner, recipient, amount)
+        }
+        ExecuteMsg::Receive { from, amount, .. } => try_receive(deps, env, info, from, amount),
+        ExecuteMsg::RegisterReceive { code_hash } => try_register_receive(deps, info, code_hash),
+        ExecuteMsg::CreateViewingKey { entropy } => try_create_viewing_key(deps, info, entropy),
+        ExecuteMsg::SetViewingKey { key } => try_set_viewing_key(deps, info, key),
+        ExecuteMsg::RevokePermit { permit_name } => try_revoke_permit(deps, info, permit_name),
+        ExecuteMsg::SetPrivateMetadata { event_id, metadata } => {
+            try_set_private_metadata(deps, info, event_id, metadata)
+        }
+        ExecuteMsg::CreateEvent(msg) => try_create_event(deps, info, *msg),
+        ExecuteMsg::BuyTicket(msg) => try_buy_ticket(deps, env, info, msg),
+        ExecuteMsg::IssueCompTicket(msg) => try_issue_comp_ticket(deps, env, info, msg),
+        ExecuteMsg::BuyTickets(msg) => try_buy_tickets(deps, env, info, msg),
+        ExecuteMsg::CreatePass(msg) => try_create_pass(deps, info, msg),
+        ExecuteMsg::BuyPass { pass_id } => try_buy_pass(deps, env, info, pass_id),
+        ExecuteMsg::RedeemPass(msg) => try_redeem_pass(deps, env, info, msg),
+        ExecuteMsg::OpenGroupOrder(msg) => try_open_group_order(deps, env, info, msg),
+        ExecuteMsg::ContributeGroupOrder { order_id, amount } => {
+            try_contribute_group_order(deps, env, info, order_id, amount)
+        }
+        ExecuteMsg::CancelGroupOrder { order_id } => try_cancel_group_order(deps, env, info, order_id),
+        ExecuteMsg::VerifyTicket { ticket_id } => try_verify_ticket(deps, env, info, ticket_id),
+        ExecuteMsg::VerifyTickets { ticket_ids } => try_verify_tickets(deps, env, info, ticket_ids),
+        ExecuteMsg::VerifyGuest { ticket_id, secret, nonce } => {
+            try_verify_guest(deps, env, info, ticket_id, secret, nonce)
+        }
+        ExecuteMsg::ConfirmCheckIn { ticket_id } => try_confirm_check_in(deps, env, info, ticket_id),
+        ExecuteMsg::CheckIn { ticket_id } => try_check_in(deps, env, info, ticket_id),
+        ExecuteMsg::AcknowledgeCheckIn { ticket_id, code } => {
+            try_acknowledge_check_in(deps, env, info, ticket_id, code)
+        }
+        ExecuteMsg::IssueOfflineCodes { event_id } => try_issue_offline_codes(deps, info, event_id),
+        ExecuteMsg::SubmitOfflineCheckins { event_id, checkins } => {
+            try_submit_offline_checkins(deps, env, info, event_id, checkins)
+        }
+        ExecuteMsg::VerifyTicketCode { ticket_id, code } => try_verify_ticket_code(deps, env, info, ticket_id, code),
+        ExecuteMsg::VerifyQrPayload { payload } => try_verify_qr_payload(deps, env, info, payload),
+        ExecuteMsg::VerifyGuestSignature { ticket_id, signature } => {
+            try_verify_guest_signature(deps, env, info, ticket_id, signature)
+        }
+        ExecuteMsg::RevokeTicket { ticket_id } => try_revoke_ticket(deps, env, info, ticket_id),
+        ExecuteMsg::RefundTicket { ticket_id } => try_refund_ticket(deps, env, info, ticket_id),
+        ExecuteMsg::PayInstallment { ticket_id } => try_pay_installment(deps, env, info, ticket_id),
+        ExecuteMsg::CancelInstallments { ticket_id } => try_cancel_installments(deps, env, info, ticket_id),
+        ExecuteMsg::ForfeitTicket { ticket_id } => try_forfeit_ticket(deps, env, info, ticket_id),
+        ExecuteMsg::TransferTicket { ticket_id, recipient, new_pk, new_pk_type } => {
+            try_transfer_ticket(deps, env, info, ticket_id, recipient, new_pk, new_pk_type)
+        }
+        ExecuteMsg::ListTicketForSale { ticket_id, price } => {
+            try_list_ticket_for_sale(deps, info, ticket_id, price)
+        }
+        ExecuteMsg::DelistTicket { ticket_id } => try_delist_ticket(deps, info, ticket_id),
+        ExecuteMsg::AddPromoCode { event_id, code_hash, discount_percent, max_uses } => {
+            try_add_promo_code(deps, info, event_id, code_hash, discount_percent, max_uses)
+        }
+        ExecuteMsg::JoinWaitlist { event_id, entropy, pk, pk_type } => {
+            try_join_waitlist(deps, info, event_id, entropy, pk, pk_type)
+        }
+        ExecuteMsg::BuyResaleTicket { ticket_id, new_pk, new_pk_type } => {
+            try_buy_resale_ticket(deps, env, info, ticket_id, new_pk, new_pk_type)
+        }
+        ExecuteMsg::SetCapacity { event_id, max_tickets, tier } => {
+            try_set_capacity(deps, info, event_id, max_tickets, tier)
+        }
+        ExecuteMsg::SetAllowlist { event_id, addresses } => {
+            try_set_allowlist(deps, info, event_id, addresses)
+        }
+        ExecuteMsg::AddCoOrganiser { event_id, address } => {
+            try_add_co_organiser(deps, info, event_id, address)
+        }
+        ExecuteMsg::RemoveCoOrganiser { event_id, address } => {
+            try_remove_co_organiser(deps, info, event_id, address)
+        }
+        ExecuteMsg::AddVerifier { event_id, address } => try_add_verifier(deps, info, event_id, address),
+        ExecuteMsg::RemoveVerifier { event_id, address } => {
+            try_remove_verifier(deps, info, event_id, address)
         }
-        ExecuteMsg::BuyTicket { event_id, entropy, pk } => try_buy_ticket(deps, info, event_id, entropy, pk),
-        ExecuteMsg::VerifyTicket { ticket_id } => try_verify_ticket(deps, info, ticket_id),
-        ExecuteMsg::VerifyGuest { ticket_id, secret } => {
-            try_verify_guest(deps, info, ticket_id, secret)
+        ExecuteMsg::BanGuest { event_id, address } => {
+            try_set_guest_banned(deps, info, event_id, address, true)
         }
+        ExecuteMsg::UnbanGuest { event_id, address } => {
+            try_set_guest_banned(deps, info, event_id, address, false)
+        }
+        ExecuteMsg::ClaimPayout { event_id } => try_claim_payout(deps, env, info, event_id),
+        ExecuteMsg::OpenDispute { ticket_id, reason } => try_open_dispute(deps, env, info, ticket_id, reason),
+        ExecuteMsg::ResolveDisputes { event_id, outcome } => {
+            try_resolve_disputes(deps, env, info, event_id, outcome)
+        }
+        ExecuteMsg::SetArbiters { addresses } => try_set_arbiters(deps, info, addresses),
+        ExecuteMsg::CancelEvent { event_id } => try_cancel_event(deps, env, info, event_id),
+        ExecuteMsg::CompleteEvent { event_id } => try_complete_event(deps, env, info, event_id),
+        ExecuteMsg::SlashOrganiser { event_id } => try_slash_organiser(deps, env, info, event_id),
+        ExecuteMsg::SetContractStatus { level } => try_set_contract_status(deps, info, level),
+        ExecuteMsg::UpdateFeeConfig { rate_bps, recipient } => {
+            try_update_fee_config(deps, info, rate_bps, recipient)
+        }
+        ExecuteMsg::SetDisputeWindow { seconds } => try_set_dispute_window(deps, info, seconds),
+        ExecuteMsg::SetLimits { min_deposit, min_withdrawal, withdrawal_cap } => {
+            try_set_limits(deps, info, min_deposit, min_withdrawal, withdrawal_cap)
+        }
+        ExecuteMsg::SetExchangeRate { rate, decimals } => {
+            try_set_exchange_rate(deps, env, info, rate, decimals)
+        }
+        ExecuteMsg::SetPriceOracle { oracle } => try_set_price_oracle(deps, info, oracle),
+        ExecuteMsg::SetStaking { validator, stake_bps, treasury } => {
+            try_set_staking(deps, info, validator, stake_bps, treasury)
+        }
+        ExecuteMsg::RebalanceStake {} => try_rebalance_stake(deps, env, info),
+        ExecuteMsg::ClaimStakingRewards {} => try_claim_staking_rewards(deps, info),
+        ExecuteMsg::PauseSales { event_id } => try_set_sales_paused(deps, info, event_id, true),
+        ExecuteMsg::ResumeSales { event_id } => try_set_sales_paused(deps, info, event_id, false),
     }
 }
 
+// Migrations upgrading storage from each schema version to the next, starting from version 0
+const MIGRATIONS: [fn(DepsMut, &Env) -> StdResult<()>; SCHEMA_VERSION as usize] =
+    [migrate_legacy_entries, migrate_address_lists];
+
 #[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
-    match msg {
-        QueryMsg::EventSoldOut { event_id } => to_binary(&query_event_sold_out(deps, event_id)?),
-        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
-        QueryMsg::Events { address } => to_binary(&query_events(deps, address)?),
-        QueryMsg::Tickets { address } => to_binary(&query_tickets(deps, address)?),
+pub fn migrate(mut deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // Run each migration from the stored schema version up to this code's
+    let version = get_schema_version_readonly(deps.storage).may_load()?.unwrap_or(0);
+    if version > SCHEMA_VERSION {
+        return Err(StdError::generic_err(format!(
+            "Cannot migrate from a newer storage schema: stored={}, supported={}",
+            version, SCHEMA_VERSION
+        )));
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(deps.branch(), &env)?;
     }
+    get_schema_version(deps.storage).save(&SCHEMA_VERSION)?;
+
+    Ok(Response::new().add_attribute("schema_version", SCHEMA_VERSION.to_string()))
 }
 
-// Function to handle user depositing SCRT tokens for sEVNT tokens
-pub fn try_deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, StdError> {
-    // Check if valid denomination tokens sent
-    let mut amount = Uint128::zero();
-    for coin in info.funds {
-        if coin.denom == "uscrt" {
-            amount = coin.amount;
-        } else {
-            return Err(StdError::generic_err(
-                "Tried to deposit an unsupported token",
-            ));
+// Upgrade the config, events and tickets from their layout before schema versioning to the
+// current one. Events keep their seeds, which are folded into the new contract seed along with
+// the block, and tickets are seeded from their event and the new contract seed
+fn migrate_legacy_entries(deps: DepsMut, env: &Env) -> StdResult<()> {
+    let legacy_config = get_legacy_config_readonly(deps.storage).load()?;
+    let (num_events, num_tickets) = (legacy_config.get_num_events(), legacy_config.get_num_tickets());
+    upgrade_entries(deps.storage, PREFIX_EVENTS, num_events, LegacyEvent::decode, LegacyEvent::upgrade)?;
+
+    // Seed contract randomness from the block and every event seed, which are secret
+    let mut events_info = BTreeMap::new();
+    let mut hasher = Sha256::new();
+    hasher.update(env.contract.address.as_bytes());
+    hasher.update(env.block.height.to_be_bytes());
+    hasher.update(env.block.time.nanos().to_be_bytes());
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    for event_id in 1..=num_events {
+        if let Some(event) = events.may_load_event(event_id)? {
+            hasher.update(event.get_seed());
+            events_info.insert(event_id, (event.get_tier(0).get_price(), *event.get_seed()));
         }
     }
+    let prng_seed = hasher.finalize().into();
 
-    // Check if non-negative number of tokens sent
-    if amount.is_zero() {
-        return Err(StdError::generic_err("No funds were sent to be deposited"));
+    // Every balance was backed by a deposit the contract still holds
+    let supply = deps.querier.query_balance(&env.contract.address, DEFAULT_DENOM)?.amount.u128();
+    let config = legacy_config.upgrade(prng_seed, supply);
+    get_config(deps.storage).save(&config)?;
+    Reserves::from_storage(deps.storage).set_reserve(DEFAULT_DENOM, supply);
+
+    upgrade_entries(deps.storage, PREFIX_TICKETS, num_tickets, LegacyTicket::decode, |legacy: LegacyTicket| {
+        let (price, event_seed) = events_info.get(&legacy.get_event_id()).copied().unwrap_or_default();
+        let ticket_id = legacy.get_id();
+        let seed = derive_ticket_seed(&event_seed, config.get_prng_seed(), ticket_id);
+        let code_seed = config.generate_code_seed(ticket_id, &[0; 32]);
+        legacy.upgrade(price, seed, code_seed)
+    })
+}
+
+// Move organisers events and guests tickets from the legacy layout, each list whole in one entry
+// keyed by the address string, to append stores keyed by address bytes, and index existing
+// tickets by event. Storage cannot be iterated, so addresses are found from events and tickets,
+// which must already be upgraded to the current layout. Safe to run again, as legacy entries are removed once moved and indexed events are skipped
+fn migrate_address_lists(deps: DepsMut, _env: &Env) -> StdResult<()> {
+    let storage = deps.storage;
+    let config = get_config_readonly(storage).load()?;
+
+    let mut organisers = BTreeSet::new();
+    let events = ReadonlyEvents::from_storage(storage);
+    for event_id in 1..=config.get_num_events() {
+        if let Some(event) = events.may_load_event(event_id)? {
+            organisers.insert(event.get_organiser().as_slice().to_vec());
+        }
+    }
+    let mut organisers_events = OrganisersEvents::from_storage(storage);
+    for organiser in organisers.into_iter().map(CanonicalAddr::from) {
+        for event_id in organisers_events.take_legacy_events(&organiser)? {
+            organisers_events.add_event(&organiser, event_id)?;
+        }
+    }
+
+    let mut guests = BTreeSet::new();
+    let mut events_tickets: BTreeMap<u128, Vec<u128>> = BTreeMap::new();
+    let tickets = ReadonlyTickets::from_storage(storage);
+    for ticket_id in 1..=config.get_num_tickets() {
+        if let Some(ticket) = tickets.may_load_ticket(ticket_id)? {
+            guests.insert(ticket.get_guest().as_slice().to_vec());
+            events_tickets.entry(ticket.get_event_id()).or_default().push(ticket_id);
+        }
+    }
+    let mut guests_tickets = GuestsTickets::from_storage(storage);
+    for guest in guests.into_iter().map(CanonicalAddr::from) {
+        for ticket_id in guests_tickets.take_legacy_tickets(&guest)? {
+            guests_tickets.add_ticket(&guest, ticket_id)?;
+        }
+    }
+    for (event_id, ticket_ids) in events_tickets {
+        if ReadonlyEventsTickets::from_storage(storage).iter_tickets(event_id, 0)?.next().is_some() {
+            continue;
+        }
+        let mut index = EventsTickets::from_storage(storage);
+        for ticket_id in ticket_ids {
+            index.add_ticket(event_id, ticket_id)?;
+        }
+    }
+    Ok(())
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<QueryResponse> {
+    match msg {
+        QueryMsg::EventSoldOut { event_id } => to_binary(&query_event_sold_out(deps, event_id)?),
+        QueryMsg::EventsSoldOut { event_ids } => to_binary(&query_events_sold_out(deps, event_ids)?),
+        QueryMsg::Listings { event_id, start_after, limit } => {
+            to_binary(&query_listings(deps, event_id, start_after, limit)?)
+        }
+        QueryMsg::SellerListings { start_after, limit, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_seller_listings(deps, &address, start_after, limit)?)
+        }
+        QueryMsg::EventInfo { event_id } => to_binary(&query_event_info(deps, env, event_id)?),
+        QueryMsg::OrganiserProfile { organiser } => to_binary(&query_organiser_profile(deps, organiser)?),
+        QueryMsg::ListEvents { start_after, limit, status } => {
+            to_binary(&query_list_events(deps, env, start_after, limit, |event, now| {
+                status.is_none_or(|status| event_status(event, now) == status)
+            })?)
+        }
+        QueryMsg::SearchEvents { text, tag, start_after, limit } => {
+            to_binary(&query_list_events(deps, env, start_after, limit, |event, _| {
+                event.matches_search(text.as_deref(), tag.as_deref())
+            })?)
+        }
+        QueryMsg::Balance { address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_balance(deps, &address)?)
+        }
+        QueryMsg::Balances { addresses, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_balances(deps, addresses, &address)?)
+        }
+        QueryMsg::Allowance { owner, spender, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_allowance(deps, owner, spender, &address)?)
+        }
+        QueryMsg::AllowancesGiven { start_after, limit, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_allowances(deps, &address, start_after, limit, true)?)
+        }
+        QueryMsg::AllowancesReceived { start_after, limit, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_allowances(deps, &address, start_after, limit, false)?)
+        }
+        QueryMsg::Events { address, key, status, start_after, limit } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_events(deps, env, &address, status, start_after, limit)?)
+        }
+        QueryMsg::Tickets { address, key, start_after, limit } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_tickets(deps, &address, start_after, limit)?)
+        }
+        QueryMsg::Pass { pass_id } => to_binary(&query_pass(deps, pass_id)?),
+        QueryMsg::GroupOrder { order_id } => to_binary(&query_group_order(deps, order_id)?),
+        QueryMsg::Passes { address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_passes(deps, &address)?)
+        }
+        QueryMsg::Payouts { event_id } => to_binary(&query_payouts(deps, event_id)?),
+        QueryMsg::ContractStatus {} => to_binary(&query_contract_status(deps)?),
+        QueryMsg::ContractInfo {} => to_binary(&query_contract_info(deps)?),
+        QueryMsg::FeeConfig {} => to_binary(&query_fee_config(deps)?),
+        QueryMsg::Limits {} => to_binary(&query_limits(deps)?),
+        QueryMsg::Solvency {} => to_binary(&query_solvency(deps, env)?),
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
+        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+        QueryMsg::InsurancePool {} => to_binary(&query_insurance_pool(deps)?),
+        QueryMsg::Staking {} => to_binary(&query_staking(deps, env)?),
+        QueryMsg::RemainingSeats { event_id } => to_binary(&query_remaining_seats(deps, event_id)?),
+        QueryMsg::QrPayload { ticket_id, expiry, signature } => {
+            to_binary(&query_qr_payload(deps, env, ticket_id, expiry, signature)?)
+        }
+        QueryMsg::TicketCode { ticket_id, signature } => {
+            to_binary(&query_ticket_code(deps, env, ticket_id, signature)?)
+        }
+        QueryMsg::PrivateMetadata { event_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_private_metadata(deps, event_id, &address)?)
+        }
+        QueryMsg::EventRevenue { event_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_event_revenue(deps, event_id, &address)?)
+        }
+        QueryMsg::AttendanceProof { ticket_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_attendance_proof(deps, ticket_id, &address)?)
+        }
+        QueryMsg::OfflineCode { ticket_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_offline_code(deps, ticket_id, &address)?)
+        }
+        QueryMsg::Installments { ticket_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_installments(deps, ticket_id, &address)?)
+        }
+        QueryMsg::CheckInStats { event_id, bucket, start_after, limit, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_check_in_stats(deps, event_id, bucket, start_after, limit, &address)?)
+        }
+        QueryMsg::Attendees { event_id, start_after, limit, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_attendees(deps, event_id, start_after, limit, &address)?)
+        }
+        QueryMsg::WaitlistPosition { event_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_waitlist_position(deps, event_id, &address)?)
+        }
+        QueryMsg::Waitlist { event_id, start_after, limit, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_waitlist(deps, event_id, start_after, limit, &address)?)
+        }
+        QueryMsg::CheckInReceipts { ticket_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_check_in_receipts(deps, ticket_id, &address)?)
+        }
+        QueryMsg::TicketHistory { ticket_id, address, key } => {
+            let address = check_viewing_key(deps, &address, &key)?;
+            to_binary(&query_ticket_history(deps, ticket_id, &address)?)
+        }
+        QueryMsg::VerifyCheckInReceipt { receipt } => to_binary(&query_verify_check_in_receipt(deps, receipt)?),
+        QueryMsg::VerifyAttendance { event_id, proof } => {
+            to_binary(&query_verify_attendance(deps, event_id, proof)?)
+        }
+        QueryMsg::WithPermit { permit, query } => query_with_permit(deps, env, permit, query),
     }
+}
 
-    // Get amount and address
-    let raw_amount = amount.u128();
+// Function to handle user depositing SCRT tokens for sEVNT tokens
+pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    // Check if non-negative number of tokens sent
     let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let amount = deposit_funds(deps.storage, &env, &sender_address, &info.funds)?;
+    if amount == 0 {
+        return Err(StdError::generic_err("No funds were sent to be deposited").into());
+    }
 
-    // Update balance
-    let mut balances = Balances::from_storage(deps.storage);
-    let account_balance = balances.read_account_balance(&sender_address);
-    balances.set_account_balance(&sender_address, account_balance + raw_amount);
+    // Ensure deposit is not dust
+    let config = get_config_readonly(deps.storage).load()?;
+    if amount < config.get_min_deposit() {
+        return Err(StdError::generic_err(format!(
+            "Deposit is below the minimum: minimum={}, deposited={}",
+            config.get_min_deposit(), amount
+        )).into());
+    }
 
     // Success
     return Ok(Response::default());
 }
 
-// Function to handle user withdrawing sEVNT tokens for SCRT
-pub fn try_withdraw(
-    deps: DepsMut,
-    info: MessageInfo,
-    amount: Uint128,
-) -> Result<Response, StdError> {
-    // Get sender address and amount to withdraw
-    let sender_address = deps.api.addr_canonicalize(info.sender.as_str()).unwrap();
-    let amount_raw = amount.u128();
-
-    // Get current balance
-    let mut balances = Balances::from_storage(deps.storage);
-    let account_balance = balances.read_account_balance(&sender_address);
-    // If enough available funds, update balance
-    if account_balance >= amount_raw {
-        balances.set_account_balance(&sender_address, account_balance - amount_raw);
-    } else {
-        return Err(StdError::generic_err(format!(
-            "Insufficient funds to withdraw: balance={}, required={}",
-            account_balance, amount_raw
-        )));
+// Credit native tokens sent with a message to an account as sEVNT tokens at the current exchange
+// rate, returning the amount credited
+fn deposit_funds(storage: &mut dyn Storage, env: &Env, account: &CanonicalAddr, funds: &[Coin]) -> StdResult<u128> {
+    // Reject the whole message if any unsupported tokens were sent, so none are kept unaccounted
+    let mut config = get_config(storage).load()?;
+    if let Some(coin) = funds.iter().find(|coin| !config.accepts_denom(&coin.denom)) {
+        return Err(StdError::generic_err(format!("Tried to deposit an unsupported token: {}", coin.denom)));
     }
 
-    // Get coins to withdraw
-    let withdrawal_coins: Vec<Coin> = vec![Coin {
-        denom: "uscrt".to_string(),
-        amount,
-    }];
+    // Exchange every coin sent, which may repeat a denom, adding each to its denom's reserve and
+    // recording it as funding the account
+    let exchange_rate = config.get_exchange_rate(env.block.time.seconds());
+    let mut raw_amount: u128 = 0;
+    for coin in funds {
+        let mut reserves = Reserves::from_storage(storage);
+        let reserve = reserves.read_reserve(&coin.denom)?;
+        reserves.set_reserve(&coin.denom, reserve + coin.amount.u128());
+        let tokens = exchange_rate.to_tokens(coin.amount.u128())?;
+        let mut fundings = Fundings::from_storage(storage);
+        fundings.add_funding(account, Asset::Native(coin.denom.clone()), tokens)?;
+        raw_amount += tokens;
+    }
 
-    // Create and send response
-    let response = Response::new().add_message(BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: withdrawal_coins,
-    });
-    Ok(response)
+    // Update balance and supply with the sEVNT the deposit exchanges for
+    let mut balances = Balances::from_storage(storage);
+    let account_balance = balances.read_account_balance(account)?;
+    balances.set_account_balance(account, account_balance + raw_amount);
+    config.mint(raw_amount);
+    get_config(storage).save(&config)?;
+    Ok(raw_amount)
 }
 
-pub fn try_create_event(
+// Function to handle sSCRT sent to this contract, crediting the sender with sEVNT tokens
+pub fn try_receive(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    price: Uint128,
-    max_tickets: Uint128,
-    entropy: String
-) -> Result<Response, StdError> {
-    // Get raw inputs and organiser address
-    let price_raw = price.u128();
-    let max_tickets_raw = max_tickets.u128();
-    let entropy_raw = match u128::from_str_radix(&entropy, 16) {
-        Result::Ok(number) => number,
-        Result::Err(_) => {
-            return Err(StdError::generic_err(format!("Entropy is not a valid 32 byte hex string",)));
-        }
-    };
-    let organiser = deps.api.addr_canonicalize(info.sender.as_str()).unwrap();
-
-    // Get next event ID
+    from: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure callback is from the configured sSCRT contract
     let mut config = get_config(deps.storage).load()?;
-    let event_id = config.get_next_event_id();
-    get_config(deps.storage).save(&config)?;
-
-    // Create event
-    let event = Event::new(event_id, organiser.clone(), price_raw, max_tickets_raw, entropy_raw);
-
-    // Store event in events
-    let mut events = Events::from_storage(deps.storage);
-    events.store_event(event_id, &event);
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if config.get_sscrt().map(|(sscrt, _)| sscrt) != Some(&sender) {
+        return Err(StdError::generic_err("Tried to deposit an unsupported token").into());
+    }
+    if amount.is_zero() {
+        return Err(StdError::generic_err("No funds were sent to be deposited").into());
+    }
 
-    // Store event in organisers events
-    let mut organisers_events = OrganisersEvents::from_storage(deps.storage);
-    let mut this_organisers_events = organisers_events.load_events(&organiser);
-    this_organisers_events.push(event_id);
-    organisers_events.store_events(&organiser, &this_organisers_events);
+    // Update balance of the account the tokens came from, sSCRT exchanging like uscrt
+    let raw_amount = config.get_exchange_rate(env.block.time.seconds()).to_tokens(amount.u128())?;
+    if raw_amount < config.get_min_deposit() {
+        return Err(StdError::generic_err(format!(
+            "Deposit is below the minimum: minimum={}, deposited={}",
+            config.get_min_deposit(), raw_amount
+        )).into());
+    }
+    let from_address = deps.api.addr_canonicalize(from.as_str())?;
+    config.set_sscrt_reserve(config.get_sscrt_reserve() + amount.u128());
+    let mut fundings = Fundings::from_storage(deps.storage);
+    fundings.add_funding(&from_address, Asset::Sscrt, raw_amount)?;
+    let mut balances = Balances::from_storage(deps.storage);
+    let account_balance = balances.read_account_balance(&from_address)?;
+    balances.set_account_balance(&from_address, account_balance + raw_amount);
+    config.mint(raw_amount);
+    get_config(deps.storage).save(&config)?;
 
-    // Respond with eventID
-    let response = Response::new().add_attribute("event_id", event_id.to_string());
-    Ok(response)
+    Ok(Response::default())
 }
 
-pub fn try_buy_ticket(
+// Function to handle user withdrawing sEVNT tokens for a native denom
+pub fn try_withdraw(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    event_id: Uint128,
-    entropy: String,
-    pk: String
-) -> Result<Response, StdError> {
-    // Get raw inputs and guest address
-    let event_id_raw = event_id.u128();
-    let entropy_raw = match u128::from_str_radix(&entropy, 16) {
-        Result::Ok(number) => number,
-        Result::Err(_) => {
-            return Err(StdError::generic_err(format!("Entropy is not a valid 32 byte hex string",)));
-        }
+    amount: Option<Uint128>,
+    denom: Option<String>,
+) -> Result<Response, ContractError> {
+    // Get sender address and amount to withdraw, the whole balance if not given
+    let sender_address = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let balances = ReadonlyBalances::from_storage(deps.storage);
+    let account_balance = balances.read_account_balance(&sender_address)?;
+    let amount_raw = match amount {
+        Some(amount) => amount.u128(),
+        None => account_balance,
     };
+    if amount_raw == 0 {
+        return Err(StdError::generic_err("No funds to withdraw").into());
+    }
 
-    let guest = deps.api.addr_canonicalize(info.sender.as_str()).unwrap();
+    // Ensure withdrawal is not dust, unless it empties the balance, and stays within the cap
+    // for this block
+    let now = env.block.time.seconds();
+    let mut config = get_config(deps.storage).load()?;
+    if amount_raw < config.get_min_withdrawal() && amount_raw != account_balance {
+        return Err(StdError::generic_err(format!(
+            "Withdrawal is below the minimum: minimum={}, requested={}",
+            config.get_min_withdrawal(), amount_raw
+        )).into());
+    }
+    config.record_withdrawal(env.block.height, amount_raw)?;
 
-    // Ensure event exists and is not sold out
-    let events = ReadonlyEvents::from_storage(deps.storage);
-    let mut event = match events.may_load_event(event_id_raw) {
-        Some(event) => event.clone(),
-        None => {
-            return Err(StdError::generic_err(format!("Event does not exist",)));
+    // Ensure requested denom is accepted
+    let requested = match denom {
+        Some(denom) if !config.accepts_denom(&denom) => {
+            return Err(StdError::generic_err("Tried to withdraw an unsupported token").into());
         }
+        Some(denom) => Some(Asset::Native(denom)),
+        None => None,
     };
-    if event.is_sold_out() {
-        return Err(StdError::generic_err(format!("Event is sold out",)));
+
+    // Pay out in the requested denom, or the assets the account deposited, converted at the
+    // current exchange rate
+    let fundings = ReadonlyFundings::from_storage(deps.storage);
+    let mut funding = fundings.load_funding(&sender_address)?;
+    let exchange_rate = config.get_exchange_rate(now);
+    let mut payouts: Vec<(Asset, u128)> = vec![];
+    for (asset, tokens) in allocate_withdrawal(&mut funding, amount_raw, requested, config.get_primary_denom()) {
+        let native_amount = exchange_rate.to_native(tokens)?;
+        match payouts.iter_mut().find(|(paid, _)| *paid == asset) {
+            Some((_, paid_amount)) => *paid_amount += native_amount,
+            None => payouts.push((asset, native_amount)),
+        }
+    }
+    payouts.retain(|(_, native_amount)| *native_amount > 0);
+    if payouts.is_empty() {
+        return Err(StdError::generic_err("Amount is too small to withdraw").into());
     }
 
-    // Ensure guest does not already own a ticket to this event
-    let guests_tickets = GuestsTickets::from_storage(deps.storage);
-    let this_guests_tickets = guests_tickets.load_tickets(&guest);
-    let tickets = Tickets::from_storage(deps.storage);
-    for ticket_id in this_guests_tickets {
-        let ticket = tickets.may_load_ticket(ticket_id).unwrap();
-        if ticket.get_event_id() == event_id_raw {
-            return Err(StdError::generic_err(format!("You already own a ticket to this event",)));
+    // Ensure enough of each asset is liquid, and actually held so the payout cannot bounce
+    let reserves = ReadonlyReserves::from_storage(deps.storage);
+    for (asset, native_amount) in &payouts {
+        let (name, liquid) = match asset {
+            Asset::Native(denom) => {
+                let liquid = config.liquid_reserve(denom, reserves.read_reserve(denom)?, now);
+                (denom.as_str(), liquid.min(query_held(deps.as_ref(), &env, &config, denom)?))
+            }
+            Asset::Sscrt => ("sSCRT", config.get_sscrt_reserve()),
+        };
+        if liquid < *native_amount {
+            return Err(StdError::generic_err(format!(
+                "Insufficient {} held to withdraw: available={}, required={}",
+                name, liquid, native_amount
+            )).into());
         }
     }
 
-    // Ensure guest has sufficient funds
+    // Get current balance
     let mut balances = Balances::from_storage(deps.storage);
-    let guest_balance = balances.read_account_balance(&guest);
-    let event_price = event.get_price();
-    if guest_balance < event_price {
-        return Err(StdError::generic_err(format!(
-            "Insufficient funds: balance={}, required={}",
-            guest_balance, event_price,
-        )));
+    let account_balance = balances.read_account_balance(&sender_address)?;
+    // If enough available funds, update balance
+    if account_balance >= amount_raw {
+        balances.set_account_balance(&sender_address, account_balance - amount_raw);
+    } else {
+        return Err(ContractError::InsufficientFunds { balance: account_balance, required: amount_raw });
     }
+    let mut fundings = Fundings::from_storage(deps.storage);
+    fundings.store_funding(&sender_address, &funding)?;
 
-    // Transfer funds
-    balances.set_account_balance(&guest, guest_balance - event_price);
-    let organiser_balance = balances.read_account_balance(event.get_organiser());
-    balances.set_account_balance(event.get_organiser(), organiser_balance + event_price);
-
-    // Record ticket sale in event
-    event.ticket_sold(entropy_raw);
-    let mut events = Events::from_storage(deps.storage);
-    events.store_event(event.get_id(), &event);
+    // Take payouts from reserves, native coins being sent together and sSCRT transferred
+    let mut withdrawal_coins: Vec<Coin> = vec![];
+    let mut response = Response::new();
+    for (asset, native_amount) in payouts {
+        match asset {
+            Asset::Native(denom) => {
+                let mut reserves = Reserves::from_storage(deps.storage);
+                let reserve = reserves.read_reserve(&denom)?;
+                reserves.set_reserve(&denom, reserve - native_amount);
+                withdrawal_coins.push(Coin { denom, amount: Uint128::from(native_amount) });
+            }
+            Asset::Sscrt => {
+                config.set_sscrt_reserve(config.get_sscrt_reserve() - native_amount);
+                let (sscrt, code_hash) = match config.get_sscrt() {
+                    Some(sscrt) => sscrt,
+                    None => return Err(StdError::generic_err("sSCRT funding found but sSCRT is not configured").into()),
+                };
+                let transfer_msg = Snip20Msg::Transfer {
+                    recipient: info.sender.to_string(),
+                    amount: Uint128::from(native_amount),
+                    padding: None,
+                };
+                response = response.add_message(WasmMsg::Execute {
+                    contract_addr: deps.api.addr_humanize(sscrt)?.into_string(),
+                    code_hash: code_hash.clone(),
+                    msg: to_binary(&transfer_msg)?,
+                    funds: vec![],
+                });
+            }
+        }
+    }
+    if !withdrawal_coins.is_empty() {
+        withdrawal_coins.sort_by(|a, b| a.denom.cmp(&b.denom));
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: withdrawal_coins,
+        });
+    }
 
-    // Get next ticket id
-    let mut config = get_config(deps.storage).load()?;
-    let ticket_id = config.get_next_ticket_id();
+    // Remove withdrawn tokens from supply
+    config.burn(amount_raw);
     get_config(deps.storage).save(&config)?;
 
-    // Create ticket
-    let secret = event.generate_secret(u128::u128::from_built_in(ticket_id));
-    let ticket = Ticket::new(ticket_id, event_id_raw, guest.clone(), secret, pk);
-
-    // Store ticket in tickets
-    let mut tickets = Tickets::from_storage(deps.storage);
-    tickets.store_ticket(ticket_id, &ticket);
-
-    // Store event in guests tickets
-    let mut guests_tickets = GuestsTickets::from_storage(deps.storage);
-    let mut this_guests_tickets = guests_tickets.load_tickets(&guest);
-    this_guests_tickets.push(ticket_id);
-    guests_tickets.store_tickets(&guest, &this_guests_tickets);
-
-    // Respond with ticketID
-    let response = Response::new().add_attribute("ticket_id", ticket_id.to_string());
     Ok(response)
 }
 
-pub fn try_verify_ticket(
-    deps: DepsMut,
-    info: MessageInfo,
-    ticket_id: Uint128,
-) -> Result<Response, StdError> {
-    // Get raw inputs and 'organiser' address
-    let ticket_id_raw = ticket_id.u128();
-    let organiser = deps.api.addr_canonicalize(info.sender.as_str()).unwrap();
-
-    // Ensure ticket exists and load it
-    let tickets = ReadonlyTickets::from_storage(deps.storage);
-    let mut ticket = match tickets.may_load_ticket(ticket_id_raw) {
-        Some(ticket) => ticket.clone(),
-        None => {
-            return Err(StdError::generic_err(format!("Ticket does not exist")));
+// Split a withdrawal among the assets an account deposited, oldest first, or only the requested
+// asset if given, removing them from its funding. Any amount not covered by funding, such as
+// tokens received from others, is paid in the requested asset or else the primary denom
+fn allocate_withdrawal(
+    funding: &mut Vec<(Asset, u128)>,
+    amount: u128,
+    requested: Option<Asset>,
+    primary_denom: &str,
+) -> Vec<(Asset, u128)> {
+    let mut allocation = vec![];
+    let mut remaining = amount;
+    for (asset, funded) in funding.iter_mut() {
+        if remaining == 0 {
+            break;
         }
-    };
-
-    // Ensure ticket is not used
-    if ticket.get_state() == 2 {
-        return Err(StdError::generic_err(format!(
-            "Ticket has already been used"
-        )));
+        if matches!(&requested, Some(requested) if requested != asset) {
+            continue;
+        }
+        let taken = remaining.min(*funded);
+        *funded -= taken;
+        remaining -= taken;
+        allocation.push((asset.clone(), taken));
     }
-
-    // Check message sender is organiser of event
-    let events = ReadonlyEvents::from_storage(deps.storage);
-    let event = events.may_load_event(ticket.get_event_id()).unwrap();
-    if *event.get_organiser() != organiser {
-        return Err(StdError::generic_err(format!(
-            "You are not the organiser of this event"
-        )));
+    funding.retain(|(_, funded)| *funded > 0);
+    if remaining > 0 {
+        allocation.push((requested.unwrap_or_else(|| Asset::Native(primary_denom.to_string())), remaining));
     }
+    allocation
+}
 
-    // Generate secret and set ticket status to validating
-    let secret = ticket.start_validation();
-    let pk = ticket.get_pk();
-    let mut tickets = Tickets::from_storage(deps.storage);
-    tickets.store_ticket(ticket_id_raw, &ticket);
+// Function to handle SNIP-20 transfer of sEVNT tokens
+pub fn try_transfer(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let recipient = deps.api.addr_canonicalize(deps.api.addr_validate(&recipient)?.as_str())?;
+    transfer_balance(deps.storage, &sender, &recipient, amount.u128())?;
+    Ok(Response::default())
+}
 
-    // Encrypt with public key of guest
-    let mut rng = ChaChaRng::from_seed(event.get_seed());
-    let public_key = RsaPublicKey::from_public_key_pem(&pk).unwrap();
-    let padding = PaddingScheme::new_pkcs1v15_encrypt();
-    let secret_encrypted = public_key.encrypt(&mut rng, padding, &secret.to_be_bytes()).unwrap();
+// Function to handle SNIP-20 send of sEVNT tokens, notifying a receiving contract
+pub fn try_send(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipient: String,
+    recipient_code_hash: Option<String>,
+    amount: Uint128,
+    msg: Option<Binary>,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let recipient_canon = deps.api.addr_canonicalize(recipient.as_str())?;
+    transfer_balance(deps.storage, &sender, &recipient_canon, amount.u128())?;
 
-    // Respond with encrypted secret
-    let response = Response::new().add_attribute("secret_encrypted", hex::encode(secret_encrypted));
+    // Call the recipient's Receive if it is a contract that registered or whose code hash was given
+    let receivers = ReadonlyReceivers::from_storage(deps.storage);
+    let code_hash = match recipient_code_hash {
+        Some(code_hash) => Some(code_hash),
+        None => receivers.may_load_code_hash(&recipient_canon)?,
+    };
+    let mut response = Response::new();
+    if let Some(code_hash) = code_hash {
+        let receive_msg = ReceiverHandleMsg::Receive {
+            sender: info.sender.clone(),
+            from: info.sender,
+            amount,
+            memo,
+            msg,
+        };
+        response = response.add_message(WasmMsg::Execute {
+            contract_addr: recipient.into_string(),
+            code_hash,
+            msg: to_binary(&receive_msg)?,
+            funds: vec![],
+        });
+    }
     Ok(response)
 }
 
-pub fn try_verify_guest(
+// Function to handle increasing or decreasing a spender's allowance of the sender's sEVNT
+pub fn try_change_allowance(
     deps: DepsMut,
     info: MessageInfo,
-    ticket_id: Uint128,
-    secret: String,
-) -> Result<Response, StdError> {
-    // Get raw inputs and 'organiser' address
-    let ticket_id_raw = ticket_id.u128();
-    let secret_raw = match u64::from_str_radix(&secret, 16) {
-        Result::Ok(number) => number,
-        Result::Err(_) => {
-            return Err(StdError::generic_err(format!("Secret is not a valid 16 byte hex string",)));
-        }
-    };
-    let organiser = deps.api.addr_canonicalize(info.sender.as_str()).unwrap();
+    spender: String,
+    amount: Uint128,
+    expiration: Option<u64>,
+    increase: bool,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let spender = deps.api.addr_validate(&spender)?;
+    let spender_canon = deps.api.addr_canonicalize(spender.as_str())?;
 
-    // Ensure ticket exists and load it
-    let tickets = ReadonlyTickets::from_storage(deps.storage);
-    let mut ticket = match tickets.may_load_ticket(ticket_id_raw) {
-        Some(ticket) => ticket.clone(),
-        None => {
-            return Err(StdError::generic_err(format!("Ticket does not exist")));
-        }
+    // Adjust the allowance, saturating so it never underflows, and replace its expiry if given
+    let mut allowances = Allowances::from_storage(deps.storage);
+    let allowance = allowances.load_allowance(&owner, &spender_canon)?;
+    let new_amount = if increase {
+        allowance.get_amount().saturating_add(amount.u128())
+    } else {
+        allowance.get_amount().saturating_sub(amount.u128())
     };
+    let allowance = Allowance::new(new_amount, expiration.or(allowance.get_expiration()));
+    allowances.store_allowance(&owner, &spender_canon, &allowance)?;
+    AllowanceIndexes::from_storage(deps.storage).add_allowance(&owner, &spender_canon)?;
 
-    // Ensure ticket is in validating state
-    match ticket.get_state() {
-        0 => {
-            return Err(StdError::generic_err(format!(
-                "Validation of ticket not initiated yet"
-            )))
+    let data = AllowanceResponse {
+        owner: info.sender,
+        spender,
+        allowance: Uint128::from(allowance.get_amount()),
+        expiration: allowance.get_expiration(),
+    };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+// Function to handle a spender transferring an owner's sEVNT within their allowance
+pub fn try_transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let spender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let owner = deps.api.addr_canonicalize(deps.api.addr_validate(&owner)?.as_str())?;
+    let recipient = deps.api.addr_canonicalize(deps.api.addr_validate(&recipient)?.as_str())?;
+
+    // Spend from the allowance, then move the tokens
+    let mut allowances = Allowances::from_storage(deps.storage);
+    let mut allowance = allowances.load_allowance(&owner, &spender)?;
+    allowance.spend(amount.u128(), env.block.time.seconds())?;
+    allowances.store_allowance(&owner, &spender, &allowance)?;
+    transfer_balance(deps.storage, &owner, &recipient, amount.u128())?;
+
+    Ok(Response::default())
+}
+
+// Function to handle a contract registering its code hash to receive Send callbacks
+pub fn try_register_receive(deps: DepsMut, info: MessageInfo, code_hash: String) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut receivers = Receivers::from_storage(deps.storage);
+    receivers.set_code_hash(&contract, &code_hash);
+    Ok(Response::default())
+}
+
+// Move sEVNT tokens between accounts
+fn transfer_balance(storage: &mut dyn Storage, from: &CanonicalAddr, to: &CanonicalAddr, amount: u128) -> Result<(), ContractError> {
+    let mut balances = Balances::from_storage(storage);
+    let from_balance = balances.read_account_balance(from)?;
+    if from_balance < amount {
+        return Err(ContractError::InsufficientFunds { balance: from_balance, required: amount });
+    }
+    balances.set_account_balance(from, from_balance - amount);
+    let to_balance = balances.read_account_balance(to)?;
+    balances.set_account_balance(to, to_balance + amount);
+    Ok(())
+}
+
+pub fn try_create_viewing_key(deps: DepsMut, info: MessageInfo, entropy: String) -> Result<Response, ContractError> {
+    // Derive key from the contract seed, folding in the sender's entropy
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    config.add_entropy(entropy.as_bytes());
+    get_config(deps.storage).save(&config)?;
+    let key = format!(
+        "{}{}",
+        VIEWING_KEY_PREFIX,
+        Binary::from(config.generate_viewing_key(&sender, entropy.as_bytes())).to_base64()
+    );
+
+    // Store key and return it in the encrypted response data
+    let mut viewing_keys = ViewingKeys::from_storage(deps.storage);
+    viewing_keys.set_viewing_key(&sender, &key);
+    Ok(Response::new().set_data(to_binary(&ViewingKeyResponse { key })?))
+}
+
+pub fn try_set_viewing_key(deps: DepsMut, info: MessageInfo, key: String) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut viewing_keys = ViewingKeys::from_storage(deps.storage);
+    viewing_keys.set_viewing_key(&sender, &key);
+    Ok(Response::new().set_data(to_binary(&ViewingKeyResponse { key })?))
+}
+
+pub fn try_revoke_permit(deps: DepsMut, info: MessageInfo, permit_name: String) -> Result<Response, ContractError> {
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut revoked_permits = RevokedPermits::from_storage(deps.storage);
+    revoked_permits.revoke(&sender, &permit_name);
+    Ok(Response::default())
+}
+
+pub fn try_register_organiser(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // Check if valid denomination tokens sent
+    let mut config = get_config(deps.storage).load()?;
+    let mut amount = Uint128::zero();
+    for coin in info.funds {
+        if coin.denom == config.get_primary_denom() {
+            amount += coin.amount;
+        } else {
+            return Err(StdError::generic_err(format!("Tried to bond an unsupported token: {}", coin.denom)).into());
         }
-        1 => (),
-        2 => {
-            return Err(StdError::generic_err(format!(
-                "Ticket has already been used"
-            )))
+    }
+
+    // Ensure not already registered and bond is sufficient
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut organisers = Organisers::from_storage(deps.storage);
+    if organisers.may_load_organiser(&organiser)?.is_some() {
+        return Err(StdError::generic_err("You are already registered as an organiser").into());
+    }
+    if amount.u128() < config.get_organiser_bond() {
+        return Err(StdError::generic_err(format!(
+            "Insufficient bond: sent={}, required={}",
+            amount, config.get_organiser_bond(),
+        )).into());
+    }
+
+    // Hold bond and register organiser
+    organisers.store_organiser(&organiser, &Organiser::new(amount.u128()))?;
+    config.add_bond(amount.u128());
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_unregister_organiser(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let organisers = ReadonlyOrganisers::from_storage(deps.storage);
+    let bond = match organisers.may_load_organiser(&organiser)? {
+        Some(registered) if registered.is_slashed() => {
+            return Err(ContractError::Unauthorized { reason: "Your bond has been slashed" });
         }
-        _ => {
-            return Err(StdError::generic_err(format!(
-                "Ticket is somehow in invalid state"
-            )))
+        Some(registered) => registered.get_bond(),
+        None => return Err(ContractError::Unauthorized { reason: "You are not registered as an organiser" }),
+    };
+
+    // Ensure all organisers events have completed and been paid out
+    let dispute_window = get_config_readonly(deps.storage).load()?.get_dispute_window();
+    let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.storage);
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    for event_id in organisers_events.load_events(&organiser)? {
+        let event = events.load_event(event_id)?;
+        if event.check_payout_open(env.block.time.seconds(), dispute_window).is_err() || event.get_escrow() > 0 {
+            return Err(StdError::generic_err(format!("Event has not settled: {}", event_id)).into());
+        }
+    }
+
+    // Unregister organiser and return bond
+    let mut config = get_config(deps.storage).load()?;
+    let held = deps.querier.query_balance(&env.contract.address, config.get_primary_denom())?.amount.u128();
+    if held < bond {
+        return Err(StdError::generic_err(format!(
+            "Contract holds insufficient {} to return the bond: held={}, required={}",
+            config.get_primary_denom(), held, bond
+        )).into());
+    }
+    let mut organisers = Organisers::from_storage(deps.storage);
+    organisers.remove_organiser(&organiser);
+    config.remove_bond(bond);
+    get_config(deps.storage).save(&config)?;
+    let mut response = Response::default();
+    if bond > 0 {
+        response = response.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: config.get_primary_denom().to_string(),
+                amount: Uint128::from(bond),
+            }],
+        });
+    }
+    Ok(response)
+}
+
+pub fn try_set_organiser_profile(
+    deps: DepsMut,
+    info: MessageInfo,
+    display_name: String,
+    contact_uri: String,
+    payout_address: Option<String>,
+) -> Result<Response, ContractError> {
+    // Ensure sender is a registered organiser
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let organisers = ReadonlyOrganisers::from_storage(deps.storage);
+    if organisers.may_load_organiser(&organiser)?.is_none() {
+        return Err(ContractError::Unauthorized { reason: "You are not registered as an organiser" });
+    }
+
+    let payout_address = match payout_address {
+        Some(address) => Some(deps.api.addr_canonicalize(deps.api.addr_validate(&address)?.as_str())?),
+        None => None,
+    };
+    let profile = OrganiserProfile::new(display_name, contact_uri, payout_address)?;
+    let mut profiles = OrganiserProfiles::from_storage(deps.storage);
+    profiles.store_profile(&organiser, &profile)?;
+
+    Ok(Response::default())
+}
+
+// Amount of a native denom the contract's bank balance holds for depositors, which excludes
+// organiser bonds
+fn query_held(deps: Deps, env: &Env, config: &Config, denom: &str) -> StdResult<u128> {
+    let balance = deps.querier.query_balance(&env.contract.address, denom)?.amount.u128();
+    match denom == config.get_primary_denom() {
+        true => Ok(balance.saturating_sub(config.get_bonds())),
+        false => Ok(balance),
+    }
+}
+
+pub fn try_create_event(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: CreateEventMsg
+) -> Result<Response, ContractError> {
+    // Get raw inputs and organiser address
+    let price_raw = msg.price.u128();
+    let max_tickets_raw = msg.max_tickets.u128();
+    let entropy_raw = parse_entropy(&msg.entropy)?;
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure organiser is registered and has not been slashed
+    let organisers = ReadonlyOrganisers::from_storage(deps.storage);
+    match organisers.may_load_organiser(&organiser)? {
+        Some(registered) if registered.is_slashed() => {
+            return Err(ContractError::Unauthorized { reason: "Your bond has been slashed, you cannot create events" });
+        }
+        Some(_) => (),
+        None => {
+            return Err(ContractError::Unauthorized { reason: "You must register as an organiser before creating events" });
+        }
+    }
+
+    // Get next event ID, folding organiser's entropy into the contract seed
+    let mut config = get_config(deps.storage).load()?;
+    let stable_price = msg.stable_price.unwrap_or(false);
+    if stable_price && config.get_oracle().is_none() {
+        return Err(StdError::generic_err("No price oracle is configured for stable priced events").into());
+    }
+    let event_id = config.get_next_event_id();
+    config.add_entropy(&entropy_raw);
+    get_config(deps.storage).save(&config)?;
+
+    // Create event, seeded from the contract seed and organiser's entropy
+    let mut event = Event::new(event_id, organiser.clone(), price_raw, max_tickets_raw);
+    event.set_seed(derive_event_seed(config.get_prng_seed(), &entropy_raw, event_id));
+    event.set_details(msg.name.unwrap_or_default(), msg.venue.unwrap_or_default(), msg.tags.unwrap_or_default())?;
+    if let Some(tiers) = msg.tiers {
+        event.set_tiers(
+            tiers
+                .into_iter()
+                .map(|tier| {
+                    let mut new_tier = Tier::new(tier.name, tier.price.u128(), tier.max_tickets.u128());
+                    new_tier.set_schedule(price_steps(tier.schedule.unwrap_or_default()));
+                    new_tier
+                })
+                .collect(),
+        )?;
+    } else {
+        if let Some(schedule) = msg.price_schedule {
+            event.set_schedule(0, price_steps(schedule));
+        }
+        if let Some(auction) = msg.dutch_auction {
+            event.set_auction(0, DutchAuction::new(
+                auction.start_price.u128(),
+                auction.floor_price.u128(),
+                auction.start_height,
+                auction.decay_per_block.u128(),
+            )?);
+        }
+    }
+    if let Some(seats) = msg.seats {
+        event.set_seats(seats)?;
+    }
+    if let Some(max_per_guest) = msg.max_per_guest {
+        event.set_max_per_guest(max_per_guest.u128())?;
+    }
+    if let Some(max_resale_bps) = msg.max_resale_bps {
+        event.set_max_resale_bps(max_resale_bps);
+    }
+    if let Some(royalty_bps) = msg.royalty_bps {
+        event.set_royalty_bps(royalty_bps)?;
+    }
+    event.set_sale_window(msg.sale_start, msg.sale_end)?;
+    event.set_presale_start(msg.presale_start)?;
+    event.set_event_times(msg.start_time, msg.end_time, msg.checkin_window.unwrap_or(0))?;
+    event.set_challenge_ttl(msg.challenge_ttl.unwrap_or(DEFAULT_CHALLENGE_TTL))?;
+    if let Some(payees) = msg.payees {
+        let payees = payees
+            .into_iter()
+            .map(|payee| {
+                let address = deps.api.addr_validate(&payee.address)?;
+                Ok(Payee::new(deps.api.addr_canonicalize(address.as_str())?, payee.bps))
+            })
+            .collect::<StdResult<_>>()?;
+        event.set_payees(payees)?;
+    }
+    if let Some(sessions) = msg.sessions {
+        event.set_sessions(sessions.into_iter().map(|session| Session::new(session.start, session.end)).collect())?;
+    }
+    event.set_refund_deadline(msg.refund_deadline);
+    event.set_payout_delay(msg.payout_delay.unwrap_or(0));
+    event.set_insured(msg.insured.unwrap_or(false));
+    event.set_two_factor(msg.two_factor.unwrap_or(false));
+    event.set_share_guests(msg.share_guests.unwrap_or(false));
+    event.set_stable_price(stable_price);
+    if let Some(plan) = msg.installment_plan {
+        event.set_installment_plan(Some(InstallmentPlan::new(plan.down_payment_bps, plan.installments, plan.interval)?));
+    }
+    if let Some(invite_key) = msg.invite_key {
+        match hex::decode(&invite_key) {
+            Ok(invite_key) => event.set_invite_key(invite_key)?,
+            Err(_) => return Err(StdError::generic_err("Invite key is not a valid hex string").into()),
+        }
+    }
+
+    // Store event in events
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event_id, &event)?;
+
+    // Store event in organisers events
+    let mut organisers_events = OrganisersEvents::from_storage(deps.storage);
+    organisers_events.add_event(&organiser, event_id)?;
+
+    // Respond with eventID in the encrypted response data
+    let data = CreateEventResponse { event_id: Uint128::from(event_id) };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+pub fn try_buy_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: BuyTicketMsg
+) -> Result<Response, ContractError> {
+    let order = PurchaseOrder {
+        event_id: msg.event_id.u128(),
+        entropy: msg.entropy,
+        tier: msg.tier,
+        promo_code: msg.promo_code,
+        invite: msg.invite,
+        holders: vec![(parse_guest_key(&msg.pk, msg.pk_type)?, msg.seat)],
+        installments: msg.installments.unwrap_or(false),
+        tip: msg.tip.map_or(0, |tip| tip.u128()),
+    };
+    let ticket_ids = purchase_tickets(deps, env, info, order)?;
+    let data = TicketIdResponse { ticket_id: ticket_ids[0] };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+pub fn try_buy_tickets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: BuyTicketsMsg
+) -> Result<Response, ContractError> {
+    // Ensure a public key was given for every ticket
+    let quantity = msg.quantity.u128();
+    if quantity == 0 {
+        return Err(StdError::generic_err("Quantity must be at least 1").into());
+    }
+    if msg.pks.len() as u128 != quantity {
+        return Err(StdError::generic_err(format!(
+            "Expected one public key per ticket: quantity={}, keys={}",
+            quantity, msg.pks.len()
+        )).into());
+    }
+
+    // Ensure a seat was given for every ticket, if any
+    let seats = match msg.seats {
+        Some(seats) if seats.len() != msg.pks.len() => {
+            return Err(StdError::generic_err("Expected one seat per ticket").into());
         }
+        Some(seats) => seats.into_iter().map(Some).collect(),
+        None => vec![None; msg.pks.len()],
     };
 
-    // Check message sender is organiser of event
+    let pks = msg
+        .pks
+        .iter()
+        .map(|pk| parse_guest_key(pk, msg.pk_type))
+        .collect::<StdResult<Vec<GuestKey>>>()?;
+
+    let order = PurchaseOrder {
+        event_id: msg.event_id.u128(),
+        entropy: msg.entropy,
+        tier: msg.tier,
+        promo_code: msg.promo_code,
+        invite: msg.invite,
+        holders: pks.into_iter().zip(seats).collect(),
+        installments: false,
+        tip: 0,
+    };
+    let ticket_ids = purchase_tickets(deps, env, info, order)?;
+    Ok(Response::new().set_data(to_binary(&TicketIdsResponse { ticket_ids })?))
+}
+
+// Tickets to be bought in a single transaction
+struct PurchaseOrder {
+    event_id: u128,
+    entropy: String,
+    tier: Option<String>,
+    promo_code: Option<String>,
+    invite: Option<String>,
+    // Public key and requested seat of each ticket
+    holders: Vec<(GuestKey, Option<String>)>,
+    // Reserve the tickets under the event's installment plan
+    installments: bool,
+    // Paid to the organiser on top of the price
+    tip: u128,
+}
+
+fn purchase_tickets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order: PurchaseOrder
+) -> Result<Vec<Uint128>, ContractError> {
+    // Get raw inputs and guest address
+    let event_id_raw = order.event_id;
+    let quantity = order.holders.len() as u128;
+    let entropy_raw = parse_entropy(&order.entropy)?;
+
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Deposit any SCRT sent with the purchase, any excess remains in the guest's balance
+    deposit_funds(deps.storage, &env, &guest, &info.funds)?;
+
+    // Ensure event exists and is not sold out
     let events = ReadonlyEvents::from_storage(deps.storage);
-    let event = events.may_load_event(ticket.get_event_id()).unwrap();
-    if *event.get_organiser() != organiser {
+    let mut event = match events.may_load_event(event_id_raw)? {
+        Some(event) => event.clone(),
+        None => {
+            return Err(ContractError::EventNotFound {});
+        }
+    };
+    if event.is_sold_out() {
+        return Err(ContractError::SoldOut { item: "Event" });
+    }
+
+    // Ensure event offers installments if the guest asked to pay in them
+    let installment_plan = match (order.installments, event.get_installment_plan()) {
+        (false, _) => None,
+        (true, Some(plan)) => Some(plan.clone()),
+        (true, None) => return Err(StdError::generic_err("This event does not offer installments").into()),
+    };
+
+    // Ensure guest is not banned from event
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event_id_raw, &guest) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+
+    // Ensure guest holds a valid invite for invite-only events
+    if let Some(invite_key) = event.get_invite_key() {
+        let signature = match order.invite.as_deref().map(hex::decode) {
+            Some(Ok(signature)) => signature,
+            Some(Err(_)) => return Err(StdError::generic_err("Invite is not a valid hex string").into()),
+            None => return Err(StdError::generic_err("An invite is required for this event").into()),
+        };
+        let message_hash = invite_hash(event_id_raw, info.sender.as_str());
+        if !deps.api.secp256k1_verify(&message_hash, &signature, invite_key).unwrap_or(false) {
+            return Err(StdError::generic_err("Invite is not valid for this event").into());
+        }
+    }
+
+    // Ensure tier exists and has enough tickets left
+    let tier = event.find_tier(order.tier.as_deref())?;
+    if event.get_tier(tier).is_sold_out() {
+        return Err(ContractError::SoldOut { item: "Tier" });
+    }
+    if event.get_tier(tier).get_tickets_left() < quantity {
         return Err(StdError::generic_err(format!(
-            "You are not the organiser of this event"
-        )));
+            "Not enough tickets left: left={}, requested={}",
+            event.get_tier(tier).get_tickets_left(), quantity
+        )).into());
+    }
+
+    // Ensure ticket sales are open, or guest is allowlisted for the presale
+    let allowlists = ReadonlyAllowlists::from_storage(deps.storage);
+    let allowlisted = allowlists.load_allowlist(event_id_raw)?.contains(&guest);
+    event.check_sale_open(env.block.time.seconds(), allowlisted)?;
+
+    // Ensure requested seats exist and are free
+    let seats: Vec<Option<String>> = order.holders.iter().map(|(_, seat)| seat.clone()).collect();
+    check_seats_free(deps.storage, &event, &seats)?;
+
+    // Ensure guest will not exceed the ticket limit for this event
+    let owned = count_guests_event_tickets(deps.storage, &guest, event_id_raw)?;
+    if owned + quantity > event.get_max_per_guest() {
+        return Err(ContractError::TicketLimitExceeded { max: event.get_max_per_guest() });
+    }
+
+    // Price each ticket
+    let mut prices = price_tickets(deps.as_ref(), &env, &event, tier, quantity)?;
+
+    // Apply promo code, if any
+    if let Some(code) = &order.promo_code {
+        let code_hash: [u8; 32] = Sha256::digest(code.as_bytes()).into();
+        let mut promo_codes = PromoCodes::from_storage(deps.storage);
+        let mut promo_code = match promo_codes.may_load_promo_code(event_id_raw, &code_hash)? {
+            Some(promo_code) => promo_code,
+            None => return Err(StdError::generic_err("Promo code is not valid for this event").into()),
+        };
+        promo_code.redeem(quantity)?;
+        promo_codes.store_promo_code(event_id_raw, &code_hash, &promo_code)?;
+        prices = prices.into_iter().map(|price| promo_code.apply(price)).collect();
+    }
+
+    // Ensure guest has sufficient funds, including any insurance premium and tip. Only the down
+    // payment is due now on tickets paid in installments
+    let charges: Vec<u128> = match &installment_plan {
+        Some(plan) => prices.iter().map(|price| plan.down_payment(*price)).collect::<StdResult<_>>()?,
+        None => prices.clone(),
+    };
+    let total_price = match charges.iter().try_fold(0u128, |total, charge| total.checked_add(*charge)) {
+        Some(total_price) => total_price,
+        None => return Err(StdError::generic_err("Total price overflows").into()),
+    };
+    let premium = match prices.iter().try_fold(0u128, |total, price| total.checked_add(event.insurance_premium(*price))) {
+        Some(premium) => premium,
+        None => return Err(StdError::generic_err("Total price overflows").into()),
+    };
+    let total_cost = match total_price.checked_add(premium).and_then(|total| total.checked_add(order.tip)) {
+        Some(total_cost) => total_cost,
+        None => return Err(StdError::generic_err("Total price overflows").into()),
+    };
+    let mut balances = Balances::from_storage(deps.storage);
+    let guest_balance = balances.read_account_balance(&guest)?;
+    if guest_balance < total_cost {
+        return Err(ContractError::InsufficientFunds { balance: guest_balance, required: total_cost });
+    }
+
+    // Transfer funds into the event's escrow, premium into the insurance pool and tip to the
+    // organiser
+    balances.set_account_balance(&guest, guest_balance - total_cost);
+    let organiser_balance = balances.read_account_balance(event.get_organiser())?;
+    balances.set_account_balance(event.get_organiser(), organiser_balance + order.tip);
+    event.add_escrow(total_price);
+    event.add_revenue(total_price);
+    event.add_tip(order.tip);
+    let mut config = get_config(deps.storage).load()?;
+    config.add_insurance_premium(premium);
+    config.add_entropy(&entropy_raw);
+    get_config(deps.storage).save(&config)?;
+
+    let mut ticket_ids = vec![];
+    for (holder, price) in order.holders.into_iter().zip(prices) {
+        // Record ticket sale in event and mint ticket
+        event.ticket_sold(tier);
+        let ticket_id = mint_ticket(deps.storage, &event, tier, &guest, holder, price, &entropy_raw)?;
+        record_ticket_action(deps.storage, &env, ticket_id, ACTION_PURCHASED)?;
+        if let Some(plan) = &installment_plan {
+            let mut tickets = Tickets::from_storage(deps.storage);
+            let mut ticket = tickets.load_ticket(ticket_id)?;
+            ticket.reserve(plan, env.block.time.seconds())?;
+            tickets.store_ticket(ticket_id, &ticket)?;
+        }
+        ticket_ids.push(Uint128::from(ticket_id));
+    }
+
+    // Store updated event
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    // Return ticketIDs
+    Ok(ticket_ids)
+}
+
+// Price of each of the next tickets sold in a tier according to its schedule, in sEVNT
+fn price_tickets(deps: Deps, env: &Env, event: &Event, tier: usize, quantity: u128) -> StdResult<Vec<u128>> {
+    let now = env.block.time.seconds();
+    let height = env.block.height;
+    let sold = event.get_tier(tier).get_tickets_sold();
+    let prices: Vec<u128> = (0..quantity)
+        .map(|i| event.get_tier(tier).price_at(sold + i, now, height))
+        .collect();
+
+    // Convert stable prices from USD cents to sEVNT at the oracle's current rate
+    if !event.is_stable_price() {
+        return Ok(prices);
+    }
+    let config = get_config_readonly(deps.storage).load()?;
+    let scrt_usd = query_scrt_usd_rate(deps, &config, now)?;
+    let exchange_rate = config.get_exchange_rate(now);
+    prices
+        .into_iter()
+        .map(|cents| exchange_rate.to_tokens(cents_to_uscrt(cents, scrt_usd)?))
+        .collect()
+}
+
+pub fn try_issue_comp_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: IssueCompTicketMsg,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and addresses
+    let entropy_raw = parse_entropy(&msg.entropy)?;
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let recipient = deps.api.addr_canonicalize(msg.recipient.as_str())?;
+    let mut event = load_managed_event(deps.as_ref(), msg.event_id.u128(), &organiser)?;
+
+    // Ensure recipient is not banned from event
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), &recipient) {
+        return Err(ContractError::Banned { who: "Recipient" });
+    }
+
+    // Ensure tier has a ticket left and seat is free
+    let tier = event.find_tier(msg.tier.as_deref())?;
+    if event.get_tier(tier).is_sold_out() {
+        return Err(ContractError::SoldOut { item: "Tier" });
+    }
+    check_seats_free(deps.storage, &event, std::slice::from_ref(&msg.seat))?;
+
+    // Record ticket in event and mint it free of charge
+    let mut config = get_config(deps.storage).load()?;
+    config.add_entropy(&entropy_raw);
+    get_config(deps.storage).save(&config)?;
+    event.ticket_sold(tier);
+    let pk = parse_guest_key(&msg.pk, msg.pk_type)?;
+    let ticket_id = mint_ticket(deps.storage, &event, tier, &recipient, (pk, msg.seat), 0, &entropy_raw)?;
+    record_ticket_action(deps.storage, &env, ticket_id, ACTION_PURCHASED)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    let data = TicketIdResponse { ticket_id: Uint128::from(ticket_id) };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+// Price of one SCRT in USD from the configured oracle, scaled by 10^18
+fn query_scrt_usd_rate(deps: Deps, config: &Config, now: u64) -> StdResult<u128> {
+    let (oracle, code_hash) = match config.get_oracle() {
+        Some(oracle) => oracle,
+        None => return Err(StdError::generic_err("No price oracle is configured")),
+    };
+    let data: ReferenceData = deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: deps.api.addr_humanize(oracle)?.into_string(),
+        code_hash: code_hash.clone(),
+        msg: to_binary(&OracleQueryMsg::GetReferenceData {
+            base_symbol: ORACLE_BASE_SYMBOL.to_string(),
+            quote_symbol: ORACLE_QUOTE_SYMBOL.to_string(),
+        })?,
+    }))?;
+
+    // Ensure rate is usable and recent
+    if data.rate.is_zero() {
+        return Err(StdError::generic_err("Price oracle returned a zero rate"));
+    }
+    if data.last_updated_base.saturating_add(MAX_ORACLE_AGE) < now {
+        return Err(StdError::generic_err("Price oracle rate is stale"));
+    }
+    Ok(data.rate.u128())
+}
+
+// Convert USD cents to uscrt at a rate of USD per SCRT scaled by 10^18, rounding down
+fn cents_to_uscrt(cents: u128, scrt_usd: u128) -> StdResult<u128> {
+    // uscrt = cents / 100 * 10^6 * 10^18 / rate
+    cents
+        .checked_mul(10u128.pow(22))
+        .map(|scaled| scaled / scrt_usd)
+        .ok_or_else(|| StdError::generic_err("Price is too large to convert"))
+}
+
+pub fn try_create_pass(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: CreatePassMsg,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure organiser is registered and has not been slashed
+    let organisers = ReadonlyOrganisers::from_storage(deps.storage);
+    match organisers.may_load_organiser(&organiser)? {
+        Some(registered) if !registered.is_slashed() => (),
+        _ => return Err(StdError::generic_err("You must be a registered organiser to create passes").into()),
+    }
+
+    // Create pass covering either the listed events, which must be the organiser's, or a period
+    let mut config = get_config(deps.storage).load()?;
+    let pass_id = config.get_next_pass_id();
+    get_config(deps.storage).save(&config)?;
+    let mut pass = Pass::new(pass_id, organiser.clone(), msg.price.u128(), msg.max_passes.u128());
+    match (msg.event_ids, msg.period_start, msg.period_end) {
+        (Some(event_ids), None, None) => {
+            for event_id in &event_ids {
+                load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+            }
+            pass.set_event_ids(event_ids.iter().map(|event_id| event_id.u128()).collect())?;
+        }
+        (None, Some(start), Some(end)) => pass.set_period(start, end)?,
+        _ => return Err(StdError::generic_err("A pass must cover either a list of events or a period").into()),
+    }
+
+    // Store pass
+    let mut passes = Passes::from_storage(deps.storage);
+    passes.store_pass(pass_id, &pass)?;
+
+    let data = PassIdResponse { pass_id: Uint128::from(pass_id) };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+pub fn try_buy_pass(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pass_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Deposit any SCRT sent with the purchase
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+    deposit_funds(deps.storage, &env, &guest, &info.funds)?;
+
+    // Ensure pass exists, is not sold out and is not already held
+    let passes = ReadonlyPasses::from_storage(deps.storage);
+    let mut pass = match passes.may_load_pass(pass_id.u128())? {
+        Some(pass) => pass,
+        None => return Err(ContractError::PassNotFound {}),
+    };
+    if pass.is_sold_out() {
+        return Err(ContractError::SoldOut { item: "Pass" });
+    }
+    let guests_passes = ReadonlyGuestsPasses::from_storage(deps.storage);
+    let mut this_guests_passes = guests_passes.load_passes(&guest)?;
+    if this_guests_passes.iter().any(|holding| holding.get_pass_id() == pass.get_id()) {
+        return Err(StdError::generic_err("You already hold this pass").into());
+    }
+
+    // Ensure guest has sufficient funds
+    let price = pass.get_price();
+    let balances = ReadonlyBalances::from_storage(deps.storage);
+    let guest_balance = balances.read_account_balance(&guest)?;
+    if guest_balance < price {
+        return Err(ContractError::InsufficientFunds { balance: guest_balance, required: price });
+    }
+
+    // Record sale and give guest the pass
+    pass.pass_sold();
+    let mut passes = Passes::from_storage(deps.storage);
+    passes.store_pass(pass.get_id(), &pass)?;
+    this_guests_passes.push(PassHolding::new(pass.get_id()));
+    let mut guests_passes = GuestsPasses::from_storage(deps.storage);
+    guests_passes.store_passes(&guest, &this_guests_passes)?;
+
+    // Pay organiser, less the platform fee paid to the fee recipient
+    let config = get_config_readonly(deps.storage).load()?;
+    let fee = bps_of(price, config.get_fee_bps()).ok_or(ContractError::Overflow { what: "fee" })?;
+    let mut balances = Balances::from_storage(deps.storage);
+    balances.set_account_balance(&guest, guest_balance - price);
+    let organiser_balance = balances.read_account_balance(pass.get_organiser())?;
+    balances.set_account_balance(pass.get_organiser(), organiser_balance + price - fee);
+    let recipient_balance = balances.read_account_balance(config.get_fee_recipient())?;
+    balances.set_account_balance(config.get_fee_recipient(), recipient_balance + fee);
+
+    Ok(Response::default())
+}
+
+pub fn try_redeem_pass(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: RedeemPassMsg,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and guest address
+    let entropy_raw = parse_entropy(&msg.entropy)?;
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let pass_id_raw = msg.pass_id.u128();
+    let event_id_raw = msg.event_id.u128();
+
+    // Ensure guest holds the pass, has not redeemed it for this event and it covers the event
+    let guests_passes = ReadonlyGuestsPasses::from_storage(deps.storage);
+    let mut this_guests_passes = guests_passes.load_passes(&guest)?;
+    let holding = match this_guests_passes.iter_mut().find(|holding| holding.get_pass_id() == pass_id_raw) {
+        Some(holding) => holding,
+        None => return Err(ContractError::Unauthorized { reason: "You do not hold this pass" }),
+    };
+    holding.redeem(event_id_raw)?;
+    let passes = ReadonlyPasses::from_storage(deps.storage);
+    let pass = match passes.may_load_pass(pass_id_raw)? {
+        Some(pass) => pass,
+        None => return Err(ContractError::PassNotFound {}),
+    };
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let mut event = match events.may_load_event(event_id_raw)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    if !pass.covers(&event) {
+        return Err(StdError::generic_err("Pass does not cover this event").into());
+    }
+    if event.is_cancelled() {
+        return Err(ContractError::EventCancelled {});
+    }
+
+    // Ensure guest is not banned and will not exceed the ticket limit for this event
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event_id_raw, &guest) {
+        return Err(ContractError::Banned { who: "Guest" });
     }
+    if count_guests_event_tickets(deps.storage, &guest, event_id_raw)? >= event.get_max_per_guest() {
+        return Err(ContractError::TicketLimitExceeded { max: event.get_max_per_guest() });
+    }
+
+    // Ensure tier has a ticket left and seat is free
+    let tier = event.find_tier(msg.tier.as_deref())?;
+    if event.get_tier(tier).is_sold_out() {
+        return Err(ContractError::SoldOut { item: "Tier" });
+    }
+    check_seats_free(deps.storage, &event, std::slice::from_ref(&msg.seat))?;
+
+    // Record redemption and ticket in event, and mint it already paid for by the pass
+    let mut guests_passes = GuestsPasses::from_storage(deps.storage);
+    guests_passes.store_passes(&guest, &this_guests_passes)?;
+    let mut config = get_config(deps.storage).load()?;
+    config.add_entropy(&entropy_raw);
+    get_config(deps.storage).save(&config)?;
+    event.ticket_sold(tier);
+    let pk = parse_guest_key(&msg.pk, msg.pk_type)?;
+    let ticket_id = mint_ticket(deps.storage, &event, tier, &guest, (pk, msg.seat), 0, &entropy_raw)?;
+    record_ticket_action(deps.storage, &env, ticket_id, ACTION_PURCHASED)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    let data = TicketIdResponse { ticket_id: Uint128::from(ticket_id) };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+pub fn try_open_group_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: OpenGroupOrderMsg,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and opener address
+    let entropy_raw = parse_entropy(&msg.entropy)?;
+    let opener = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let now = env.block.time.seconds();
+    if msg.pks.is_empty() {
+        return Err(StdError::generic_err("Quantity must be at least 1").into());
+    }
+    if msg.deadline <= now {
+        return Err(StdError::generic_err("Deadline must be in the future").into());
+    }
+
+    // Ensure a seat was given for every ticket, if any
+    let seats = match msg.seats {
+        Some(seats) if seats.len() != msg.pks.len() => {
+            return Err(StdError::generic_err("Expected one seat per ticket").into());
+        }
+        Some(seats) => seats.into_iter().map(Some).collect(),
+        None => vec![None; msg.pks.len()],
+    };
+    let pks = msg
+        .pks
+        .iter()
+        .map(|pk| parse_guest_key(pk, msg.pk_type))
+        .collect::<StdResult<Vec<GuestKey>>>()?;
+
+    // Ensure event exists, tier has enough tickets left, sales are open, seats are free and
+    // the opener will not exceed the ticket limit
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(msg.event_id.u128())? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    let quantity = pks.len() as u128;
+    let tier = event.find_tier(msg.tier.as_deref())?;
+    if event.get_tier(tier).get_tickets_left() < quantity {
+        return Err(StdError::generic_err(format!(
+            "Not enough tickets left: left={}, requested={}",
+            event.get_tier(tier).get_tickets_left(), quantity
+        )).into());
+    }
+    let allowlists = ReadonlyAllowlists::from_storage(deps.storage);
+    let allowlisted = allowlists.load_allowlist(event.get_id())?.contains(&opener);
+    event.check_sale_open(now, allowlisted)?;
+    check_seats_free(deps.storage, &event, &seats)?;
+    if count_guests_event_tickets(deps.storage, &opener, event.get_id())? + quantity > event.get_max_per_guest() {
+        return Err(ContractError::TicketLimitExceeded { max: event.get_max_per_guest() });
+    }
+
+    // Quote the order at the current price, including any insurance premium
+    let prices = price_tickets(deps.as_ref(), &env, &event, tier, quantity)?;
+    let target = prices
+        .iter()
+        .try_fold(0u128, |total, price| total.checked_add(*price)?.checked_add(event.insurance_premium(*price)))
+        .ok_or_else(|| StdError::generic_err("Total price overflows"))?;
+
+    // Store order
+    let mut config = get_config(deps.storage).load()?;
+    let order_id = config.get_next_group_order_id();
+    get_config(deps.storage).save(&config)?;
+    let holders = pks.into_iter().zip(seats).collect();
+    let mut order = GroupOrder::new(order_id, opener, event.get_id(), holders, target, msg.deadline);
+    order.set_tier(msg.tier);
+    order.set_invite(msg.invite);
+    order.set_entropy(entropy_raw);
+    let mut group_orders = GroupOrders::from_storage(deps.storage);
+    group_orders.store_group_order(order_id, &order)?;
+
+    let data = GroupOrderIdResponse { order_id: Uint128::from(order_id) };
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+pub fn try_contribute_group_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: Uint128,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    // Deposit any SCRT sent with the contribution
+    let contributor = deps.api.addr_canonicalize(info.sender.as_str())?;
+    deposit_funds(deps.storage, &env, &contributor, &info.funds)?;
+
+    // Ensure order exists and is still open
+    let group_orders = ReadonlyGroupOrders::from_storage(deps.storage);
+    let mut order = match group_orders.may_load_group_order(order_id.u128())? {
+        Some(order) => order,
+        None => return Err(ContractError::GroupOrderNotFound {}),
+    };
+    if order.is_expired(env.block.time.seconds()) {
+        return Err(StdError::generic_err("Group order has expired").into());
+    }
+
+    // Ensure contributor has sufficient funds and move the contribution into the order
+    let amount = match amount {
+        Some(amount) => amount.u128(),
+        None => order.get_target() - order.get_funded(),
+    };
+    let mut balances = Balances::from_storage(deps.storage);
+    let contributor_balance = balances.read_account_balance(&contributor)?;
+    if contributor_balance < amount {
+        return Err(ContractError::InsufficientFunds { balance: contributor_balance, required: amount });
+    }
+    order.contribute(&contributor, amount)?;
+    balances.set_account_balance(&contributor, contributor_balance - amount);
+    let mut group_orders = GroupOrders::from_storage(deps.storage);
+    if !order.is_funded() {
+        group_orders.store_group_order(order.get_id(), &order)?;
+        return Ok(Response::default());
+    }
+
+    // Once fully funded, pass the contributions to the opener and buy the tickets for them.
+    // Any change in price since the order was opened is settled from the opener's balance
+    group_orders.remove_group_order(order.get_id());
+    let mut balances = Balances::from_storage(deps.storage);
+    let opener_balance = balances.read_account_balance(order.get_opener())?;
+    balances.set_account_balance(order.get_opener(), opener_balance + order.get_target());
+    let opener_info = MessageInfo { sender: deps.api.addr_humanize(order.get_opener())?, funds: vec![] };
+    let purchase = PurchaseOrder {
+        event_id: order.get_event_id(),
+        entropy: hex::encode(order.get_entropy()),
+        tier: order.get_tier().map(String::from),
+        promo_code: None,
+        invite: order.get_invite().map(String::from),
+        holders: order.get_holders().clone(),
+        installments: false,
+        tip: 0,
+    };
+    let ticket_ids = purchase_tickets(deps, env, opener_info, purchase)?;
+    Ok(Response::new().set_data(to_binary(&TicketIdsResponse { ticket_ids })?))
+}
+
+pub fn try_cancel_group_order(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure order exists and sender is its opener, or its deadline has passed
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let group_orders = ReadonlyGroupOrders::from_storage(deps.storage);
+    let order = match group_orders.may_load_group_order(order_id.u128())? {
+        Some(order) => order,
+        None => return Err(ContractError::GroupOrderNotFound {}),
+    };
+    if *order.get_opener() != sender && !order.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::Unauthorized { reason: "Only the opener can cancel a group order before its deadline" });
+    }
+
+    // Refund every contribution and remove the order
+    let mut balances = Balances::from_storage(deps.storage);
+    for (contributor, amount) in order.get_contributions() {
+        let contributor_balance = balances.read_account_balance(contributor)?;
+        balances.set_account_balance(contributor, contributor_balance + amount);
+    }
+    let mut group_orders = GroupOrders::from_storage(deps.storage);
+    group_orders.remove_group_order(order.get_id());
+
+    Ok(Response::default())
+}
+
+// Hash of an invite message, which the organiser signs for each invited guest
+fn invite_hash(event_id: u128, guest: &str) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(event_id.to_be_bytes());
+    hasher.update(guest.as_bytes());
+    hasher.finalize().into()
+}
+
+// Ensure requested seats exist on the event and are not already taken
+fn check_seats_free(storage: &dyn Storage, event: &Event, requested: &[Option<String>]) -> StdResult<()> {
+    let event_seats = ReadonlyEventSeats::from_storage(storage);
+    for (i, seat) in requested.iter().enumerate() {
+        match (event.get_seats(), seat) {
+            (Some(seats), Some(seat)) => {
+                if !seats.contains(seat) {
+                    return Err(StdError::generic_err(format!("Seat does not exist: {}", seat)));
+                }
+                if event_seats.may_load_seat(event.get_id(), seat)?.is_some()
+                    || requested[..i].iter().any(|other| other.as_ref() == Some(seat))
+                {
+                    return Err(StdError::generic_err(format!("Seat is already taken: {}", seat)));
+                }
+            }
+            (Some(_), None) => {
+                return Err(StdError::generic_err("A seat must be chosen for this event"));
+            }
+            (None, Some(_)) => {
+                return Err(StdError::generic_err("This event does not have reserved seating"));
+            }
+            (None, None) => (),
+        }
+    }
+    Ok(())
+}
+
+// Create and store a new ticket for a sale already recorded in the event, seeding its secrets
+// from the event seed and the buyer's entropy
+fn mint_ticket(
+    storage: &mut dyn Storage,
+    event: &Event,
+    tier: usize,
+    guest: &CanonicalAddr,
+    (pk, seat): (GuestKey, Option<String>),
+    price: u128,
+    entropy: &[u8],
+) -> StdResult<u128> {
+    // Get next ticket id
+    let mut config = get_config(storage).load()?;
+    let ticket_id = config.get_next_ticket_id();
+    get_config(storage).save(&config)?;
+
+    // Create ticket
+    let tier_name = event.get_tier(tier).get_name().to_string();
+    let mut ticket = Ticket::new(ticket_id, event.get_id(), guest.clone(), pk, tier_name, price);
+    ticket.set_seed(derive_ticket_seed(event.get_seed(), entropy, ticket_id));
+    ticket.set_code_seed(config.generate_code_seed(ticket_id, ticket.get_code_seed()));
+    if let Some(seat) = &seat {
+        let mut event_seats = EventSeats::from_storage(storage);
+        event_seats.take_seat(event.get_id(), seat, ticket_id);
+    }
+    ticket.set_seat(seat);
+
+    // Store ticket in tickets
+    let mut tickets = Tickets::from_storage(storage);
+    tickets.store_ticket(ticket_id, &ticket)?;
+
+    // Store ticket in guests tickets and events tickets
+    let mut guests_tickets = GuestsTickets::from_storage(storage);
+    guests_tickets.add_ticket(guest, ticket_id)?;
+    let mut events_tickets = EventsTickets::from_storage(storage);
+    events_tickets.add_ticket(event.get_id(), ticket_id)?;
+
+    Ok(ticket_id)
+}
+
+pub fn try_verify_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let data = issue_ticket_challenge(deps, &env, &organiser, ticket_id.u128())?;
+
+    // Respond with encrypted secret and the key to decrypt it with in the encrypted response data
+    Ok(Response::new().set_data(to_binary(&data)?))
+}
+
+pub fn try_verify_tickets(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_ids: Vec<Uint128>,
+) -> Result<Response, ContractError> {
+    // Ensure tickets were given, once each so no secret is replaced within the batch
+    if ticket_ids.is_empty() {
+        return Err(StdError::generic_err("No tickets given").into());
+    }
+    let mut ticket_ids_raw: Vec<u128> = ticket_ids.iter().map(|ticket_id| ticket_id.u128()).collect();
+    ticket_ids_raw.sort_unstable();
+    ticket_ids_raw.dedup();
+    if ticket_ids_raw.len() != ticket_ids.len() {
+        return Err(StdError::generic_err("Tickets cannot be verified more than once per batch").into());
+    }
+
+    // Start validation of every ticket, failing the batch if any ticket cannot be verified
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut tickets = vec![];
+    for ticket_id in ticket_ids {
+        tickets.push(issue_ticket_challenge(deps.branch(), &env, &organiser, ticket_id.u128())?);
+    }
+
+    // Respond with every encrypted secret in the encrypted response data
+    Ok(Response::new().set_data(to_binary(&VerifyTicketsResponse { tickets })?))
+}
+
+// Issue a fresh challenge for a ticket to a verifier, returning the secret encrypted for the guest
+fn issue_ticket_challenge(
+    deps: DepsMut,
+    env: &Env,
+    organiser: &CanonicalAddr,
+    ticket_id_raw: u128,
+) -> Result<VerifyTicketResponse, ContractError> {
+    // Ensure ticket exists and load it
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket.clone(),
+        None => {
+            return Err(ContractError::TicketNotFound {});
+        }
+    };
+
+    // Ensure ticket is not used
+    if ticket.get_state() == TICKET_USED {
+        return Err(ContractError::TicketUsed {});
+    }
+    if ticket.get_state() == TICKET_REVOKED {
+        return Err(ContractError::TicketRevoked {});
+    }
+    if ticket.get_state() == TICKET_REFUNDED {
+        return Err(ContractError::TicketRefunded {});
+    }
+    if ticket.get_state() == TICKET_RESERVED {
+        return Err(ContractError::TicketUnpaid {});
+    }
+
+    // Check message sender is an organiser or verifier of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if !event.can_verify(organiser) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+
+    // Ensure ticket is not locked, check-in is open for this event, and ticket unused for the current session
+    ticket.check_not_locked(env.block.time.seconds())?;
+    event.check_checkin_open(env.block.time.seconds())?;
+    if let Some(session) = event.current_session(env.block.time.seconds())? {
+        if ticket.get_sessions_used().contains(&session) {
+            return Err(StdError::generic_err("Ticket has already been used for this session").into());
+        }
+    }
+
+    // Remove any resale listing, the holder is using the ticket
+    let mut listings = Listings::from_storage(deps.storage);
+    listings.remove_listing(ticket_id_raw);
+
+    // Generate fresh secret and set ticket status to validating, invalidating any previous challenge
+    let config = get_config_readonly(deps.storage).load()?;
+    let challenge = ticket.get_challenges();
+    let secret = derive_ticket_secret(ticket.get_seed(), config.get_prng_seed(), challenge);
+    let nonce = config.generate_nonce(ticket_id_raw, challenge);
+    let secret = ticket.start_validation(secret, nonce, env.block.height, env.block.time.seconds());
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+    record_ticket_action(deps.storage, env, ticket_id_raw, ACTION_CHALLENGED)?;
+    let pk = ticket.get_pk();
+
+    // Encrypt with public key of guest
+    let (ephemeral_pk, secret_encrypted) = encrypt_secret(config.get_prng_seed(), ticket_id_raw, challenge, pk, secret)?;
+
+    Ok(VerifyTicketResponse {
+        ticket_id: Uint128::from(ticket_id_raw),
+        ephemeral_pk: hex::encode(ephemeral_pk),
+        secret_encrypted: hex::encode(secret_encrypted),
+        nonce: format!("{:016X}", nonce),
+        challenges: ticket.get_challenges(),
+    })
+}
+
+// Encrypt a ticket secret for a guest's public key, using an ephemeral key exchange on the key's
+// curve and AES-GCM-SIV. Returns the ephemeral public key and the nonce followed by the ciphertext
+fn encrypt_secret(
+    prng_seed: &[u8; 32],
+    ticket_id: u128,
+    challenge: u32,
+    pk: &GuestKey,
+    secret: u64,
+) -> StdResult<(Vec<u8>, Vec<u8>)> {
+    // Derive ephemeral key and nonce for this challenge from the contract seed
+    let mut hasher = Sha256::new_with_prefix(prng_seed);
+    hasher.update(b"secret encryption");
+    hasher.update(ticket_id.to_be_bytes());
+    hasher.update(challenge.to_be_bytes());
+    let mut rng = ChaChaRng::from_seed(hasher.finalize().into());
+    let mut ephemeral_sk = [0u8; 32];
+    rng.fill_bytes(&mut ephemeral_sk);
+    let mut nonce = [0u8; 12];
+    rng.fill_bytes(&mut nonce);
+
+    // Agree a shared secret with the guest's key
+    let invalid_key = || StdError::generic_err("Ticket public key is not valid for its type");
+    let (ephemeral_pk, shared_secret) = match pk.get_key_type() {
+        KEY_SECP256K1 => {
+            let guest_pk = k256::PublicKey::from_sec1_bytes(pk.get_key()).map_err(|_| invalid_key())?;
+            let ephemeral_sk = k256::SecretKey::from_be_bytes(&ephemeral_sk)
+                .map_err(|_| StdError::generic_err("Failed to generate ephemeral key"))?;
+            let shared_secret = k256::elliptic_curve::ecdh::diffie_hellman(ephemeral_sk.to_nonzero_scalar(), guest_pk.as_affine());
+            let ephemeral_pk = ephemeral_sk.public_key().to_encoded_point(true).as_bytes().to_vec();
+            (ephemeral_pk, shared_secret.as_bytes().to_vec())
+        }
+        KEY_RSA => return Err(StdError::generic_err("Ticket has a legacy RSA key, transfer it to a new key")),
+        key_type => {
+            // Ed25519 keys are converted to their x25519 equivalent
+            let guest_pk: [u8; 32] = match key_type {
+                KEY_ED25519 => match CompressedEdwardsY::from_slice(pk.get_key()).ok().and_then(|point| point.decompress()) {
+                    Some(point) => point.to_montgomery().to_bytes(),
+                    None => return Err(invalid_key()),
+                },
+                _ => pk.get_key().try_into().map_err(|_| invalid_key())?,
+            };
+            let ephemeral_sk = StaticSecret::from(ephemeral_sk);
+            let shared_secret = ephemeral_sk.diffie_hellman(&PublicKey::from(guest_pk));
+            (PublicKey::from(&ephemeral_sk).to_bytes().to_vec(), shared_secret.as_bytes().to_vec())
+        }
+    };
+
+    // Key the cipher with the hashed shared secret
+    let key: [u8; 32] = Sha256::digest(&shared_secret).into();
+    let cipher = Aes256GcmSiv::new(&key.into());
+    let ciphertext = match cipher.encrypt(Nonce::from_slice(&nonce), secret.to_be_bytes().as_slice()) {
+        Ok(ciphertext) => ciphertext,
+        Err(_) => return Err(StdError::generic_err("Failed to encrypt ticket secret")),
+    };
+
+    Ok((ephemeral_pk, [nonce.as_slice(), &ciphertext].concat()))
+}
+
+// Decode 32 bytes of hex encoded entropy
+fn parse_entropy(entropy: &str) -> StdResult<[u8; 32]> {
+    let mut entropy_raw = [0u8; 32];
+    match hex::decode_to_slice(entropy, &mut entropy_raw) {
+        Ok(()) => Ok(entropy_raw),
+        Err(_) => Err(StdError::generic_err("Entropy is not a valid 32 byte hex string")),
+    }
+}
+
+// Parse a guest's public key, of the given type or inferred from its length
+fn parse_guest_key(pk: &str, pk_type: Option<PublicKeyType>) -> StdResult<GuestKey> {
+    let key_type = pk_type.map(|pk_type| match pk_type {
+        PublicKeyType::X25519 => KEY_X25519,
+        PublicKeyType::Ed25519 => KEY_ED25519,
+        PublicKeyType::Secp256k1 => KEY_SECP256K1,
+    });
+    GuestKey::parse(pk, key_type)
+}
+
+pub fn try_verify_guest(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    secret: String,
+    nonce: String,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and 'organiser' address
+    let ticket_id_raw = ticket_id.u128();
+    let secret_raw = match u64::from_str_radix(&secret, 16) {
+        Result::Ok(number) => number,
+        Result::Err(_) => {
+            return Err(StdError::generic_err(format!("Secret is not a valid 16 byte hex string",)).into());
+        }
+    };
+    let nonce_raw = match u64::from_str_radix(&nonce, 16) {
+        Ok(number) => number,
+        Err(_) => return Err(StdError::generic_err("Nonce is not a valid 8 byte hex string").into()),
+    };
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists and load it
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket.clone(),
+        None => {
+            return Err(ContractError::TicketNotFound {});
+        }
+    };
+
+    // Ensure ticket is in validating state
+    match ticket.get_state() {
+        TICKET_VALID => {
+            return Err(StdError::generic_err(format!(
+                "Validation of ticket not initiated yet"
+            )).into())
+        }
+        TICKET_VALIDATING => (),
+        TICKET_USED => {
+            return Err(ContractError::TicketUsed {})
+        }
+        TICKET_REVOKED => {
+            return Err(ContractError::TicketRevoked {})
+        }
+        TICKET_REFUNDED => {
+            return Err(ContractError::TicketRefunded {})
+        }
+        _ => {
+            return Err(StdError::generic_err(format!(
+                "Ticket is somehow in invalid state"
+            )).into())
+        }
+    };
+
+    // Check message sender is an organiser or verifier of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if !event.can_verify(&organiser) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+
+    // Ensure challenge has not expired
+    if ticket.is_challenge_expired(env.block.time.seconds(), event.get_challenge_ttl()) {
+        return Err(StdError::generic_err("Verification challenge has expired, verify the ticket again").into());
+    }
+
+    // Ensure guest is not banned and check-in is open for this event
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), ticket.get_guest()) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+    event.check_checkin_open(env.block.time.seconds())?;
+
+    // Record wrong secrets rather than erroring, so the attempt counts towards a lockout
+    let now = env.block.time.seconds();
+    ticket.check_not_locked(now)?;
+
+    // Reject nonces from earlier challenges, so an observed check-in cannot be replayed
+    if !ticket.matches_nonce(nonce_raw) {
+        return Err(StdError::generic_err("Nonce does not match the current challenge").into());
+    }
+    if !ticket.matches_secret(secret_raw) {
+        let failed_attempts = ticket.record_failed_attempt(now);
+        let mut tickets = Tickets::from_storage(deps.storage);
+        tickets.store_ticket(ticket_id_raw, &ticket)?;
+        return Ok(Response::new()
+            .add_attribute("verified", "false")
+            .add_attribute("failed_attempts", failed_attempts.to_string()));
+    }
+
+    // Ensure guest has confirmed from their address for two-factor events
+    if event.is_two_factor() && !ticket.is_confirmed(now) {
+        return Err(StdError::generic_err("Guest has not confirmed check-in from their address").into());
+    }
+
+    // Check if secret is correct, only marking the current session used for multi-day events
+    let verified = match event.current_session(env.block.time.seconds())? {
+        Some(session) => ticket.try_verify_session(secret_raw, session, event.get_sessions().len()),
+        None => ticket.try_verify(secret_raw),
+    };
+    match verified {
+        Ok(()) => {
+            let mut tickets = Tickets::from_storage(deps.storage);
+            tickets.store_ticket(ticket_id_raw, &ticket)?;
+
+            // Record the check-in and respond with the contract's receipt of it
+            let check_in = CheckIn::new(ticket_id_raw, event.get_id(), now, organiser);
+            let receipt = check_in_receipt(deps.as_ref(), &check_in)?;
+            let mut check_ins = CheckIns::from_storage(deps.storage);
+            check_ins.add_check_in(check_in)?;
+            record_ticket_action(deps.storage, &env, ticket_id_raw, ACTION_CHECKED_IN)?;
+            Ok(Response::new().add_attribute("verified", "true").set_data(to_binary(&receipt)?))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Contract's receipt of a check-in, for the guest and organiser to present in disputes
+fn check_in_receipt(deps: Deps, check_in: &CheckIn) -> StdResult<CheckInReceipt> {
+    let config = get_config_readonly(deps.storage).load()?;
+    let mac = check_in_mac(
+        config.get_payload_key(),
+        check_in.get_ticket_id(),
+        check_in.get_event_id(),
+        check_in.get_time(),
+        check_in.get_verifier(),
+    );
+    Ok(CheckInReceipt {
+        ticket_id: Uint128::from(check_in.get_ticket_id()),
+        event_id: Uint128::from(check_in.get_event_id()),
+        time: check_in.get_time(),
+        verifier: deps.api.addr_humanize(check_in.get_verifier())?,
+        receipt: hex::encode(mac),
+    })
+}
+
+// MAC over a check-in's fields, keyed so only this contract can issue receipts
+fn check_in_mac(payload_key: &[u8; 32], ticket_id: u128, event_id: u128, time: u64, verifier: &CanonicalAddr) -> [u8; 32] {
+    let mut hasher = Sha256::new_with_prefix(payload_key);
+    hasher.update(b"check-in receipt");
+    hasher.update(ticket_id.to_be_bytes());
+    hasher.update(event_id.to_be_bytes());
+    hasher.update(time.to_be_bytes());
+    hasher.update(verifier.as_slice());
+    hasher.finalize().into()
+}
+
+pub fn try_confirm_check_in(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure ticket exists and belongs to sender
+    let ticket_id_raw = ticket_id.u128();
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != guest {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+
+    // Ensure event requires confirmation and challenge is still open
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if !event.is_two_factor() {
+        return Err(StdError::generic_err("Event does not require check-in confirmation").into());
+    }
+    let now = env.block.time.seconds();
+    if ticket.is_challenge_expired(now, event.get_challenge_ttl()) {
+        return Err(StdError::generic_err("Verification challenge has expired, verify the ticket again").into());
+    }
+
+    ticket.confirm(now)?;
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_check_in(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure ticket exists, belongs to sender and can still be used
+    let ticket_id_raw = ticket_id.u128();
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != guest {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    match ticket.get_state() {
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_REVOKED => return Err(ContractError::TicketRevoked {}),
+        TICKET_REFUNDED => return Err(ContractError::TicketRefunded {}),
+        TICKET_RESERVED => return Err(ContractError::TicketUnpaid {}),
+        _ => (),
+    }
+
+    // Ensure guest is not banned, ticket is not locked and check-in is open for this event
+    let now = env.block.time.seconds();
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), &guest) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+    ticket.check_not_locked(now)?;
+    event.check_checkin_open(now)?;
+
+    // Generate a short code for the organiser to acknowledge before the challenge ttl
+    let config = get_config_readonly(deps.storage).load()?;
+    let code = config.generate_checkin_code(ticket_id_raw);
+    ticket.request_check_in(code, now);
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+
+    // Respond with code in the encrypted response data
+    let code = format!("{:0width$}", code, width = CODE_DIGITS as usize);
+    let valid_until = now.saturating_add(event.get_challenge_ttl());
+    Ok(Response::new().set_data(to_binary(&CheckInResponse { code, valid_until })?))
+}
+
+pub fn try_acknowledge_check_in(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    code: String,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and verifier address
+    let ticket_id_raw = ticket_id.u128();
+    let code_raw = match code.parse::<u32>() {
+        Ok(code_raw) if code.len() == CODE_DIGITS as usize => code_raw,
+        _ => return Err(StdError::generic_err(format!("Code must be {} digits", CODE_DIGITS)).into()),
+    };
+    let verifier = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists and can still be used
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    match ticket.get_state() {
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_REVOKED => return Err(ContractError::TicketRevoked {}),
+        TICKET_REFUNDED => return Err(ContractError::TicketRefunded {}),
+        TICKET_RESERVED => return Err(ContractError::TicketUnpaid {}),
+        _ => (),
+    }
+
+    // Check message sender is an organiser or verifier of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if !event.can_verify(&verifier) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+
+    // Ensure guest requested check-in recently, is not banned and check-in is still open
+    let now = env.block.time.seconds();
+    if !ticket.has_pending_check_in(now, event.get_challenge_ttl()) {
+        return Err(StdError::generic_err("Guest has no pending check-in, ask them to check in again").into());
+    }
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), ticket.get_guest()) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+    ticket.check_not_locked(now)?;
+    event.check_checkin_open(now)?;
+
+    // Record wrong codes rather than erroring, so the attempt counts towards a lockout
+    if !ticket.matches_checkin_code(code_raw) {
+        let failed_attempts = ticket.record_failed_attempt(now);
+        let mut tickets = Tickets::from_storage(deps.storage);
+        tickets.store_ticket(ticket_id_raw, &ticket)?;
+        return Ok(Response::new()
+            .add_attribute("verified", "false")
+            .add_attribute("failed_attempts", failed_attempts.to_string()));
+    }
+
+    // Check in, only marking the current session used for multi-day events
+    let session = event.current_session(now)?;
+    ticket.try_acknowledge_check_in(session, event.get_sessions().len())?;
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+    record_ticket_action(deps.storage, &env, ticket_id_raw, ACTION_CHECKED_IN)?;
+
+    Ok(Response::new().add_attribute("verified", "true"))
+}
+
+pub fn try_issue_offline_codes(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure message sender manages the event, which must be a single session
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let event = load_managed_event(deps.as_ref(), event_id.u128(), &sender)?;
+    if event.is_cancelled() {
+        return Err(ContractError::EventCancelled {});
+    }
+    if !event.get_sessions().is_empty() {
+        return Err(StdError::generic_err("Offline codes are not supported for multi-session events").into());
+    }
+
+    // Issue a fresh code to every unused ticket, replacing any previous batch
+    let config = get_config_readonly(deps.storage).load()?;
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let events_tickets = ReadonlyEventsTickets::from_storage(deps.storage);
+    let valid: Vec<Ticket> = events_tickets
+        .iter_tickets(event.get_id(), 0)?
+        .filter_map(|ticket_id| ticket_id.and_then(|ticket_id| tickets.may_load_ticket(ticket_id)).transpose())
+        .collect::<StdResult<Vec<Ticket>>>()?
+        .into_iter()
+        .filter(|ticket| ticket.get_state() == TICKET_VALID || ticket.get_state() == TICKET_VALIDATING)
+        .collect();
+    let mut codes = vec![];
+    let mut tickets = Tickets::from_storage(deps.storage);
+    for mut ticket in valid {
+        let code = config.generate_offline_code(ticket.get_id());
+        ticket.set_offline_code(code);
+        tickets.store_ticket(ticket.get_id(), &ticket)?;
+        codes.push(OfflineCheckin { ticket_id: Uint128::from(ticket.get_id()), code: format!("{:016X}", code) });
+    }
+
+    // Respond with codes in the encrypted response data
+    Ok(Response::new().set_data(to_binary(&OfflineCodesResponse { codes })?))
+}
+
+pub fn try_submit_offline_checkins(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Uint128,
+    checkins: Vec<OfflineCheckin>,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is an organiser or verifier of event
+    let event_id_raw = event_id.u128();
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id_raw)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    if !event.can_verify(&sender) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+    if event.is_cancelled() {
+        return Err(ContractError::EventCancelled {});
+    }
+
+    // Check in each ticket whose code matches, rejecting the rest rather than failing the batch
+    let mut checked_in = vec![];
+    let mut rejected = vec![];
+    for checkin in checkins {
+        let ticket_id_raw = checkin.ticket_id.u128();
+        let tickets = ReadonlyTickets::from_storage(deps.storage);
+        let ticket = tickets.may_load_ticket(ticket_id_raw)?.filter(|ticket| ticket.get_event_id() == event_id_raw);
+        let code = u64::from_str_radix(&checkin.code, 16).ok();
+        let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+        let verified = match (ticket, code) {
+            (Some(mut ticket), Some(code)) if !banned_guests.is_banned(event_id_raw, ticket.get_guest()) => {
+                ticket.try_verify_offline(code).map(|()| ticket).ok()
+            }
+            _ => None,
+        };
+        match verified {
+            Some(ticket) => {
+                let mut tickets = Tickets::from_storage(deps.storage);
+                tickets.store_ticket(ticket_id_raw, &ticket)?;
+                record_ticket_action(deps.storage, &env, ticket_id_raw, ACTION_CHECKED_IN)?;
+                checked_in.push(checkin.ticket_id);
+            }
+            None => rejected.push(checkin.ticket_id),
+        }
+    }
+
+    Ok(Response::new().set_data(to_binary(&OfflineCheckinsResponse { checked_in, rejected })?))
+}
+
+pub fn try_verify_guest_signature(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    signature: String,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and verifier address
+    let ticket_id_raw = ticket_id.u128();
+    let signature_raw = match hex::decode(&signature) {
+        Ok(signature) => signature,
+        Err(_) => return Err(StdError::generic_err("Signature is not a valid hex string").into()),
+    };
+    let verifier = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists, is being validated and has a key that can sign
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    match ticket.get_state() {
+        TICKET_VALIDATING => (),
+        TICKET_VALID => return Err(StdError::generic_err("Validation of ticket not initiated yet").into()),
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_REVOKED => return Err(ContractError::TicketRevoked {}),
+        TICKET_REFUNDED => return Err(ContractError::TicketRefunded {}),
+        TICKET_RESERVED => return Err(ContractError::TicketUnpaid {}),
+        _ => return Err(StdError::generic_err("Ticket is somehow in invalid state").into()),
+    }
+    if ticket.get_pk().get_key_type() != KEY_SECP256K1 {
+        return Err(StdError::generic_err("Signature check-in requires a secp256k1 ticket key").into());
+    }
+
+    // Check message sender is an organiser or verifier of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if !event.can_verify(&verifier) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+
+    // Ensure guest is not banned, ticket is not locked and check-in is open for this event
+    let now = env.block.time.seconds();
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), ticket.get_guest()) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+    ticket.check_not_locked(now)?;
+    event.check_checkin_open(now)?;
+    if ticket.is_challenge_expired(now, event.get_challenge_ttl()) {
+        return Err(StdError::generic_err("Verification challenge has expired, verify the ticket again").into());
+    }
+
+    // Record bad signatures rather than erroring, so the attempt counts towards a lockout. The
+    // guest signs the nonce of the current challenge, which checking in consumes
+    let message_hash = check_in_hash(ticket_id_raw, ticket.get_nonce());
+    let valid = deps.api.secp256k1_verify(&message_hash, &signature_raw, ticket.get_pk().get_key()).unwrap_or(false);
+    if !valid {
+        let failed_attempts = ticket.record_failed_attempt(now);
+        let mut tickets = Tickets::from_storage(deps.storage);
+        tickets.store_ticket(ticket_id_raw, &ticket)?;
+        return Ok(Response::new()
+            .add_attribute("verified", "false")
+            .add_attribute("failed_attempts", failed_attempts.to_string()));
+    }
+
+    // Check in, only marking the current session used for multi-day events
+    let session = event.current_session(now)?;
+    ticket.try_verify_nonce_signed(session, event.get_sessions().len())?;
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+    record_ticket_action(deps.storage, &env, ticket_id_raw, ACTION_CHECKED_IN)?;
+
+    Ok(Response::new().add_attribute("verified", "true"))
+}
+
+pub fn try_verify_qr_payload(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payload: String,
+) -> Result<Response, ContractError> {
+    // Ensure payload was issued by this contract and has not expired
+    let now = env.block.time.seconds();
+    let verifier = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let config = get_config_readonly(deps.storage).load()?;
+    let payload_raw = match Binary::from_base64(&payload) {
+        Ok(payload) if payload.len() == QR_PAYLOAD_LEN => payload.to_vec(),
+        _ => return Err(StdError::generic_err("QR payload is malformed").into()),
+    };
+    let (data, mac) = payload_raw.split_at(QR_PAYLOAD_LEN - 32);
+    if !ct_eq(&qr_payload_mac(config.get_payload_key(), data), mac) {
+        return Err(StdError::generic_err("QR payload was not issued by this contract").into());
+    }
+    let ticket_id_raw = u128::from_be_bytes(data[0..16].try_into().unwrap());
+    let event_id_raw = u128::from_be_bytes(data[16..32].try_into().unwrap());
+    let expiry = u64::from_be_bytes(data[32..40].try_into().unwrap());
+    if now >= expiry {
+        return Err(StdError::generic_err("QR payload has expired").into());
+    }
+
+    // Ensure ticket can still be used and is held by the guest the payload was issued to
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) if ticket.get_event_id() == event_id_raw => ticket,
+        _ => return Err(ContractError::TicketNotFound {}),
+    };
+    match ticket.get_state() {
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_REVOKED => return Err(ContractError::TicketRevoked {}),
+        TICKET_REFUNDED => return Err(ContractError::TicketRefunded {}),
+        TICKET_RESERVED => return Err(ContractError::TicketUnpaid {}),
+        _ => (),
+    }
+    if qr_commitment(config.get_payload_key(), &ticket) != data[40..72] {
+        return Err(StdError::generic_err("Ticket has changed hands since the QR payload was issued").into());
+    }
+
+    // Check message sender is an organiser or verifier of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(event_id_raw)?;
+    if !event.can_verify(&verifier) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+
+    // Ensure guest is not banned, ticket is not locked and check-in is open for this event
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), ticket.get_guest()) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+    ticket.check_not_locked(now)?;
+    event.check_checkin_open(now)?;
+
+    // Check in, only marking the current session used for multi-day events
+    let session = event.current_session(now)?;
+    ticket.try_verify_signed(&payload_raw, session, event.get_sessions().len())?;
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+    record_ticket_action(deps.storage, &env, ticket_id_raw, ACTION_CHECKED_IN)?;
+
+    Ok(Response::new().add_attribute("verified", "true"))
+}
+
+pub fn try_verify_ticket_code(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    code: String,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and verifier address
+    let ticket_id_raw = ticket_id.u128();
+    let code_raw = match code.parse::<u32>() {
+        Ok(code_raw) if code.len() == CODE_DIGITS as usize => code_raw,
+        _ => return Err(StdError::generic_err(format!("Code must be {} digits", CODE_DIGITS)).into()),
+    };
+    let verifier = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists and can still be used
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    match ticket.get_state() {
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_REVOKED => return Err(ContractError::TicketRevoked {}),
+        TICKET_REFUNDED => return Err(ContractError::TicketRefunded {}),
+        TICKET_RESERVED => return Err(ContractError::TicketUnpaid {}),
+        _ => (),
+    }
+
+    // Check message sender is an organiser or verifier of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if !event.can_verify(&verifier) {
+        return Err(ContractError::Unauthorized { reason: "You are not a verifier of this event" });
+    }
+
+    // Ensure guest is not banned, ticket is not locked and check-in is open for this event
+    let now = env.block.time.seconds();
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), ticket.get_guest()) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+    ticket.check_not_locked(now)?;
+    event.check_checkin_open(now)?;
+
+    // Accept the current or previous window's code, recording wrong codes towards a lockout
+    let window = now / CODE_WINDOW;
+    let matched = [window, window.saturating_sub(1)].into_iter().find(|window| ticket.code_at(*window) == code_raw);
+    let window = match matched {
+        Some(window) => window,
+        None => {
+            let failed_attempts = ticket.record_failed_attempt(now);
+            let mut tickets = Tickets::from_storage(deps.storage);
+            tickets.store_ticket(ticket_id_raw, &ticket)?;
+            return Ok(Response::new()
+                .add_attribute("verified", "false")
+                .add_attribute("failed_attempts", failed_attempts.to_string()));
+        }
+    };
+
+    // Check in, only marking the current session used for multi-day events
+    let session = event.current_session(now)?;
+    ticket.try_verify_signed(&window.to_be_bytes(), session, event.get_sessions().len())?;
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id_raw, &ticket)?;
+    record_ticket_action(deps.storage, &env, ticket_id_raw, ACTION_CHECKED_IN)?;
+
+    Ok(Response::new().add_attribute("verified", "true"))
+}
+
+// Hash of a ticket code request, which the guest signs with their ticket key
+fn code_request_hash(ticket_id: u128, window: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ticket_id.to_be_bytes());
+    hasher.update(window.to_be_bytes());
+    hasher.finalize().into()
+}
+
+// Length of a QR payload: ticket id, event id, expiry, commitment and mac
+const QR_PAYLOAD_LEN: usize = 16 + 16 + 8 + 32 + 32;
+
+// Maximum seconds a QR payload may be valid for
+const QR_MAX_TTL: u64 = 86_400;
+
+// Commitment binding a QR payload to the ticket's current holder and key
+fn qr_commitment(payload_key: &[u8; 32], ticket: &Ticket) -> [u8; 32] {
+    let mut hasher = Sha256::new_with_prefix(payload_key);
+    hasher.update(b"commitment");
+    hasher.update(ticket.get_id().to_be_bytes());
+    hasher.update(ticket.get_guest().as_slice());
+    hasher.update(ticket.get_pk().get_key());
+    hasher.finalize().into()
+}
+
+// Mac authenticating QR payload data as issued by this contract
+fn qr_payload_mac(payload_key: &[u8; 32], data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new_with_prefix(payload_key);
+    hasher.update(b"mac");
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+// Hash of a QR payload request, which the guest signs with their ticket key
+fn qr_request_hash(ticket_id: u128, expiry: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ticket_id.to_be_bytes());
+    hasher.update(expiry.to_be_bytes());
+    hasher.finalize().into()
+}
+
+// Hash of a check-in challenge's nonce, which the guest signs with their ticket key
+fn check_in_hash(ticket_id: u128, nonce: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(ticket_id.to_be_bytes());
+    hasher.update(nonce.to_be_bytes());
+    hasher.finalize().into()
+}
+
+pub fn try_revoke_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and 'organiser' address
+    let ticket_id_raw = ticket_id.u128();
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists and load it
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => {
+            return Err(ContractError::TicketNotFound {});
+        }
+    };
+
+    // Ensure ticket can still be revoked
+    match ticket.get_state() {
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_REVOKED => return Err(ContractError::TicketRevoked {}),
+        TICKET_REFUNDED => return Err(ContractError::TicketRefunded {}),
+        _ => (),
+    }
+
+    // Check message sender is organiser of event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if *event.get_organiser() != organiser {
+        return Err(ContractError::Unauthorized { reason: "You are not the organiser of this event" });
+    }
+
+    // Refund guest and void ticket
+    ticket.revoke();
+    let refund = ticket.get_price();
+    void_ticket(deps, &env, ticket, event, refund)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_refund_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and guest address
+    let ticket_id_raw = ticket_id.u128();
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists and belongs to sender
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => {
+            return Err(ContractError::TicketNotFound {});
+        }
+    };
+    if *ticket.get_guest() != guest {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    if ticket.get_state() != TICKET_VALID || !ticket.get_sessions_used().is_empty() {
+        return Err(StdError::generic_err("Ticket cannot be refunded").into());
+    }
+
+    // Ensure event is still accepting refunds
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    event.check_refund_open(env.block.time.seconds())?;
+
+    // Refund guest and void ticket
+    ticket.refund();
+    let refund = ticket.get_price();
+    void_ticket(deps, &env, ticket, event, refund)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_pay_installment(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Deposit any SCRT sent with the payment
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+    deposit_funds(deps.storage, &env, &guest, &info.funds)?;
+
+    // Ensure ticket exists, belongs to sender and has installments due
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != guest {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let mut event = events.load_event(ticket.get_event_id())?;
+    if event.is_cancelled() {
+        return Err(ContractError::EventCancelled {});
+    }
+
+    // Ensure guest has sufficient funds for the next installment
+    let amount = match ticket.get_installments() {
+        Some(installments) => installments.get_next_amount(),
+        None => return Err(StdError::generic_err("Ticket has no installments due").into()),
+    };
+    let mut balances = Balances::from_storage(deps.storage);
+    let guest_balance = balances.read_account_balance(&guest)?;
+    if guest_balance < amount {
+        return Err(ContractError::InsufficientFunds { balance: guest_balance, required: amount });
+    }
+
+    // Transfer installment into the event's escrow
+    balances.set_account_balance(&guest, guest_balance - amount);
+    ticket.pay_installment()?;
+    event.add_escrow(amount);
+    event.add_revenue(amount);
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket.get_id(), &ticket)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_cancel_installments(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure ticket exists, belongs to sender and has installments due
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != guest {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    let down_payment = match ticket.get_installments() {
+        Some(installments) => installments.get_down_payment(),
+        None => return Err(StdError::generic_err("Ticket has no installments due").into()),
+    };
+
+    // Refund installments paid, the down payment is kept by the event, and void ticket
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    ticket.refund();
+    let refund = ticket.get_price() - down_payment;
+    void_ticket(deps, &env, ticket, event, refund)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_forfeit_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure ticket exists and sender manages its event
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    let event = load_managed_event(deps.as_ref(), ticket.get_event_id(), &organiser)?;
+
+    // Ensure guest has missed an installment
+    if ticket.get_installments().is_none() {
+        return Err(StdError::generic_err("Ticket has no installments due").into());
+    }
+    if !ticket.is_overdue(env.block.time.seconds()) {
+        return Err(StdError::generic_err("No installment is overdue on this ticket").into());
+    }
+
+    // Void ticket, everything paid for it is kept by the event
+    ticket.revoke();
+    void_ticket(deps, &env, ticket, event, 0)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_transfer_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    recipient: Addr,
+    new_pk: String,
+    new_pk_type: Option<PublicKeyType>,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and addresses
+    let ticket_id_raw = ticket_id.u128();
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let recipient = deps.api.addr_canonicalize(recipient.as_str())?;
+
+    // Ensure ticket exists and belongs to sender
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != sender {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    if recipient == sender {
+        return Err(StdError::generic_err("Cannot transfer a ticket to yourself").into());
+    }
+
+    // Ensure ticket is not being validated, used or revoked
+    match ticket.get_state() {
+        TICKET_VALID => (),
+        TICKET_VALIDATING => return Err(StdError::generic_err("Ticket is being validated").into()),
+        TICKET_USED => return Err(ContractError::TicketUsed {}),
+        TICKET_RESERVED => return Err(ContractError::TicketUnpaid {}),
+        _ => return Err(ContractError::TicketRevoked {}),
+    }
+
+    // Ensure ticket is not listed for resale
+    let listings = ReadonlyListings::from_storage(deps.storage);
+    if listings.may_load_listing(ticket_id_raw)?.is_some() {
+        return Err(StdError::generic_err("Ticket is listed for sale, delist it first").into());
+    }
+
+    reassign_ticket(deps, &env, ticket, recipient, new_pk, new_pk_type)?;
+    Ok(Response::default())
+}
+
+pub fn try_list_ticket_for_sale(
+    deps: DepsMut,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    price: Uint128,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and seller address
+    let ticket_id_raw = ticket_id.u128();
+    let price_raw = price.u128();
+    let seller = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists, belongs to seller and is unused
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != seller {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    if ticket.get_state() != TICKET_VALID {
+        return Err(StdError::generic_err("Only unused tickets can be listed for sale").into());
+    }
+
+    // Ensure price is within the organiser's resale cap
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    let max_price = ticket.get_price().saturating_mul(event.get_max_resale_bps() as u128) / 10_000;
+    if price_raw > max_price {
+        return Err(StdError::generic_err(format!(
+            "Resale price exceeds the cap for this event: price={}, max={}",
+            price_raw, max_price
+        )).into());
+    }
+
+    // Store listing, replacing any existing one
+    let mut listings = Listings::from_storage(deps.storage);
+    listings.store_listing(ticket_id_raw, &Listing::new(ticket_id_raw, event.get_id(), seller, price_raw))?;
+
+    Ok(Response::default())
+}
+
+pub fn try_delist_ticket(
+    deps: DepsMut,
+    info: MessageInfo,
+    ticket_id: Uint128,
+) -> Result<Response, ContractError> {
+    let ticket_id_raw = ticket_id.u128();
+    let seller = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure listing exists and belongs to sender
+    let mut listings = Listings::from_storage(deps.storage);
+    let listing = match listings.may_load_listing(ticket_id_raw)? {
+        Some(listing) => listing,
+        None => return Err(StdError::generic_err("Ticket is not listed for sale").into()),
+    };
+    if *listing.get_seller() != seller {
+        return Err(ContractError::Unauthorized { reason: "You did not list this ticket" });
+    }
+
+    listings.remove_listing(ticket_id_raw);
+    Ok(Response::default())
+}
+
+pub fn try_buy_resale_ticket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    new_pk: String,
+    new_pk_type: Option<PublicKeyType>,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and buyer address
+    let ticket_id_raw = ticket_id.u128();
+    let buyer = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure listing exists
+    let listings = ReadonlyListings::from_storage(deps.storage);
+    let listing = match listings.may_load_listing(ticket_id_raw)? {
+        Some(listing) => listing,
+        None => return Err(StdError::generic_err("Ticket is not listed for sale").into()),
+    };
+    let seller = listing.get_seller().clone();
+    if seller == buyer {
+        return Err(StdError::generic_err("Cannot buy your own ticket").into());
+    }
+
+    // Ensure ticket is still owned by the seller and unused
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = tickets.load_ticket(ticket_id_raw)?;
+    if *ticket.get_guest() != seller || ticket.get_state() != TICKET_VALID {
+        return Err(StdError::generic_err("Listing is no longer valid").into());
+    }
+
+    // Ensure buyer has sufficient funds
+    let balances = ReadonlyBalances::from_storage(deps.storage);
+    let buyer_balance = balances.read_account_balance(&buyer)?;
+    let price = listing.get_price();
+    if buyer_balance < price {
+        return Err(ContractError::InsufficientFunds { balance: buyer_balance, required: price });
+    }
+
+    // Split proceeds between seller and the event's payees
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(listing.get_event_id())?;
+    let royalty = price.saturating_mul(event.get_royalty_bps() as u128) / 10_000;
+
+    // Transfer funds
+    let mut balances = Balances::from_storage(deps.storage);
+    balances.set_account_balance(&buyer, buyer_balance - price);
+    let seller_balance = balances.read_account_balance(&seller)?;
+    balances.set_account_balance(&seller, seller_balance + price - royalty);
+    credit_proceeds(deps.storage, &event, royalty)?;
+
+    // Remove listing and hand over ticket
+    let mut listings = Listings::from_storage(deps.storage);
+    listings.remove_listing(ticket_id_raw);
+    reassign_ticket(deps, &env, ticket, buyer, new_pk, new_pk_type)?;
+
+    Ok(Response::default())
+}
+
+// Move a ticket to a new guest, enforcing the event's per guest limit and rotating its secrets
+// so the previous guest cannot check in with anything they learnt while holding it
+fn reassign_ticket(
+    deps: DepsMut,
+    env: &Env,
+    mut ticket: Ticket,
+    recipient: CanonicalAddr,
+    new_pk: String,
+    new_pk_type: Option<PublicKeyType>,
+) -> Result<(), ContractError> {
+    let ticket_id = ticket.get_id();
+    let previous = ticket.get_guest().clone();
+
+    // Ensure recipient will not exceed the ticket limit for this event
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event.get_id(), &recipient) {
+        return Err(ContractError::Banned { who: "Recipient" });
+    }
+    let owned = count_guests_event_tickets(deps.storage, &recipient, event.get_id())?;
+    if owned >= event.get_max_per_guest() {
+        return Err(StdError::generic_err(format!(
+            "Recipient already owns the maximum number of tickets to this event: {}",
+            event.get_max_per_guest()
+        )).into());
+    }
+
+    // Ensure the new guest brings their own key
+    let pk = parse_guest_key(&new_pk, new_pk_type)?;
+    if pk == *ticket.get_pk() {
+        return Err(StdError::generic_err("New guest must provide their own public key").into());
+    }
+
+    // Reassign ticket with a fresh code seed
+    let config = get_config_readonly(deps.storage).load()?;
+    let code_seed = config.generate_code_seed(ticket_id, ticket.get_code_seed());
+    ticket.transfer(recipient.clone(), pk, code_seed);
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket_id, &ticket)?;
+    record_ticket_action(deps.storage, env, ticket_id, ACTION_TRANSFERRED)?;
+
+    // Move ticket between guests tickets
+    let mut guests_tickets = GuestsTickets::from_storage(deps.storage);
+    guests_tickets.remove_ticket(&previous, ticket_id)?;
+    guests_tickets.add_ticket(&recipient, ticket_id)?;
+
+    Ok(())
+}
+
+pub fn try_join_waitlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    entropy: String,
+    pk: String,
+    pk_type: Option<PublicKeyType>,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and guest address
+    let event_id_raw = event_id.u128();
+    let entropy_raw = parse_entropy(&entropy)?;
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure event exists and is sold out
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id_raw)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    if !event.is_sold_out() {
+        return Err(StdError::generic_err("Event is not sold out, buy a ticket instead").into());
+    }
+    let banned_guests = ReadonlyBannedGuests::from_storage(deps.storage);
+    if banned_guests.is_banned(event_id_raw, &guest) {
+        return Err(ContractError::Banned { who: "Guest" });
+    }
+
+    // Ensure guest is not already waiting
+    let mut waitlists = Waitlists::from_storage(deps.storage);
+    let mut waitlist = waitlists.load_waitlist(event_id_raw)?;
+    if waitlist.iter().any(|entry| *entry.get_guest() == guest) {
+        return Err(StdError::generic_err("You are already on the waitlist for this event").into());
+    }
+
+    // Join the back of the waitlist
+    waitlist.push(WaitlistEntry::new(guest, parse_guest_key(&pk, pk_type)?, entropy_raw));
+    waitlists.store_waitlist(event_id_raw, &waitlist)?;
+
+    Ok(Response::default())
+}
+
+// Mint a freed ticket in a tier for the first waitlisted guest able to pay for it
+fn fill_from_waitlist(
+    deps: DepsMut,
+    env: &Env,
+    event: &mut Event,
+    tier: usize,
+    seat: Option<String>,
+) -> StdResult<Option<u128>> {
+    // Price the freed ticket as the tier's next sale, in sEVNT, plus any insurance premium
+    let price = price_tickets(deps.as_ref(), env, event, tier, 1)?[0];
+    let premium = event.insurance_premium(price);
+    let cost = match price.checked_add(premium) {
+        Some(cost) => cost,
+        None => return Err(StdError::generic_err("Total price overflows")),
+    };
+    let storage = deps.storage;
+    let waitlists = ReadonlyWaitlists::from_storage(storage);
+    let mut waitlist = waitlists.load_waitlist(event.get_id())?;
+
+    let mut minted = None;
+    while !waitlist.is_empty() && minted.is_none() {
+        let entry = waitlist.remove(0);
+        let guest = entry.get_guest();
+
+        // Skip guests who can no longer pay or have since reached the limit
+        let balances = ReadonlyBalances::from_storage(storage);
+        let guest_balance = balances.read_account_balance(guest)?;
+        if guest_balance < cost {
+            continue;
+        }
+        if count_guests_event_tickets(storage, guest, event.get_id())? >= event.get_max_per_guest() {
+            continue;
+        }
+
+        // Transfer funds into the event's escrow and premium into the insurance pool
+        let mut balances = Balances::from_storage(storage);
+        balances.set_account_balance(guest, guest_balance - cost);
+        event.add_escrow(price);
+        event.add_revenue(price);
+
+        // Record ticket sale in event and mint ticket
+        let mut config = get_config(storage).load()?;
+        config.add_insurance_premium(premium);
+        config.add_entropy(entry.get_entropy());
+        get_config(storage).save(&config)?;
+        event.ticket_sold(tier);
+        let holder = (entry.get_pk().clone(), seat.clone());
+        let ticket_id = mint_ticket(storage, event, tier, guest, holder, price, entry.get_entropy())?;
+        record_ticket_action(storage, env, ticket_id, ACTION_PURCHASED)?;
+        minted = Some(ticket_id);
+    }
+
+    let mut waitlists = Waitlists::from_storage(storage);
+    waitlists.store_waitlist(event.get_id(), &waitlist)?;
+    Ok(minted)
+}
+
+pub fn try_add_promo_code(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    code_hash: String,
+    discount_percent: u8,
+    max_uses: u32,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Decode SHA-256 hash of the code
+    let code_hash: [u8; 32] = match hex::decode(&code_hash).map(<[u8; 32]>::try_from) {
+        Ok(Ok(hash)) => hash,
+        _ => return Err(StdError::generic_err("Code hash is not a valid 32 byte hex string").into()),
+    };
+    if discount_percent == 0 || discount_percent > 100 {
+        return Err(StdError::generic_err("Discount must be between 1 and 100 percent").into());
+    }
+
+    // Store promo code, replacing any existing one with the same hash
+    let mut promo_codes = PromoCodes::from_storage(deps.storage);
+    promo_codes.store_promo_code(event.get_id(), &code_hash, &PromoCode::new(discount_percent, max_uses))?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    addresses: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Store presale allowlist, replacing any existing one
+    let allowlist = addresses
+        .iter()
+        .map(|address| deps.api.addr_canonicalize(address.as_str()))
+        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    let mut allowlists = Allowlists::from_storage(deps.storage);
+    allowlists.store_allowlist(event.get_id(), &allowlist)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_add_co_organiser(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Add co-organiser to event
+    event.add_co_organiser(deps.api.addr_canonicalize(address.as_str())?)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_remove_co_organiser(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Remove co-organiser from event
+    event.remove_co_organiser(&deps.api.addr_canonicalize(address.as_str())?)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_add_verifier(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Add verifier to event
+    event.add_verifier(deps.api.addr_canonicalize(address.as_str())?)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_remove_verifier(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    address: Addr,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Remove verifier from event
+    event.remove_verifier(&deps.api.addr_canonicalize(address.as_str())?)?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_guest_banned(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    address: Addr,
+    banned: bool,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Update guests ban
+    let guest = deps.api.addr_canonicalize(address.as_str())?;
+    let mut banned_guests = BannedGuests::from_storage(deps.storage);
+    if banned {
+        banned_guests.ban_guest(event.get_id(), &guest);
+    } else {
+        banned_guests.unban_guest(event.get_id(), &guest);
+    }
+
+    Ok(Response::default())
+}
+
+pub fn try_set_capacity(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    max_tickets: Uint128,
+    tier: Option<String>,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Update capacity of tier
+    let tier = event.find_tier(tier.as_deref())?;
+    event.set_max_tickets(tier, max_tickets.u128())?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_claim_payout(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Uint128,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Ensure event has completed and there are proceeds to release
+    let now = env.block.time.seconds();
+    let dispute_window = get_config_readonly(deps.storage).load()?.get_dispute_window();
+    event.check_payout_open(now, dispute_window)?;
+    let amount = event.take_escrow(event.get_escrow());
+    if amount == 0 {
+        return Err(StdError::generic_err("No proceeds to claim").into());
+    }
+
+    // Credit payees, less the platform fee paid to the fee recipient
+    let config = get_config_readonly(deps.storage).load()?;
+    let fee = bps_of(amount, config.get_fee_bps()).ok_or(ContractError::Overflow { what: "fee" })?;
+    credit_proceeds(deps.storage, &event, amount - fee)?;
+    let mut balances = Balances::from_storage(deps.storage);
+    let recipient_balance = balances.read_account_balance(config.get_fee_recipient())?;
+    balances.set_account_balance(config.get_fee_recipient(), recipient_balance + fee);
+
+    // Store updated event and payout record
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+    let mut payouts = Payouts::from_storage(deps.storage);
+    let mut this_events_payouts = payouts.load_payouts(event.get_id())?;
+    this_events_payouts.push(Payout::new(amount - fee, fee, now));
+    payouts.store_payouts(event.get_id(), &this_events_payouts)?;
+
+    Ok(Response::new()
+        .add_attribute("payout", (amount - fee).to_string())
+        .add_attribute("fee", fee.to_string()))
+}
+
+// Credit an event's proceeds to its payees, or its organiser if it has none. The organiser's
+// share goes to their payout address if they have set one
+fn credit_proceeds(storage: &mut dyn Storage, event: &Event, amount: u128) -> StdResult<()> {
+    let profiles = ReadonlyOrganiserProfiles::from_storage(storage);
+    let payout_address = profiles
+        .may_load_profile(event.get_organiser())?
+        .and_then(|profile| profile.get_payout_address().cloned());
+    let mut balances = Balances::from_storage(storage);
+    for (payee, share) in event.split_proceeds(amount)? {
+        let payee = match &payout_address {
+            Some(payout_address) if payee == *event.get_organiser() => payout_address.clone(),
+            _ => payee,
+        };
+        let payee_balance = balances.read_account_balance(&payee)?;
+        balances.set_account_balance(&payee, payee_balance + share);
+    }
+    Ok(())
+}
+
+pub fn try_open_dispute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ticket_id: Uint128,
+    reason: String,
+) -> Result<Response, ContractError> {
+    // Get raw inputs and guest address
+    let ticket_id_raw = ticket_id.u128();
+    let guest = deps.api.addr_canonicalize(info.sender.as_str())?;
+
+    // Ensure ticket exists, belongs to sender and was not refunded
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}),
+    };
+    if *ticket.get_guest() != guest {
+        return Err(ContractError::Unauthorized { reason: "You do not own this ticket" });
+    }
+    if ticket.get_state() == TICKET_REVOKED || ticket.get_state() == TICKET_REFUNDED {
+        return Err(ContractError::TicketRefunded {});
+    }
+
+    // Ensure dispute window is open
+    let dispute_window = get_config_readonly(deps.storage).load()?.get_dispute_window();
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let mut event = events.load_event(ticket.get_event_id())?;
+    event.check_dispute_open(env.block.time.seconds(), dispute_window)?;
+
+    // Record dispute and freeze escrow
+    let mut disputes = Disputes::from_storage(deps.storage);
+    let mut this_events_disputes = disputes.load_disputes(event.get_id())?;
+    if this_events_disputes.iter().any(|dispute| dispute.get_ticket_id() == ticket_id_raw) {
+        return Err(StdError::generic_err("A dispute is already open for this ticket").into());
+    }
+    this_events_disputes.push(Dispute::new(ticket_id_raw, reason));
+    disputes.store_disputes(event.get_id(), &this_events_disputes)?;
+    event.set_disputed(true);
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_resolve_disputes(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Uint128,
+    outcome: DisputeOutcome,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is the owner or an arbiter
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let config = get_config_readonly(deps.storage).load()?;
+    if !config.can_arbitrate(&sender) {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner or an arbiter can resolve disputes" });
+    }
+
+    // Ensure event has open disputes
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id.u128())? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    if !event.is_disputed() {
+        return Err(StdError::generic_err("Event has no open disputes").into());
+    }
+    let mut disputes = Disputes::from_storage(deps.storage);
+    let this_events_disputes = disputes.load_disputes(event.get_id())?;
+    disputes.store_disputes(event.get_id(), &vec![])?;
+
+    // Refund and void disputed tickets, drawing on the organiser for any shortfall in escrow
+    if outcome == DisputeOutcome::Refund {
+        for dispute in this_events_disputes {
+            let tickets = ReadonlyTickets::from_storage(deps.storage);
+            let mut ticket = tickets.load_ticket(dispute.get_ticket_id())?;
+            if ticket.get_state() == TICKET_REVOKED || ticket.get_state() == TICKET_REFUNDED {
+                continue;
+            }
+            let events = ReadonlyEvents::from_storage(deps.storage);
+            let event = events.load_event(event_id.u128())?;
+            ticket.refund();
+            let refund = ticket.get_price();
+            void_ticket(deps.branch(), &env, ticket, event, refund)?;
+        }
+    }
+
+    // Unfreeze escrow
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let mut event = events.load_event(event_id.u128())?;
+    event.set_disputed(false);
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_arbiters(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can set arbiters" });
+    }
+
+    // Update and save config
+    let arbiters = addresses
+        .iter()
+        .map(|address| deps.api.addr_canonicalize(address.as_str()))
+        .collect::<StdResult<Vec<CanonicalAddr>>>()?;
+    config.set_arbiters(arbiters);
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_private_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    metadata: String,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is the events organiser
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let event = load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    let mut private_metadata = PrivateMetadata::from_storage(deps.storage);
+    private_metadata.store_metadata(event.get_id(), &metadata);
+
+    Ok(Response::default())
+}
+
+pub fn try_complete_event(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is the events organiser and event is not cancelled
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+    if event.is_cancelled() {
+        return Err(ContractError::EventCancelled {});
+    }
+
+    // Complete event, starting the wait before its proceeds are released
+    event.complete(env.block.time.seconds())?;
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_cancel_event(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is the events organiser and event is not already cancelled
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_organisers_event(deps.as_ref(), event_id.u128(), &organiser)?;
+    if event.is_cancelled() {
+        return Err(ContractError::EventCancelled {});
+    }
+    event.cancel();
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    // Find tickets to refund, those not already checked in, revoked or refunded
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let events_tickets = ReadonlyEventsTickets::from_storage(deps.storage);
+    let affected: Vec<Ticket> = events_tickets
+        .iter_tickets(event.get_id(), 0)?
+        .filter_map(|ticket_id| ticket_id.and_then(|ticket_id| tickets.may_load_ticket(ticket_id)).transpose())
+        .collect::<StdResult<Vec<Ticket>>>()?
+        .into_iter()
+        .filter(|ticket| ![TICKET_USED, TICKET_REVOKED, TICKET_REFUNDED].contains(&ticket.get_state()))
+        .collect();
+
+    // Refund and void each ticket against the cancelled event as stored so far
+    for mut ticket in affected {
+        let events = ReadonlyEvents::from_storage(deps.storage);
+        let event = events.load_event(event_id.u128())?;
+        ticket.refund();
+        let refund = ticket.get_price();
+        void_ticket(deps.branch(), &env, ticket, event, refund)?;
+    }
+
+    Ok(Response::default())
+}
+
+pub fn try_slash_organiser(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    event_id: Uint128,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can slash organisers" });
+    }
+
+    // Ensure event exists and its organiser has a bond to slash
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id.u128())? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    let mut organisers = Organisers::from_storage(deps.storage);
+    let mut organiser = match organisers.may_load_organiser(event.get_organiser())? {
+        Some(organiser) if !organiser.is_slashed() => organiser,
+        _ => return Err(StdError::generic_err("Organiser has no bond to slash").into()),
+    };
+    let bond_native = organiser.slash();
+    organisers.store_organiser(event.get_organiser(), &organiser)?;
+    config.remove_bond(bond_native);
+
+    // Find affected tickets, those not already revoked or refunded
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let events_tickets = ReadonlyEventsTickets::from_storage(deps.storage);
+    let affected: Vec<Ticket> = events_tickets
+        .iter_tickets(event.get_id(), 0)?
+        .filter_map(|ticket_id| ticket_id.and_then(|ticket_id| tickets.may_load_ticket(ticket_id)).transpose())
+        .collect::<StdResult<Vec<Ticket>>>()?
+        .into_iter()
+        .filter(|ticket| ticket.get_state() != TICKET_REVOKED && ticket.get_state() != TICKET_REFUNDED)
+        .collect();
+
+    // Slashed bond now backs sEVNT credited to guests
+    let bond = config.get_exchange_rate(env.block.time.seconds()).to_tokens(bond_native)?;
+    config.mint(bond);
+    get_config(deps.storage).save(&config)?;
+    let mut reserves = Reserves::from_storage(deps.storage);
+    let reserve = reserves.read_reserve(config.get_primary_denom())?;
+    reserves.set_reserve(config.get_primary_denom(), reserve + bond_native);
+
+    // Distribute bond pro-rata to ticket price, or evenly if all tickets were free
+    let total_price = affected
+        .iter()
+        .try_fold(0u128, |total, ticket| total.checked_add(ticket.get_price()))
+        .ok_or(ContractError::Overflow { what: "slashed shares" })?;
+    let mut balances = Balances::from_storage(deps.storage);
+    let mut distributed = 0;
+    for ticket in &affected {
+        let share = match total_price {
+            0 => bond / affected.len() as u128,
+            _ => Uint128::from(bond)
+                .checked_multiply_ratio(ticket.get_price(), total_price)
+                .map_err(|_| ContractError::Overflow { what: "slashed shares" })?
+                .u128(),
+        };
+        let guest_balance = balances.read_account_balance(ticket.get_guest())?;
+        balances.set_account_balance(ticket.get_guest(), guest_balance + share);
+        distributed += share;
+    }
+
+    // Remainder from rounding, or the whole bond if no tickets were sold, goes to the fee recipient
+    let recipient_balance = balances.read_account_balance(config.get_fee_recipient())?;
+    balances.set_account_balance(config.get_fee_recipient(), recipient_balance + bond - distributed);
+
+    Ok(Response::new().add_attribute("slashed", bond.to_string()))
+}
+
+pub fn try_set_contract_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    level: ContractStatusLevel,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can set the contract status" });
+    }
+
+    // Update and save config
+    config.set_status(match level {
+        ContractStatusLevel::NormalRun => STATUS_NORMAL,
+        ContractStatusLevel::StopPurchases => STATUS_STOP_PURCHASES,
+        ContractStatusLevel::StopAll => STATUS_STOP_ALL,
+    });
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate_bps: u32,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can update the fee config" });
+    }
+
+    // Update and save config
+    config.set_fee_config(rate_bps, deps.api.addr_canonicalize(recipient.as_str())?)?;
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_dispute_window(deps: DepsMut, info: MessageInfo, seconds: u64) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can set the dispute window" });
+    }
+
+    // Update and save config
+    config.set_dispute_window(seconds)?;
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_deposit: Uint128,
+    min_withdrawal: Uint128,
+    withdrawal_cap: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can set the limits" });
+    }
+
+    // Update and save config
+    config.set_limits(min_deposit.u128(), min_withdrawal.u128(), withdrawal_cap.map(|cap| cap.u128()))?;
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_exchange_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    rate: Uint128,
+    decimals: u8,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can set the exchange rate" });
+    }
+
+    // Schedule new rate and save config
+    let rate = ExchangeRate::new(rate.u128(), decimals)?;
+    let effective_at = config.set_exchange_rate(rate, env.block.time.seconds());
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::new().add_attribute("effective_at", effective_at.to_string()))
+}
+
+pub fn try_set_price_oracle(
+    deps: DepsMut,
+    info: MessageInfo,
+    oracle: Option<OracleContract>,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can set the price oracle" });
+    }
+
+    // Update and save config
+    let oracle = match oracle {
+        Some(oracle) => {
+            let address = deps.api.addr_validate(&oracle.address)?;
+            Some((deps.api.addr_canonicalize(address.as_str())?, oracle.code_hash))
+        }
+        None => None,
+    };
+    config.set_oracle(oracle);
+    get_config(deps.storage).save(&config)?;
+
+    Ok(Response::default())
+}
+
+pub fn try_set_staking(
+    deps: DepsMut,
+    info: MessageInfo,
+    validator: String,
+    stake_bps: u32,
+    treasury: Addr,
+) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can configure staking" });
+    }
+
+    // Only the chain's staking denom can be delegated
+    if deps.querier.query_bonded_denom()? != config.get_primary_denom() {
+        return Err(StdError::generic_err("The primary denom cannot be staked").into());
+    }
+
+    // Update the existing delegation, which must be undelegated before changing validator
+    let treasury_canon = deps.api.addr_canonicalize(treasury.as_str())?;
+    match config.get_staking_mut() {
+        Some(staking) if staking.get_validator() == validator => {
+            staking.set_stake_bps(stake_bps)?;
+            staking.set_treasury(treasury_canon);
+        }
+        Some(staking) if staking.get_staked() > 0 => {
+            return Err(StdError::generic_err("Stake must be undelegated before changing validator").into());
+        }
+        _ => config.set_staking(Some(Staking::new(validator, stake_bps, treasury_canon)?)),
+    }
+    get_config(deps.storage).save(&config)?;
+
+    // Rewards are paid straight to the treasury
+    Ok(Response::new().add_message(DistributionMsg::SetWithdrawAddress { address: treasury.into_string() }))
+}
+
+pub fn try_rebalance_stake(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner and staking is configured
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut config = get_config(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can rebalance stake" });
+    }
+    let denom = config.get_primary_denom().to_string();
+    let reserves = ReadonlyReserves::from_storage(deps.storage);
+    let reserve = reserves.read_reserve(&denom)?;
+    let liquid = config.liquid_reserve(&denom, reserve, env.block.time.seconds());
+    let staking = match config.get_staking_mut() {
+        Some(staking) => staking,
+        None => return Err(StdError::generic_err("Staking is not configured").into()),
+    };
+
+    // Move stake towards the target share of the reserve, delegating only liquid tokens
+    let target = bps_of(reserve, staking.get_stake_bps()).ok_or(ContractError::Overflow { what: "stake" })?;
+    let staked = staking.get_staked();
+    let validator = staking.get_validator().to_string();
+    let response = if staked < target && liquid > 0 {
+        let amount = (target - staked).min(liquid);
+        staking.delegate(amount);
+        Response::new()
+            .add_message(StakingMsg::Delegate { validator, amount: Coin { denom, amount: Uint128::from(amount) } })
+            .add_attribute("delegated", amount.to_string())
+    } else if staked > target {
+        let amount = staked - target;
+        staking.undelegate(amount, env.block.time.seconds());
+        Response::new()
+            .add_message(StakingMsg::Undelegate { validator, amount: Coin { denom, amount: Uint128::from(amount) } })
+            .add_attribute("undelegated", amount.to_string())
+    } else {
+        Response::default()
+    };
+    get_config(deps.storage).save(&config)?;
+
+    Ok(response)
+}
+
+pub fn try_claim_staking_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    // Ensure message sender is contract owner and staking is configured
+    let sender = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let config = get_config_readonly(deps.storage).load()?;
+    if *config.get_owner() != sender {
+        return Err(ContractError::Unauthorized { reason: "Only the contract owner can claim staking rewards" });
+    }
+    let staking = match config.get_staking() {
+        Some(staking) => staking,
+        None => return Err(StdError::generic_err("Staking is not configured").into()),
+    };
+
+    // Rewards go to the treasury set as the withdraw address
+    Ok(Response::new().add_message(DistributionMsg::WithdrawDelegatorReward {
+        validator: staking.get_validator().to_string(),
+    }))
+}
+
+pub fn try_set_sales_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    event_id: Uint128,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let organiser = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut event = load_managed_event(deps.as_ref(), event_id.u128(), &organiser)?;
+
+    // Update paused flag
+    event.set_paused(paused);
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    Ok(Response::default())
+}
+
+// Append an action taken in this block to a ticket's history
+fn record_ticket_action(storage: &mut dyn Storage, env: &Env, ticket_id: u128, action: u8) -> StdResult<()> {
+    let mut histories = TicketHistories::from_storage(storage);
+    histories.add_record(ticket_id, TicketRecord::new(action, env.block.height, env.block.time.seconds()))
+}
+
+// Refund a revoked, refunded or forfeited ticket from escrow, or the organiser once released,
+// free its capacity and seat, and offer it to the waitlist
+fn void_ticket(mut deps: DepsMut, env: &Env, ticket: Ticket, mut event: Event, refund: u128) -> Result<(), ContractError> {
+    // Refund guest, drawing on the organiser and then, if the event is insured, the insurance
+    // pool for any shortfall in escrow
+    let balances = ReadonlyBalances::from_storage(deps.storage);
+    let shortfall = refund - event.take_escrow(refund);
+    let organiser_balance = balances.read_account_balance(event.get_organiser())?;
+    let from_organiser = shortfall.min(organiser_balance);
+    if from_organiser < shortfall {
+        let mut config = get_config(deps.storage).load()?;
+        if !event.is_insured() || config.get_insurance_pool() < shortfall - from_organiser {
+            return Err(ContractError::InsufficientFunds { balance: organiser_balance, required: shortfall });
+        }
+        config.take_insurance(shortfall - from_organiser);
+        get_config(deps.storage).save(&config)?;
+    }
+    let mut balances = Balances::from_storage(deps.storage);
+    balances.set_account_balance(event.get_organiser(), organiser_balance - from_organiser);
+    let guest_balance = balances.read_account_balance(ticket.get_guest())?;
+    balances.set_account_balance(ticket.get_guest(), guest_balance + refund);
+    event.remove_revenue(refund);
+
+    // Store voided ticket and remove any resale listing
+    let mut listings = Listings::from_storage(deps.storage);
+    listings.remove_listing(ticket.get_id());
+    let mut tickets = Tickets::from_storage(deps.storage);
+    tickets.store_ticket(ticket.get_id(), &ticket)?;
+    let action = match ticket.get_state() {
+        TICKET_REVOKED => ACTION_REVOKED,
+        _ => ACTION_REFUNDED,
+    };
+    record_ticket_action(deps.storage, env, ticket.get_id(), action)?;
+
+    // Free up capacity in event
+    let tier = event.find_tier(Some(ticket.get_tier()))?;
+    event.ticket_refunded(tier);
+    if let Some(seat) = ticket.get_seat() {
+        let mut event_seats = EventSeats::from_storage(deps.storage);
+        event_seats.free_seat(event.get_id(), seat);
+    }
+
+    // Offer freed ticket to the waitlist, unless the event is cancelled or has already completed
+    let now = env.block.time.seconds();
+    if !event.is_cancelled() && event.get_completion_time().is_none_or(|end| now < end) {
+        fill_from_waitlist(deps.branch(), env, &mut event, tier, ticket.get_seat().map(String::from))?;
+    }
+    let mut events = Events::from_storage(deps.storage);
+    events.store_event(event.get_id(), &event)?;
+
+    // Remove ticket from guests tickets
+    let mut guests_tickets = GuestsTickets::from_storage(deps.storage);
+    guests_tickets.remove_ticket(ticket.get_guest(), ticket.get_id())?;
+
+    Ok(())
+}
+
+// Convert price steps from a message into stored price steps
+fn price_steps(steps: Vec<PriceStepMsg>) -> Vec<PriceStep> {
+    steps
+        .into_iter()
+        .map(|step| PriceStep::new(step.price.u128(), step.until_sold.map(|sold| sold.u128()), step.until_time))
+        .collect()
+}
+
+// Count the tickets a guest holds to an event
+fn count_guests_event_tickets(storage: &dyn Storage, guest: &CanonicalAddr, event_id: u128) -> StdResult<u128> {
+    let guests_tickets = ReadonlyGuestsTickets::from_storage(storage);
+    let tickets = ReadonlyTickets::from_storage(storage);
+    let mut owned = 0;
+    for ticket_id in guests_tickets.iter_tickets(guest)? {
+        let ticket_id = ticket_id?;
+        let ticket = tickets.load_ticket(ticket_id)?;
+        if ticket.get_event_id() == event_id {
+            owned += 1;
+        }
+    }
+    Ok(owned)
+}
+
+// Load an event, ensuring address is its organiser or a co-organiser
+fn load_managed_event(deps: Deps, event_id: u128, address: &CanonicalAddr) -> Result<Event, ContractError> {
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    if !event.can_manage(address) {
+        return Err(ContractError::Unauthorized { reason: "You are not an organiser of this event" });
+    }
+    Ok(event)
+}
+
+fn load_organisers_event(deps: Deps, event_id: u128, organiser: &CanonicalAddr) -> Result<Event, ContractError> {
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}),
+    };
+    if event.get_organiser() != organiser {
+        return Err(ContractError::Unauthorized { reason: "You are not the organiser of this event" });
+    }
+    Ok(event)
+}
+
+fn query_event_sold_out(deps: Deps, event_id: Uint128) -> StdResult<SoldOutResponse> {
+    let event_id_raw = event_id.u128();
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    match events.may_load_event(event_id_raw)? {
+        Some(event) => Ok(SoldOutResponse {
+            sold_out: event.is_sold_out(),
+            tiers: tier_availability(&event),
+        }),
+        None => Err(ContractError::EventNotFound {}.into()),
+    }
+}
+
+fn query_events_sold_out(deps: Deps, event_ids: Vec<Uint128>) -> StdResult<EventsSoldOutResponse> {
+    if event_ids.len() > MAX_PAGE_LIMIT as usize {
+        return Err(StdError::generic_err(format!("Cannot query more than {} events at once", MAX_PAGE_LIMIT)));
+    }
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let mut availability = vec![];
+    for event_id in event_ids {
+        let event = match events.may_load_event(event_id.u128())? {
+            Some(event) => event,
+            None => return Err(ContractError::EventNotFound {}.into()),
+        };
+        availability.push(EventAvailability {
+            event_id,
+            sold_out: event.is_sold_out(),
+            tiers: tier_availability(&event),
+        });
+    }
+    Ok(EventsSoldOutResponse { events: availability })
+}
+
+fn tier_availability(event: &Event) -> Vec<TierAvailability> {
+    event
+        .get_tiers()
+        .iter()
+        .map(|tier| TierAvailability {
+            name: tier.get_name().to_string(),
+            tickets_left: Uint128::from(tier.get_tickets_left()),
+            sold_out: tier.is_sold_out(),
+        })
+        .collect()
+}
+
+fn query_listings(deps: Deps, event_id: Uint128, start_after: Option<Uint128>, limit: Option<u32>) -> StdResult<ListingsResponse> {
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    if events.may_load_event(event_id.u128())?.is_none() {
+        return Err(ContractError::EventNotFound {}.into());
+    }
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+
+    // Check this event's tickets after the cursor for listings
+    let listings = ReadonlyListings::from_storage(deps.storage);
+    let events_tickets = ReadonlyEventsTickets::from_storage(deps.storage);
+    let mut infos = vec![];
+    for ticket_id in events_tickets.iter_tickets(event_id.u128(), start_after.map_or(0, |id| id.u128()))? {
+        let ticket_id = ticket_id?;
+        if infos.len() == limit {
+            break;
+        }
+        if let Some(listing) = listings.may_load_listing(ticket_id)? {
+            infos.push(listing_info(deps, &listing)?);
+        }
+    }
+    Ok(ListingsResponse { listings: infos })
+}
+
+fn query_seller_listings(
+    deps: Deps,
+    address: &CanonicalAddr,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    // Listed tickets are held by their seller until sold
+    let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.storage);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let listings = ReadonlyListings::from_storage(deps.storage);
+    let listings = page_ids(guests_tickets.load_tickets(address)?, start_after)
+        .into_iter()
+        .filter_map(|ticket_id| listings.may_load_listing(ticket_id).transpose())
+        .filter(|listing| listing.as_ref().map_or(true, |listing| listing.get_seller() == address))
+        .take(limit)
+        .map(|listing| listing_info(deps, &listing?))
+        .collect::<StdResult<_>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+fn listing_info(deps: Deps, listing: &Listing) -> StdResult<ListingInfo> {
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = tickets.load_ticket(listing.get_ticket_id())?;
+    Ok(ListingInfo {
+        ticket_id: Uint128::from(listing.get_ticket_id()),
+        event_id: Uint128::from(listing.get_event_id()),
+        tier: ticket.get_tier().to_string(),
+        seat: ticket.get_seat().map(|seat| seat.to_string()),
+        price: Uint128::from(listing.get_price()),
+        face_value: Uint128::from(ticket.get_price()),
+    })
+}
+
+fn query_event_info(deps: Deps, env: Env, event_id: Uint128) -> StdResult<EventInfoResponse> {
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    match events.may_load_event(event_id.u128())? {
+        Some(event) => event_info(deps, &env, &event),
+        None => Err(ContractError::EventNotFound {}.into()),
+    }
+}
+
+// Public details of events after the cursor that pass the filter, given the time (seconds)
+fn query_list_events(
+    deps: Deps,
+    env: Env,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    filter: impl Fn(&Event, u64) -> bool,
+) -> StdResult<ListEventsResponse> {
+    // Event ids are assigned sequentially from the config's counter, scan them after the cursor
+    // until the page is full
+    let config = get_config_readonly(deps.storage).load()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.map_or(0, |id| id.u128()).saturating_add(1);
+    let now = env.block.time.seconds();
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let mut infos = vec![];
+    for event_id in start..=config.get_num_events() {
+        if infos.len() == limit {
+            break;
+        }
+        let event = match events.may_load_event(event_id)? {
+            Some(event) => event,
+            None => continue,
+        };
+        if !filter(&event, now) {
+            continue;
+        }
+        infos.push(event_info(deps, &env, &event)?);
+    }
+    Ok(ListEventsResponse { events: infos })
+}
+
+// Status of an event at the given time (seconds), computed from its times and flags
+fn event_status(event: &Event, now: u64) -> EventStatus {
+    let started = matches!(event.get_start_time(), Some(start) if now >= start);
+    let finished = match event.get_completion_time() {
+        Some(end) => now >= end,
+        None => false,
+    };
+    if event.is_cancelled() {
+        EventStatus::Cancelled
+    } else if finished {
+        EventStatus::Finished
+    } else if started {
+        EventStatus::InProgress
+    } else if event.is_paused() {
+        EventStatus::Paused
+    } else if event.is_sold_out() {
+        EventStatus::SoldOut
+    } else if matches!(event.get_sale_end(), Some(end) if now >= end) {
+        EventStatus::SalesEnded
+    } else if matches!(event.get_sale_start(), Some(start) if now < start) {
+        match event.get_presale_start() {
+            Some(presale_start) if now >= presale_start => EventStatus::Presale,
+            _ => EventStatus::Upcoming,
+        }
+    } else {
+        EventStatus::OnSale
+    }
+}
+
+// Public details of an event, shared by EventInfo and ListEvents
+fn event_info(deps: Deps, env: &Env, event: &Event) -> StdResult<EventInfoResponse> {
+    let status = event_status(event, env.block.time.seconds());
+    let mut tiers = vec![];
+    for (index, tier) in event.get_tiers().iter().enumerate() {
+        tiers.push(TierInfo {
+            name: tier.get_name().to_string(),
+            price: Uint128::from(price_tickets(deps, env, event, index, 1)?[0]),
+            capacity: Uint128::from(tier.get_max_tickets()),
+            tickets_sold: Uint128::from(tier.get_tickets_sold()),
+        });
+    }
+    Ok(EventInfoResponse {
+        event_id: Uint128::from(event.get_id()),
+        organiser: deps.api.addr_humanize(event.get_organiser())?,
+        name: event.get_name().to_string(),
+        venue: event.get_venue().to_string(),
+        tags: event.get_tags().clone(),
+        status,
+        price: tiers[0].price,
+        capacity: Uint128::from(event.get_max_tickets()),
+        tickets_sold: Uint128::from(event.get_tickets_sold()),
+        tiers,
+        sale_start: event.get_sale_start(),
+        sale_end: event.get_sale_end(),
+        presale_start: event.get_presale_start(),
+        start_time: event.get_start_time(),
+        end_time: event.get_end_time(),
+        refund_deadline: event.get_refund_deadline(),
+        seated: event.get_seats().is_some(),
+        max_per_guest: Uint128::from(event.get_max_per_guest()),
+        insured: event.is_insured(),
+        stable_price: event.is_stable_price(),
+        installments: event.get_installment_plan().is_some(),
+        share_guests: event.is_share_guests(),
+        organiser_profile: ReadonlyOrganiserProfiles::from_storage(deps.storage)
+            .may_load_profile(event.get_organiser())?
+            .map(|profile| organiser_profile_response(&profile)),
+    })
+}
+
+fn query_organiser_profile(deps: Deps, organiser: Addr) -> StdResult<OrganiserProfileResponse> {
+    let organiser = deps.api.addr_canonicalize(organiser.as_str())?;
+    let profiles = ReadonlyOrganiserProfiles::from_storage(deps.storage);
+    match profiles.may_load_profile(&organiser)? {
+        Some(profile) => Ok(organiser_profile_response(&profile)),
+        None => Err(StdError::generic_err("Organiser has not set a profile")),
+    }
+}
+
+fn organiser_profile_response(profile: &OrganiserProfile) -> OrganiserProfileResponse {
+    OrganiserProfileResponse {
+        display_name: profile.get_display_name().to_string(),
+        contact_uri: profile.get_contact_uri().to_string(),
+    }
+}
+
+fn query_contract_status(deps: Deps) -> StdResult<ContractStatusResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    let level = match config.get_status() {
+        STATUS_STOP_PURCHASES => ContractStatusLevel::StopPurchases,
+        STATUS_STOP_ALL => ContractStatusLevel::StopAll,
+        _ => ContractStatusLevel::NormalRun,
+    };
+    Ok(ContractStatusResponse { level })
+}
+
+fn query_insurance_pool(deps: Deps) -> StdResult<InsurancePoolResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    Ok(InsurancePoolResponse {
+        balance: Uint128::from(config.get_insurance_pool()),
+    })
+}
+
+fn query_staking(deps: Deps, env: Env) -> StdResult<StakingResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    let staking = match config.get_staking() {
+        Some(staking) => staking,
+        None => return Err(StdError::generic_err("Staking is not configured")),
+    };
+    let now = env.block.time.seconds();
+    let denom = config.get_primary_denom();
+    let reserve = ReadonlyReserves::from_storage(deps.storage).read_reserve(denom)?;
+    Ok(StakingResponse {
+        validator: staking.get_validator().to_string(),
+        stake_bps: staking.get_stake_bps(),
+        treasury: deps.api.addr_humanize(staking.get_treasury())?,
+        staked: Uint128::from(staking.get_staked()),
+        unbonding: Uint128::from(staking.get_unbonding(now)),
+        liquid: Uint128::from(config.liquid_reserve(denom, reserve, now)),
+    })
+}
+
+fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    Ok(ContractInfoResponse {
+        name: CONTRACT_NAME.to_string(),
+        version: CONTRACT_VERSION.to_string(),
+        owner: deps.api.addr_humanize(config.get_owner())?,
+        fee: query_fee_config(deps)?,
+        denoms: config.get_denoms().clone(),
+        num_events: Uint128::from(config.get_num_events()),
+        num_tickets: Uint128::from(config.get_num_tickets()),
+        num_passes: Uint128::from(config.get_num_passes()),
+        num_group_orders: Uint128::from(config.get_num_group_orders()),
+    })
+}
+
+fn query_fee_config(deps: Deps) -> StdResult<FeeConfigResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    Ok(FeeConfigResponse {
+        rate_bps: config.get_fee_bps(),
+        recipient: deps.api.addr_humanize(config.get_fee_recipient())?,
+    })
+}
+
+fn query_limits(deps: Deps) -> StdResult<LimitsResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    Ok(LimitsResponse {
+        min_deposit: Uint128::from(config.get_min_deposit()),
+        min_withdrawal: Uint128::from(config.get_min_withdrawal()),
+        withdrawal_cap: config.get_withdrawal_cap().map(Uint128::from),
+    })
+}
+
+fn query_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    // Native tokens held for depositors are everything held in accepted denoms besides organiser
+    // bonds, plus whatever is delegated or unbonding, and the sSCRT held
+    let config = get_config_readonly(deps.storage).load()?;
+    let now = env.block.time.seconds();
+    let mut held: u128 = 0;
+    for denom in config.get_denoms() {
+        held += query_held(deps, &env, &config, denom)?;
+    }
+    if let Some(staking) = config.get_staking() {
+        held += staking.get_locked(now);
+    }
+    held += config.get_sscrt_reserve();
+
+    let backing = config.get_exchange_rate(now).to_tokens(held)?;
+    let total_supply = config.get_total_supply();
+    Ok(SolvencyResponse {
+        total_supply: Uint128::from(total_supply),
+        backing: Uint128::from(backing),
+        ratio: match total_supply {
+            0 => None,
+            _ => Some(Decimal::from_ratio(backing, total_supply)),
+        },
+    })
+}
+
+fn query_exchange_rate(deps: Deps, env: Env) -> StdResult<ExchangeRateResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    let now = env.block.time.seconds();
+    let rate = config.get_exchange_rate(now);
+    Ok(ExchangeRateResponse {
+        rate: Uint128::from(rate.get_rate()),
+        decimals: rate.get_decimals(),
+        pending: config.get_pending_exchange_rate(now).map(|(rate, effective_at)| PendingExchangeRate {
+            rate: Uint128::from(rate.get_rate()),
+            decimals: rate.get_decimals(),
+            effective_at,
+        }),
+    })
+}
+
+fn query_payouts(deps: Deps, event_id: Uint128) -> StdResult<PayoutsResponse> {
+    let payouts = ReadonlyPayouts::from_storage(deps.storage);
+    Ok(PayoutsResponse {
+        payouts: payouts
+            .load_payouts(event_id.u128())?
+            .iter()
+            .map(|payout| PayoutInfo {
+                amount: Uint128::from(payout.get_amount()),
+                fee: Uint128::from(payout.get_fee()),
+                time: payout.get_time(),
+            })
+            .collect(),
+    })
+}
+
+fn query_ticket_code(deps: Deps, env: Env, ticket_id: Uint128, signature: String) -> StdResult<TicketCodeResponse> {
+    // Ensure request is signed by the ticket's key for the current window
+    let ticket_id_raw = ticket_id.u128();
+    let window = env.block.time.seconds() / CODE_WINDOW;
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}.into()),
+    };
+    check_ticket_key_signature(deps, &ticket, &code_request_hash(ticket_id_raw, window), &signature)?;
+
+    Ok(TicketCodeResponse {
+        code: format!("{:0width$}", ticket.code_at(window), width = CODE_DIGITS as usize),
+        valid_until: (window + 1) * CODE_WINDOW,
+    })
+}
+
+// Ensure a hash was signed by the ticket's key, which must be able to sign
+fn check_ticket_key_signature(deps: Deps, ticket: &Ticket, message_hash: &[u8; 32], signature: &str) -> StdResult<()> {
+    let signature_raw = match hex::decode(signature) {
+        Ok(signature) => signature,
+        Err(_) => return Err(StdError::generic_err("Signature is not a valid hex string")),
+    };
+    let key = ticket.get_pk().get_key();
+    let valid = match ticket.get_pk().get_key_type() {
+        KEY_SECP256K1 => deps.api.secp256k1_verify(message_hash, &signature_raw, key).unwrap_or(false),
+        KEY_ED25519 => deps.api.ed25519_verify(message_hash, &signature_raw, key).unwrap_or(false),
+        _ => return Err(StdError::generic_err("This query requires a secp256k1 or ed25519 ticket key")),
+    };
+    if !valid {
+        return Err(StdError::generic_err("Signature does not match the ticket key"));
+    }
+    Ok(())
+}
+
+fn query_qr_payload(
+    deps: Deps,
+    env: Env,
+    ticket_id: Uint128,
+    expiry: u64,
+    signature: String,
+) -> StdResult<QrPayloadResponse> {
+    // Ensure expiry is in the near future
+    let now = env.block.time.seconds();
+    if expiry <= now || expiry > now.saturating_add(QR_MAX_TTL) {
+        return Err(StdError::generic_err(format!(
+            "Expiry must be within {} seconds from now",
+            QR_MAX_TTL
+        )));
+    }
+
+    // Ensure request is signed by the ticket's key
+    let ticket_id_raw = ticket_id.u128();
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}.into()),
+    };
+    check_ticket_key_signature(deps, &ticket, &qr_request_hash(ticket_id_raw, expiry), &signature)?;
+
+    // Build and authenticate payload
+    let config = get_config_readonly(deps.storage).load()?;
+    let mut payload = Vec::with_capacity(QR_PAYLOAD_LEN);
+    payload.extend_from_slice(&ticket_id_raw.to_be_bytes());
+    payload.extend_from_slice(&ticket.get_event_id().to_be_bytes());
+    payload.extend_from_slice(&expiry.to_be_bytes());
+    payload.extend_from_slice(&qr_commitment(config.get_payload_key(), &ticket));
+    let mac = qr_payload_mac(config.get_payload_key(), &payload);
+    payload.extend_from_slice(&mac);
+
+    Ok(QrPayloadResponse {
+        payload: Binary::from(payload).to_base64(),
+        expiry,
+    })
+}
+
+fn query_remaining_seats(deps: Deps, event_id: Uint128) -> StdResult<SeatsResponse> {
+    let event_id_raw = event_id.u128();
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id_raw)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}.into()),
+    };
+    let seats = match event.get_seats() {
+        Some(seats) => seats,
+        None => return Err(StdError::generic_err("This event does not have reserved seating")),
+    };
+
+    // Filter out taken seats
+    let event_seats = ReadonlyEventSeats::from_storage(deps.storage);
+    let mut remaining = vec![];
+    for seat in seats {
+        if event_seats.may_load_seat(event_id_raw, seat)?.is_none() {
+            remaining.push(seat.clone());
+        }
+    }
+    Ok(SeatsResponse { seats: remaining })
+}
+
+// Ensure a key is the address' viewing key, returning the canonical address
+fn check_viewing_key(deps: Deps, address: &Addr, key: &str) -> StdResult<CanonicalAddr> {
+    let address_canon = deps.api.addr_canonicalize(address.as_str())?;
+    let viewing_keys = ReadonlyViewingKeys::from_storage(deps.storage);
+    if !viewing_keys.check_viewing_key(&address_canon, key) {
+        return Err(StdError::generic_err("Wrong viewing key for this address or viewing key not set"));
+    }
+    Ok(address_canon)
+}
+
+fn query_with_permit(deps: Deps, env: Env, permit: Permit, query: QueryWithPermit) -> StdResult<QueryResponse> {
+    match query {
+        QueryWithPermit::Balance {} => {
+            let address = check_permit(deps, &env, &permit, Permission::Balance)?;
+            to_binary(&query_balance(deps, &address)?)
+        }
+        QueryWithPermit::Balances { addresses } => {
+            let address = check_permit(deps, &env, &permit, Permission::Balance)?;
+            to_binary(&query_balances(deps, addresses, &address)?)
+        }
+        QueryWithPermit::Allowance { owner, spender } => {
+            let address = check_permit(deps, &env, &permit, Permission::Allowance)?;
+            to_binary(&query_allowance(deps, owner, spender, &address)?)
+        }
+        QueryWithPermit::AllowancesGiven { start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Allowance)?;
+            to_binary(&query_allowances(deps, &address, start_after, limit, true)?)
+        }
+        QueryWithPermit::AllowancesReceived { start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Allowance)?;
+            to_binary(&query_allowances(deps, &address, start_after, limit, false)?)
+        }
+        QueryWithPermit::Events { status, start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_events(deps, env, &address, status, start_after, limit)?)
+        }
+        QueryWithPermit::Tickets { start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_tickets(deps, &address, start_after, limit)?)
+        }
+        QueryWithPermit::Passes {} => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_passes(deps, &address)?)
+        }
+        QueryWithPermit::SellerListings { start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_seller_listings(deps, &address, start_after, limit)?)
+        }
+        QueryWithPermit::PrivateMetadata { event_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_private_metadata(deps, event_id, &address)?)
+        }
+        QueryWithPermit::EventRevenue { event_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_event_revenue(deps, event_id, &address)?)
+        }
+        QueryWithPermit::AttendanceProof { ticket_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_attendance_proof(deps, ticket_id, &address)?)
+        }
+        QueryWithPermit::OfflineCode { ticket_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_offline_code(deps, ticket_id, &address)?)
+        }
+        QueryWithPermit::Installments { ticket_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_installments(deps, ticket_id, &address)?)
+        }
+        QueryWithPermit::CheckInStats { event_id, bucket, start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_check_in_stats(deps, event_id, bucket, start_after, limit, &address)?)
+        }
+        QueryWithPermit::Attendees { event_id, start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_attendees(deps, event_id, start_after, limit, &address)?)
+        }
+        QueryWithPermit::TicketValidity { ticket_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::TicketValidity)?;
+            to_binary(&query_ticket_validity(deps, ticket_id, &address)?)
+        }
+        QueryWithPermit::WaitlistPosition { event_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_waitlist_position(deps, event_id, &address)?)
+        }
+        QueryWithPermit::Waitlist { event_id, start_after, limit } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_waitlist(deps, event_id, start_after, limit, &address)?)
+        }
+        QueryWithPermit::CheckInReceipts { ticket_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_check_in_receipts(deps, ticket_id, &address)?)
+        }
+        QueryWithPermit::TicketHistory { ticket_id } => {
+            let address = check_permit(deps, &env, &permit, Permission::Owner)?;
+            to_binary(&query_ticket_history(deps, ticket_id, &address)?)
+        }
+    }
+}
+
+// Ensure a permit is signed for this contract, grants the permission and has not been revoked,
+// returning the signer's canonical address
+fn check_permit(deps: Deps, env: &Env, permit: &Permit, permission: Permission) -> StdResult<CanonicalAddr> {
+    let params = &permit.params;
+    if !params.allowed_tokens.contains(&env.contract.address.to_string()) {
+        return Err(StdError::generic_err("Permit is not valid for this contract"));
+    }
+    if params.chain_id != env.block.chain_id {
+        return Err(StdError::generic_err("Permit is not valid for this chain"));
+    }
+    if !params.permissions.contains(&permission) {
+        return Err(StdError::generic_err("Permit does not grant permission for this query"));
+    }
+
+    // Verify signature over the sign document, deriving the signer's address from their key
+    let message_hash = Sha256::digest(to_binary(&permit_sign_doc(params))?.as_slice());
+    let pubkey = permit.signature.pub_key.value.as_slice();
+    let signature = permit.signature.signature.as_slice();
+    if !deps.api.secp256k1_verify(&message_hash, signature, pubkey).unwrap_or(false) {
+        return Err(StdError::generic_err("Failed to verify permit signature"));
+    }
+    let address = match bech32::encode(ADDRESS_PREFIX, Ripemd160::digest(Sha256::digest(pubkey)).to_base32(), Variant::Bech32) {
+        Ok(address) => address,
+        Err(_) => return Err(StdError::generic_err("Failed to derive permit signer's address")),
+    };
+    let address_canon = deps.api.addr_canonicalize(&address)?;
+
+    // Ensure signer has not revoked permit
+    let revoked_permits = ReadonlyRevokedPermits::from_storage(deps.storage);
+    if revoked_permits.is_revoked(&address_canon, &params.permit_name) {
+        return Err(StdError::generic_err(format!("Permit {} was revoked", params.permit_name)));
+    }
+    Ok(address_canon)
+}
+
+// Amino sign document wallets sign for a permit
+fn permit_sign_doc(params: &PermitParams) -> PermitSignDoc {
+    PermitSignDoc {
+        account_number: "0".to_string(),
+        chain_id: params.chain_id.clone(),
+        fee: PermitFee {
+            amount: vec![PermitCoin { amount: "0".to_string(), denom: "uscrt".to_string() }],
+            gas: "1".to_string(),
+        },
+        memo: String::new(),
+        msgs: vec![PermitSignMsg { r#type: "query_permit".to_string(), value: params.clone() }],
+        sequence: "0".to_string(),
+    }
+}
+
+fn query_event_revenue(deps: Deps, event_id: Uint128, address: &CanonicalAddr) -> StdResult<EventRevenueResponse> {
+    let event = load_managed_event(deps, event_id.u128(), address)?;
+    Ok(EventRevenueResponse {
+        revenue: Uint128::from(event.get_revenue()),
+        tips: Uint128::from(event.get_tips()),
+    })
+}
+
+fn query_check_in_stats(
+    deps: Deps,
+    event_id: Uint128,
+    bucket: Option<u64>,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    address: &CanonicalAddr,
+) -> StdResult<CheckInStatsResponse> {
+    let event = load_managed_event(deps, event_id.u128(), address)?;
+    let bucket = bucket.unwrap_or(DEFAULT_STATS_BUCKET);
+    if bucket < MIN_STATS_BUCKET {
+        return Err(StdError::generic_err(format!("Buckets must be at least {} seconds", MIN_STATS_BUCKET)));
+    }
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT) as usize;
+
+    // Count check-ins over a page of this event's tickets after the cursor from ticket histories,
+    // which record every way of checking in
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let events_tickets = ReadonlyEventsTickets::from_storage(deps.storage);
+    let histories = ReadonlyTicketHistories::from_storage(deps.storage);
+    let mut checked_in: u128 = 0;
+    let mut buckets: BTreeMap<u64, u128> = BTreeMap::new();
+    let mut counted = 0;
+    let mut next_start_after = None;
+    let mut ticket_ids = events_tickets.iter_tickets(event.get_id(), start_after.map_or(0, |id| id.u128()))?.peekable();
+    while let Some(ticket_id) = ticket_ids.next() {
+        let ticket_id = ticket_id?;
+        counted += 1;
+        if counted == limit && ticket_ids.peek().is_some() {
+            next_start_after = Some(Uint128::from(ticket_id));
+        }
+        let ticket = tickets.load_ticket(ticket_id)?;
+        if ticket.get_state() == TICKET_USED || !ticket.get_sessions_used().is_empty() {
+            checked_in += 1;
+        }
+        for record in histories.load_history(ticket_id)? {
+            if record.get_action() == ACTION_CHECKED_IN {
+                *buckets.entry(record.get_time() - record.get_time() % bucket).or_insert(0) += 1;
+            }
+        }
+        if counted == limit {
+            break;
+        }
+    }
+
+    Ok(CheckInStatsResponse {
+        tickets_sold: Uint128::from(event.get_tickets_sold()),
+        checked_in: Uint128::from(checked_in),
+        check_ins: Uint128::from(buckets.values().sum::<u128>()),
+        buckets: buckets
+            .into_iter()
+            .map(|(start, check_ins)| CheckInBucket { start, check_ins: Uint128::from(check_ins) })
+            .collect(),
+        next_start_after,
+    })
+}
+
+fn query_attendees(
+    deps: Deps,
+    event_id: Uint128,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+    address: &CanonicalAddr,
+) -> StdResult<AttendeesResponse> {
+    let event = load_managed_event(deps, event_id.u128(), address)?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+
+    // Page through this event's tickets after the cursor
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let events_tickets = ReadonlyEventsTickets::from_storage(deps.storage);
+    let mut attendees = vec![];
+    for ticket_id in events_tickets.iter_tickets(event.get_id(), start_after.map_or(0, |id| id.u128()))? {
+        let ticket_id = ticket_id?;
+        if attendees.len() == limit {
+            break;
+        }
+        let ticket = tickets.load_ticket(ticket_id)?;
+        let guest = match event.is_share_guests() {
+            true => Some(deps.api.addr_humanize(ticket.get_guest())?),
+            false => None,
+        };
+        attendees.push(AttendeeInfo {
+            ticket_id: Uint128::from(ticket_id),
+            guest,
+            tier: ticket.get_tier().to_string(),
+            seat: ticket.get_seat().map(|seat| seat.to_string()),
+            state: ticket_state(ticket.get_state()),
+            checked_in: ticket.get_state() == TICKET_USED || !ticket.get_sessions_used().is_empty(),
+            sessions_used: ticket.get_sessions_used().iter().map(|session| *session as u32).collect(),
+        });
+    }
+    Ok(AttendeesResponse { attendees })
+}
+
+// Answers only yes or no, so partners learn nothing about the ticket or its holder
+fn query_ticket_validity(deps: Deps, ticket_id: Uint128, address: &CanonicalAddr) -> StdResult<TicketValidityResponse> {
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) if ticket.get_guest() == address => ticket,
+        _ => return Ok(TicketValidityResponse { valid: false }),
+    };
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let cancelled = events.may_load_event(ticket.get_event_id())?.is_none_or(|event| event.is_cancelled());
+    let valid = !cancelled && !matches!(ticket.get_state(), TICKET_REVOKED | TICKET_REFUNDED | TICKET_RESERVED);
+    Ok(TicketValidityResponse { valid })
+}
+
+fn query_waitlist_position(deps: Deps, event_id: Uint128, address: &CanonicalAddr) -> StdResult<WaitlistPositionResponse> {
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    if events.may_load_event(event_id.u128())?.is_none() {
+        return Err(ContractError::EventNotFound {}.into());
+    }
+    let waitlist = ReadonlyWaitlists::from_storage(deps.storage).load_waitlist(event_id.u128())?;
+    let position = waitlist.iter().position(|entry| entry.get_guest() == address);
+    Ok(WaitlistPositionResponse {
+        position: position.map(|index| index as u32 + 1),
+        depth: waitlist.len() as u32,
+    })
+}
+
+fn query_waitlist(
+    deps: Deps,
+    event_id: Uint128,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+    address: &CanonicalAddr,
+) -> StdResult<WaitlistResponse> {
+    let event = load_managed_event(deps, event_id.u128(), address)?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+
+    // Page through positions after the cursor
+    let waitlist = ReadonlyWaitlists::from_storage(deps.storage).load_waitlist(event.get_id())?;
+    let start = start_after.unwrap_or(0) as usize;
+    let mut entries = vec![];
+    for (index, entry) in waitlist.iter().enumerate().skip(start).take(limit) {
+        let guest = match event.is_share_guests() {
+            true => Some(deps.api.addr_humanize(entry.get_guest())?),
+            false => None,
+        };
+        entries.push(WaitlistEntryInfo { position: index as u32 + 1, guest });
+    }
+    Ok(WaitlistResponse { entries, depth: waitlist.len() as u32 })
+}
+
+fn query_private_metadata(deps: Deps, event_id: Uint128, address: &CanonicalAddr) -> StdResult<PrivateMetadataResponse> {
+    // Ensure event exists
+    let event_id_raw = event_id.u128();
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = match events.may_load_event(event_id_raw)? {
+        Some(event) => event,
+        None => return Err(ContractError::EventNotFound {}.into()),
+    };
+
+    // Ensure address is the organiser or holds a ticket that has not been revoked or refunded
+    let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.storage);
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let mut holds_ticket = false;
+    for ticket_id in guests_tickets.iter_tickets(address)? {
+        let ticket_id = ticket_id?;
+        let ticket = tickets.load_ticket(ticket_id)?;
+        if ticket.get_event_id() == event_id_raw
+            && ticket.get_state() != TICKET_REVOKED
+            && ticket.get_state() != TICKET_REFUNDED
+        {
+            holds_ticket = true;
+            break;
+        }
+    }
+    if !holds_ticket && event.get_organiser() != address {
+        return Err(StdError::generic_err("You do not hold a ticket to this event"));
+    }
+
+    let private_metadata = ReadonlyPrivateMetadata::from_storage(deps.storage);
+    Ok(PrivateMetadataResponse { metadata: private_metadata.may_load_metadata(event_id_raw)? })
+}
+
+fn query_attendance_proof(deps: Deps, ticket_id: Uint128, address: &CanonicalAddr) -> StdResult<AttendanceProofResponse> {
+    // Ensure ticket exists, belongs to address and has been checked in
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) if ticket.get_guest() == address => ticket,
+        _ => return Err(StdError::generic_err("You do not own this ticket")),
+    };
+    if ticket.get_state() != TICKET_USED && ticket.get_sessions_used().is_empty() {
+        return Err(StdError::generic_err("Ticket has not been checked in"));
+    }
+
+    // Attest to a nullifier unique to the ticket, which reveals neither it nor the guest
+    let config = get_config_readonly(deps.storage).load()?;
+    let mut hasher = Sha256::new_with_prefix(config.get_payload_key());
+    hasher.update(b"attendance nullifier");
+    hasher.update(ticket.get_id().to_be_bytes());
+    let nullifier: [u8; 32] = hasher.finalize().into();
+    let attestation = attendance_attestation(config.get_payload_key(), ticket.get_event_id(), &nullifier);
+
+    Ok(AttendanceProofResponse {
+        event_id: Uint128::from(ticket.get_event_id()),
+        proof: hex::encode([nullifier, attestation].concat()),
+    })
+}
+
+fn query_offline_code(deps: Deps, ticket_id: Uint128, address: &CanonicalAddr) -> StdResult<OfflineCodeResponse> {
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) if ticket.get_guest() == address => ticket,
+        _ => return Err(StdError::generic_err("You do not own this ticket")),
+    };
+    let code = match ticket.get_offline_code() {
+        0 => None,
+        code => Some(format!("{:016X}", code)),
+    };
+    Ok(OfflineCodeResponse { code })
+}
+
+fn query_installments(deps: Deps, ticket_id: Uint128, address: &CanonicalAddr) -> StdResult<InstallmentsResponse> {
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id.u128())? {
+        Some(ticket) if ticket.get_guest() == address => ticket,
+        _ => return Err(StdError::generic_err("You do not own this ticket")),
+    };
+    let installments = ticket.get_installments();
+    Ok(InstallmentsResponse {
+        paid: Uint128::from(ticket.get_price()),
+        unpaid: Uint128::from(installments.map_or(0, |installments| installments.get_unpaid())),
+        next_amount: Uint128::from(installments.map_or(0, |installments| installments.get_next_amount())),
+        next_due: installments.map(|installments| installments.get_next_due()),
+    })
+}
+
+fn query_check_in_receipts(deps: Deps, ticket_id: Uint128, address: &CanonicalAddr) -> StdResult<CheckInReceiptsResponse> {
+    // Ensure ticket exists and address is its guest or manages its event
+    let ticket_id_raw = ticket_id.u128();
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}.into()),
+    };
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if ticket.get_guest() != address && !event.can_manage(address) {
+        return Err(StdError::generic_err("You do not own this ticket or manage its event"));
+    }
+
+    let check_ins = ReadonlyCheckIns::from_storage(deps.storage);
+    let receipts = check_ins
+        .load_check_ins(ticket_id_raw)?
+        .iter()
+        .map(|check_in| check_in_receipt(deps, check_in))
+        .collect::<StdResult<Vec<CheckInReceipt>>>()?;
+    Ok(CheckInReceiptsResponse { receipts })
+}
+
+fn query_ticket_history(deps: Deps, ticket_id: Uint128, address: &CanonicalAddr) -> StdResult<TicketHistoryResponse> {
+    // Ensure ticket exists and address is its guest or manages its event
+    let ticket_id_raw = ticket_id.u128();
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    let ticket = match tickets.may_load_ticket(ticket_id_raw)? {
+        Some(ticket) => ticket,
+        None => return Err(ContractError::TicketNotFound {}.into()),
+    };
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let event = events.load_event(ticket.get_event_id())?;
+    if ticket.get_guest() != address && !event.can_manage(address) {
+        return Err(StdError::generic_err("You do not own this ticket or manage its event"));
+    }
+
+    let histories = ReadonlyTicketHistories::from_storage(deps.storage);
+    let history = histories
+        .load_history(ticket_id_raw)?
+        .iter()
+        .map(|record| TicketHistoryEntry {
+            action: match record.get_action() {
+                ACTION_PURCHASED => TicketAction::Purchased,
+                ACTION_CHALLENGED => TicketAction::Challenged,
+                ACTION_CHECKED_IN => TicketAction::CheckedIn,
+                ACTION_TRANSFERRED => TicketAction::Transferred,
+                ACTION_REVOKED => TicketAction::Revoked,
+                _ => TicketAction::Refunded,
+            },
+            height: record.get_height(),
+            time: record.get_time(),
+        })
+        .collect();
+    Ok(TicketHistoryResponse { history })
+}
+
+fn query_verify_check_in_receipt(deps: Deps, receipt: CheckInReceipt) -> StdResult<VerifyCheckInReceiptResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    let verifier = deps.api.addr_canonicalize(receipt.verifier.as_str())?;
+    let mac = check_in_mac(
+        config.get_payload_key(),
+        receipt.ticket_id.u128(),
+        receipt.event_id.u128(),
+        receipt.time,
+        &verifier,
+    );
+    Ok(VerifyCheckInReceiptResponse { valid: hex::encode(mac) == receipt.receipt.to_lowercase() })
+}
+
+fn query_verify_attendance(deps: Deps, event_id: Uint128, proof: String) -> StdResult<VerifyAttendanceResponse> {
+    let proof_raw = match hex::decode(&proof) {
+        Ok(proof) if proof.len() == 64 => proof,
+        _ => return Err(StdError::generic_err("Proof is not a valid 64 byte hex string")),
+    };
+    let (nullifier, attestation) = proof_raw.split_at(32);
+    let config = get_config_readonly(deps.storage).load()?;
+    let expected = attendance_attestation(config.get_payload_key(), event_id.u128(), nullifier);
+    Ok(VerifyAttendanceResponse { attended: expected == attestation })
+}
+
+// Contract's attestation that the holder of a nullifier attended an event
+fn attendance_attestation(payload_key: &[u8; 32], event_id: u128, nullifier: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new_with_prefix(payload_key);
+    hasher.update(b"attendance");
+    hasher.update(event_id.to_be_bytes());
+    hasher.update(nullifier);
+    hasher.finalize().into()
+}
+
+fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
+    let config = get_config_readonly(deps.storage).load()?;
+    Ok(TokenInfoResponse {
+        token_info: TokenInfo {
+            name: TOKEN_NAME.to_string(),
+            symbol: TOKEN_SYMBOL.to_string(),
+            decimals: TOKEN_DECIMALS,
+            total_supply: Some(Uint128::from(config.get_total_supply())),
+        },
+    })
+}
+
+fn query_balance(deps: Deps, address: &CanonicalAddr) -> StdResult<BalanceResponse> {
+    let balances = ReadonlyBalances::from_storage(deps.storage);
+    Ok(BalanceResponse {
+        balance: Uint128::from(balances.read_account_balance(address)?),
+    })
+}
+
+fn query_balances(deps: Deps, addresses: Vec<Addr>, address: &CanonicalAddr) -> StdResult<BalancesResponse> {
+    if addresses.len() > MAX_PAGE_LIMIT as usize {
+        return Err(StdError::generic_err(format!("Cannot query more than {} balances at once", MAX_PAGE_LIMIT)));
+    }
+    let config = get_config_readonly(deps.storage).load()?;
+    let balances = ReadonlyBalances::from_storage(deps.storage);
+    let mut response = vec![];
+    for account in addresses {
+        let account_canon = deps.api.addr_canonicalize(account.as_str())?;
+        if account_canon != *address && config.get_owner() != address {
+            return Err(StdError::generic_err("Only the contract owner can query other accounts' balances"));
+        }
+        response.push(AccountBalance {
+            address: account,
+            balance: Uint128::from(balances.read_account_balance(&account_canon)?),
+        });
+    }
+    Ok(BalancesResponse { balances: response })
+}
+
+fn query_allowance(deps: Deps, owner: Addr, spender: Addr, address: &CanonicalAddr) -> StdResult<AllowanceResponse> {
+    let owner_canon = deps.api.addr_canonicalize(owner.as_str())?;
+    let spender_canon = deps.api.addr_canonicalize(spender.as_str())?;
+    if owner_canon != *address && spender_canon != *address {
+        return Err(StdError::generic_err("Only the owner or spender can query an allowance"));
+    }
+    let allowance = ReadonlyAllowances::from_storage(deps.storage).load_allowance(&owner_canon, &spender_canon)?;
+    Ok(AllowanceResponse {
+        owner,
+        spender,
+        allowance: Uint128::from(allowance.get_amount()),
+        expiration: allowance.get_expiration(),
+    })
+}
+
+// Allowances address has given, or received, ordered by the other party's address
+fn query_allowances(
+    deps: Deps,
+    address: &CanonicalAddr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    given: bool,
+) -> StdResult<AllowancesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let indexes = ReadonlyAllowanceIndexes::from_storage(deps.storage);
+    let counterparts = match given {
+        true => indexes.load_spenders(address)?,
+        false => indexes.load_owners(address)?,
+    };
+    let mut counterparts = counterparts
+        .iter()
+        .map(|counterpart| Ok((deps.api.addr_humanize(counterpart)?, counterpart)))
+        .collect::<StdResult<Vec<(Addr, &CanonicalAddr)>>>()?;
+    counterparts.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    counterparts.retain(|(counterpart, _)| start_after.as_ref().is_none_or(|start| counterpart > start));
+
+    let address_human = deps.api.addr_humanize(address)?;
+    let allowances = ReadonlyAllowances::from_storage(deps.storage);
+    let mut response = vec![];
+    for (counterpart, counterpart_canon) in counterparts.into_iter().take(limit) {
+        let (owner, spender, allowance) = match given {
+            true => (address_human.clone(), counterpart, allowances.load_allowance(address, counterpart_canon)?),
+            false => (counterpart, address_human.clone(), allowances.load_allowance(counterpart_canon, address)?),
+        };
+        response.push(AllowanceResponse {
+            owner,
+            spender,
+            allowance: Uint128::from(allowance.get_amount()),
+            expiration: allowance.get_expiration(),
+        });
+    }
+    Ok(AllowancesResponse { allowances: response })
+}
+
+fn query_events(
+    deps: Deps,
+    env: Env,
+    address: &CanonicalAddr,
+    status: Option<EventStatus>,
+    start_after: Option<Uint128>,
+    limit: Option<u32>,
+) -> StdResult<EventsResponse> {
+    let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.storage);
+    let this_organisers_events = organisers_events.iter_events(address, start_after.map_or(0, |id| id.u128()))?;
+    let events = ReadonlyEvents::from_storage(deps.storage);
+    let now = env.block.time.seconds();
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+
+    let mut events_vec = vec![];
+    for event_id in this_organisers_events {
+        let event_id = event_id?;
+        if events_vec.len() == limit {
+            break;
+        }
+
+        let event = events.load_event(event_id)?;
+        let event_status = event_status(&event, now);
+        if status.is_some_and(|status| event_status != status) {
+            continue;
+        }
+        events_vec.push(EventSummary {
+            event_id: Uint128::from(event_id),
+            name: event.get_name().to_string(),
+            status: event_status,
+            tickets_left: Uint128::from(event.get_tickets_left()),
+        });
+    }
+    Ok(EventsResponse { events: events_vec })
+}
+
+fn query_pass(deps: Deps, pass_id: Uint128) -> StdResult<PassResponse> {
+    let passes = ReadonlyPasses::from_storage(deps.storage);
+    let pass = match passes.may_load_pass(pass_id.u128())? {
+        Some(pass) => pass,
+        None => return Err(ContractError::PassNotFound {}.into()),
+    };
+    Ok(PassResponse {
+        pass_id,
+        organiser: deps.api.addr_humanize(pass.get_organiser())?,
+        price: Uint128::from(pass.get_price()),
+        event_ids: pass.get_event_ids().iter().map(|event_id| Uint128::from(*event_id)).collect(),
+        period_start: pass.get_period().map(|(start, _)| start),
+        period_end: pass.get_period().map(|(_, end)| end),
+        max_passes: Uint128::from(pass.get_max_passes()),
+        passes_sold: Uint128::from(pass.get_passes_sold()),
+    })
+}
+
+fn query_group_order(deps: Deps, order_id: Uint128) -> StdResult<GroupOrderResponse> {
+    let group_orders = ReadonlyGroupOrders::from_storage(deps.storage);
+    let order = match group_orders.may_load_group_order(order_id.u128())? {
+        Some(order) => order,
+        None => return Err(ContractError::GroupOrderNotFound {}.into()),
+    };
+    Ok(GroupOrderResponse {
+        order_id,
+        event_id: Uint128::from(order.get_event_id()),
+        quantity: Uint128::from(order.get_holders().len() as u128),
+        target: Uint128::from(order.get_target()),
+        funded: Uint128::from(order.get_funded()),
+        deadline: order.get_deadline(),
+    })
+}
+
+fn query_passes(deps: Deps, address: &CanonicalAddr) -> StdResult<PassesResponse> {
+    let guests_passes = ReadonlyGuestsPasses::from_storage(deps.storage);
+    Ok(PassesResponse {
+        passes: guests_passes
+            .load_passes(address)?
+            .iter()
+            .map(|holding| PassHoldingInfo {
+                pass_id: Uint128::from(holding.get_pass_id()),
+                redeemed: holding.get_redeemed().iter().map(|event_id| Uint128::from(*event_id)).collect(),
+            })
+            .collect(),
+    })
+}
+
+fn query_tickets(deps: Deps, address: &CanonicalAddr, start_after: Option<Uint128>, limit: Option<u32>) -> StdResult<TicketsResponse> {
+    let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.storage);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let this_guests_tickets = page_ids(guests_tickets.load_tickets(address)?, start_after);
+    let tickets = ReadonlyTickets::from_storage(deps.storage);
+
+    let mut tickets_vec = vec![];
+    for ticket_id in this_guests_tickets.into_iter().take(limit) {
+        let ticket = tickets.load_ticket(ticket_id)?;
+        tickets_vec.push(TicketInfo {
+            ticket_id: Uint128::from(ticket_id),
+            event_id: Uint128::from(ticket.get_event_id()),
+            tier: ticket.get_tier().to_string(),
+            seat: ticket.get_seat().map(|seat| seat.to_string()),
+            state: ticket_state(ticket.get_state()),
+        });
+    }
+    Ok(TicketsResponse { tickets: tickets_vec })
+}
+
+fn ticket_state(state: u8) -> TicketState {
+    match state {
+        TICKET_VALID => TicketState::Valid,
+        TICKET_VALIDATING => TicketState::Validating,
+        TICKET_USED => TicketState::Used,
+        TICKET_REVOKED => TicketState::Revoked,
+        TICKET_REFUNDED => TicketState::Refunded,
+        _ => TicketState::Reserved,
+    }
+}
+
+// Ids after the cursor in ascending order, as transfers and removals reorder guests tickets
+fn page_ids(mut ids: Vec<u128>, start_after: Option<Uint128>) -> Vec<u128> {
+    ids.sort_unstable();
+    let start = start_after.map_or(0, |id| id.u128());
+    ids.retain(|id| *id > start);
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::msg::{DutchAuctionMsg, InstallmentPlanMsg, IssueCompTicketMsg, PayeeMsg, SessionMsg, Snip20Contract, TierMsg};
+    use crate::state::{get_config_readonly, ReadonlyBalances, DEFAULT_DISPUTE_WINDOW, MAX_DISPUTE_WINDOW, MAX_NAME_LEN, EXCHANGE_RATE_DELAY, UNBONDING_PERIOD, MAX_FEE_BPS, MAX_VERIFY_ATTEMPTS, TWO_FACTOR_WINDOW, VERIFY_LOCKOUT, DEFAULT_TIER, PREFIX_GUESTS_TICKETS};
+    use cosmwasm_std::{coin, coins};
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_binary, Addr, Api, ContractResult, CosmosMsg, Empty, OwnedDeps, SystemResult};
+
+    const TEST_SK: [u8; 32] = [7; 32];
+    const TEST_EVENT_ENTROPY: &str = "3457263458762a8f0c4e1d9b7a6f5e4d3c2b1a09f8e7d6c5b4a39281706f5e4d";
+    const TEST_GUEST_ENTROPY: &str = "1827391824b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d";
+
+    // Hex encoded x25519 public key of the test guest
+    fn test_pk() -> String {
+        hex::encode(PublicKey::from(&StaticSecret::from(TEST_SK)).as_bytes())
+    }
+
+    // Mock dependencies whose contract holds plenty of each test denom, as funds sent with mock
+    // calls never reach the mock bank
+    fn mock_dependencies_funded() -> OwnedDeps<MockStorage, MockApi, MockQuerier, Empty> {
+        mock_dependencies_with_balance(&[coin(1_000_000_000, "uscrt"), coin(1_000_000_000, "ibc/scrt")])
+    }
+
+    fn instantiate_test() -> (
+        Addr,
+        OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        MessageInfo,
+        InstantiateMsg,
+    ) {
+        let mut deps = mock_dependencies_funded();
+
+        let owner = deps.api.addr_validate("owner").unwrap();
+        let info = mock_info(owner.as_str(), &coins(1000, "earth"));
+        let msg = InstantiateMsg { organiser_bond: None, entropy: "b16b00b5".to_string(), sscrt: None, denoms: None };
+
+        let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        return (owner, deps, info, msg);
+    }
+
+    fn register_organiser_test(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        organiser: &Addr,
+    ) {
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let organisers = ReadonlyOrganisers::from_storage(&deps.storage);
+        if organisers.may_load_organiser(&organiser_canon).unwrap().is_none() {
+            let info = mock_info(organiser.as_str(), &coins(DEFAULT_ORGANISER_BOND, "uscrt"));
+            try_register_organiser(deps.as_mut(), info).unwrap();
+        }
+    }
+
+    fn create_event_test(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        organiser: &Addr,
+        msg: CreateEventMsg,
+    ) -> u128 {
+        register_organiser_test(deps, organiser);
+        let info = mock_info(organiser.as_str(), &[]);
+        let resp = try_create_event(deps.as_mut(), info, msg).unwrap();
+        from_binary::<CreateEventResponse>(&resp.data.unwrap()).unwrap().event_id.u128()
+    }
+
+    fn buy_ticket_test(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+        env: Env,
+        guest: &Addr,
+        event_id: u128,
+    ) -> Result<Response, ContractError> {
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            ..Default::default()
+        };
+        try_buy_ticket(deps.as_mut(), env, info, msg)
+    }
+
+    #[test]
+    fn instantiate_proper() {
+        let (owner, deps, _, _) = instantiate_test();
+
+        // Check if owner is correct
+        let config = get_config_readonly(&deps.storage).load().unwrap();
+        assert_eq!(deps.api.addr_humanize(config.get_owner()).unwrap(), owner);
+    }
+
+    #[test]
+    fn contract_info_proper() {
+        // Instantiate contract and create an event
+        let (owner, mut deps, _, _) = instantiate_test();
+        create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+
+        // Check deployment details and counters
+        let resp: ContractInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap()).unwrap();
+        assert_eq!(resp.version, CONTRACT_VERSION);
+        assert_eq!(resp.owner, owner);
+        assert_eq!(resp.fee, FeeConfigResponse { rate_bps: 0, recipient: owner });
+        assert_eq!(resp.denoms, vec!["uscrt".to_string()]);
+        assert_eq!(resp.num_events, Uint128::from(1u128));
+        assert_eq!(resp.num_tickets, Uint128::zero());
+    }
+
+    #[test]
+    fn deposit_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Check if balance increased
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        let owner_balance = balances.read_account_balance(&owner_canon).unwrap();
+        assert_eq!(owner_balance, 1000);
+    }
+
+    #[test]
+    fn withdraw_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Withdraw tokens
+        let deposit_info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+        let _deposit_resp =
+            try_withdraw(deps.as_mut(), mock_env(), deposit_info, Some(Uint128::from(500u128)), None).unwrap();
+
+        // Check if balance increased
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        let owner_balance = balances.read_account_balance(&owner_canon).unwrap();
+        assert_eq!(owner_balance, 500);
+    }
+
+    #[test]
+    fn withdraw_all_proper() {
+        // Instantiate contract and deposit tokens
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        for address in [&owner, &guest] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+        let config = get_config_readonly(&deps.storage).load().unwrap();
+        assert_eq!(config.get_total_supply(), 2000);
+
+        // Withdrawing without an amount withdraws the whole balance
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_withdraw(deps.as_mut(), mock_env(), info, None, None).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: guest.to_string(), amount: coins(1000, "uscrt") })
+        );
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 0);
+
+        // Nothing is left to withdraw
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, None, None).is_err());
+
+        // Supply tracks what is still owed
+        let resp: TokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+        assert_eq!(resp.token_info.total_supply, Some(Uint128::from(1000u128)));
+    }
+
+    #[test]
+    fn deposit_withdraw_limits() {
+        // Instantiate contract and set limits, only the owner can
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let limits = ExecuteMsg::SetLimits {
+            min_deposit: Uint128::from(100u128),
+            min_withdrawal: Uint128::from(50u128),
+            withdrawal_cap: Some(Uint128::from(300u128)),
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(execute(deps.as_mut(), mock_env(), info, limits.clone()).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        execute(deps.as_mut(), mock_env(), info, limits).unwrap();
+        let resp: LimitsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Limits {}).unwrap()).unwrap();
+        assert_eq!(resp.withdrawal_cap, Some(Uint128::from(300u128)));
+
+        // Deposits below the minimum are rejected
+        let info = mock_info(guest.as_str(), &coins(99, "uscrt"));
+        assert!(try_deposit(deps.as_mut(), mock_env(), info).is_err());
+        let info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+
+        // Withdrawals must meet the minimum and stay within the cap for each block
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(49u128)), None).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(200u128)), None).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(200u128)), None).is_err());
+        let mut env = mock_env();
+        env.block.height += 1;
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), env, info, Some(Uint128::from(200u128)), None).unwrap();
+    }
+
+    #[test]
+    fn solvency_proper() {
+        // Instantiate contract with an organiser's bond and a guest's deposit held by the contract
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        register_organiser_test(&mut deps, &organiser);
+        let info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(DEFAULT_ORGANISER_BOND + 1000, "uscrt"));
+
+        // Bonds do not count towards backing
+        let resp: SolvencyResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap()).unwrap();
+        assert_eq!(resp, SolvencyResponse {
+            total_supply: Uint128::from(1000u128),
+            backing: Uint128::from(1000u128),
+            ratio: Some(Decimal::one()),
+        });
+
+        // Withdrawals the contract cannot cover without dipping into bonds fail rather than bounce
+        deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(DEFAULT_ORGANISER_BOND + 500, "uscrt"));
+        let resp: SolvencyResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap()).unwrap();
+        assert_eq!(resp.ratio, Some(Decimal::percent(50)));
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(600u128)), None).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(500u128)), None).unwrap();
+    }
+
+    #[test]
+    fn exchange_rate_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+
+        // Only the owner can set a valid rate
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_set_exchange_rate(deps.as_mut(), mock_env(), info, Uint128::from(2u128), 0).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_set_exchange_rate(deps.as_mut(), mock_env(), info, Uint128::zero(), 0).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_set_exchange_rate(deps.as_mut(), mock_env(), info, Uint128::from(2u128), 19).is_err());
+
+        // With no sEVNT outstanding a new rate applies immediately
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_set_exchange_rate(deps.as_mut(), mock_env(), info, Uint128::from(20u128), 1).unwrap();
+        assert_eq!(resp.attributes[0].value, mock_env().block.time.seconds().to_string());
+
+        // Deposits mint sEVNT at the rate
+        let info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 2000);
+
+        // Withdrawals too small to pay out any native tokens are rejected
+        let info = mock_info(guest.as_str(), &[]);
+        let err = try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(1u128)), None).unwrap_err();
+        assert_eq!(err, ContractError::Std(StdError::generic_err("Amount is too small to withdraw")));
+
+        // With sEVNT outstanding a new rate is delayed
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_set_exchange_rate(deps.as_mut(), mock_env(), info, Uint128::from(4u128), 0).unwrap();
+        let effective_at = mock_env().block.time.seconds() + EXCHANGE_RATE_DELAY;
+        assert_eq!(resp.attributes[0].value, effective_at.to_string());
+        let resp: ExchangeRateResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ExchangeRate {}).unwrap()).unwrap();
+        assert_eq!(
+            resp,
+            ExchangeRateResponse {
+                rate: Uint128::from(20u128),
+                decimals: 1,
+                pending: Some(PendingExchangeRate { rate: Uint128::from(4u128), decimals: 0, effective_at }),
+            }
+        );
+
+        // Until then withdrawals use the old rate
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(1000u128)), None).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: guest.to_string(), amount: coins(500, "uscrt") })
+        );
+
+        // Afterwards the new rate applies
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(EXCHANGE_RATE_DELAY);
+        let resp: ExchangeRateResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ExchangeRate {}).unwrap()).unwrap();
+        assert_eq!(resp, ExchangeRateResponse { rate: Uint128::from(4u128), decimals: 0, pending: None });
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_withdraw(deps.as_mut(), env, info, None, None).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: guest.to_string(), amount: coins(250, "uscrt") })
+        );
+    }
+
+    #[test]
+    fn stable_price_proper() {
+        // Instantiate contract, stable priced events need an oracle
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        register_organiser_test(&mut deps, &owner);
+        let msg = CreateEventMsg {
+            price: Uint128::from(250u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            stable_price: Some(true),
+            ..Default::default()
+        };
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_create_event(deps.as_mut(), info, msg.clone()).is_err());
+
+        // Only the owner can set the oracle
+        let oracle = OracleContract { address: "oracle".to_string(), code_hash: "hash".to_string() };
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_set_price_oracle(deps.as_mut(), info, Some(oracle.clone())).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_price_oracle(deps.as_mut(), info, Some(oracle)).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, msg);
+
+        // Oracle prices SCRT at 0.50 USD
+        let now = mock_env().block.time.seconds();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "oracle" => {
+                let data = ReferenceData {
+                    rate: Uint128::from(500_000_000_000_000_000u128),
+                    last_updated_base: now,
+                    last_updated_quote: now,
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&data).unwrap()))
+            }
+            _ => panic!("Unexpected query"),
+        });
+
+        // 2.50 USD ticket costs 5 SCRT
+        let deposit_info = mock_info(guest.as_str(), &coins(6_000_000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_price(), 5_000_000);
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 1_000_000);
+
+        // Stale rates are rejected
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(MAX_ORACLE_AGE + 1);
+        let buyer = deps.api.addr_validate("buyer").unwrap();
+        let deposit_info = mock_info(buyer.as_str(), &coins(6_000_000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let err = buy_ticket_test(&mut deps, env, &buyer, event_id).unwrap_err();
+        assert_eq!(err, ContractError::Std(StdError::generic_err("Price oracle rate is stale")));
+    }
+
+    #[test]
+    fn rebalance_stake_large_reserve() {
+        // Instantiate contract with staking and a reserve near the largest amount
+        let (owner, mut deps, _, _) = instantiate_test();
+        let treasury = deps.api.addr_validate("treasury").unwrap();
+        deps.querier.update_staking("uscrt", &[], &[]);
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_staking(deps.as_mut(), info, "validator".to_string(), 5_000, treasury).unwrap();
+        let reserve = u128::MAX / 2;
+        let mut reserves = Reserves::from_storage(deps.as_mut().storage);
+        reserves.set_reserve("uscrt", reserve);
+
+        // Target stake is taken without overflowing
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_rebalance_stake(deps.as_mut(), mock_env(), info).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Delegate { validator: "validator".to_string(), amount: coin(reserve / 2, "uscrt") })
+        );
+    }
+
+    #[test]
+    fn staking_proper() {
+        // Instantiate contract and deposit tokens
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let treasury = deps.api.addr_validate("treasury").unwrap();
+        deps.querier.update_staking("uscrt", &[], &[]);
+        let info = mock_info(guest.as_str(), &coins(10_000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+
+        // Only the owner can configure staking, up to the maximum share
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_set_staking(deps.as_mut(), info, "validator".to_string(), 5_000, treasury.clone()).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_set_staking(deps.as_mut(), info, "validator".to_string(), 5_001, treasury.clone()).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_set_staking(deps.as_mut(), info, "validator".to_string(), 5_000, treasury.clone()).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Distribution(DistributionMsg::SetWithdrawAddress { address: treasury.to_string() })
+        );
+
+        // Rebalancing delegates the target share
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_rebalance_stake(deps.as_mut(), mock_env(), info).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Delegate { validator: "validator".to_string(), amount: coin(5_000, "uscrt") })
+        );
+
+        // Only liquid tokens can be withdrawn
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(5_001u128)), None).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(5_000u128)), None).unwrap();
+
+        // Rebalancing the smaller reserve undelegates, locking tokens until unbonded
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_rebalance_stake(deps.as_mut(), mock_env(), info).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate { validator: "validator".to_string(), amount: coin(2_500, "uscrt") })
+        );
+        let resp: StakingResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Staking {}).unwrap()).unwrap();
+        assert_eq!((resp.staked, resp.unbonding, resp.liquid), (Uint128::from(2_500u128), Uint128::from(2_500u128), Uint128::zero()));
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        let resp: StakingResponse = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Staking {}).unwrap()).unwrap();
+        assert_eq!((resp.staked, resp.unbonding, resp.liquid), (Uint128::from(2_500u128), Uint128::zero(), Uint128::from(2_500u128)));
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), env, info, Some(Uint128::from(2_500u128)), None).unwrap();
+
+        // Rewards are claimed to the treasury
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_claim_staking_rewards(deps.as_mut(), info).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward { validator: "validator".to_string() })
+        );
+    }
+
+    #[test]
+    fn multi_denom_proper() {
+        // Instantiate contract accepting two denoms
+        let mut deps = mock_dependencies_funded();
+        let owner = deps.api.addr_validate("owner").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = InstantiateMsg {
+            organiser_bond: None,
+            entropy: "b16b00b5".to_string(),
+            sscrt: None,
+            denoms: Some(vec!["uscrt".to_string(), "ibc/scrt".to_string()]),
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Accepted denoms credit the balance, others are rejected
+        let info = mock_info(guest.as_str(), &coins(100, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        let info = mock_info(guest.as_str(), &coins(200, "ibc/scrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        let info = mock_info(guest.as_str(), &coins(100, "earth"));
+        assert!(try_deposit(deps.as_mut(), mock_env(), info).is_err());
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 300);
+
+        // Withdrawals are limited by how much of the denom is held
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(150u128)), Some("uscrt".to_string())).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(150u128)), Some("earth".to_string())).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(150u128)), Some("ibc/scrt".to_string())).unwrap();
+        assert_eq!(
+            resp.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: guest.to_string(), amount: coins(150, "ibc/scrt") })
+        );
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(100u128)), None).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 50);
+    }
+
+    #[test]
+    fn snip20_proper() {
+        // Instantiate contract and deposit tokens
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let receiver = deps.api.addr_validate("receiver").unwrap();
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Transfer moves tokens, but not more than the balance
+        let info = mock_info(owner.as_str(), &[]);
+        try_transfer(deps.as_mut(), info, guest.to_string(), Uint128::from(300u128)).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_transfer(deps.as_mut(), info, guest.to_string(), Uint128::from(701u128)).is_err());
+
+        // Send to an address that has not registered makes no callback
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_send(deps.as_mut(), info, receiver.to_string(), None, Uint128::from(100u128), None, None).unwrap();
+        assert!(resp.messages.is_empty());
+
+        // Send to a registered contract calls its Receive
+        let info = mock_info(receiver.as_str(), &[]);
+        try_register_receive(deps.as_mut(), info, "receiver code hash".to_string()).unwrap();
+        let msg = Some(Binary::from(b"buy".to_vec()));
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_send(deps.as_mut(), info, receiver.to_string(), None, Uint128::from(100u128), msg.clone(), None).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        match &resp.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg: receive_msg, .. }) => {
+                assert_eq!(*contract_addr, receiver.to_string());
+                let expected = ReceiverHandleMsg::Receive {
+                    sender: owner.clone(),
+                    from: owner.clone(),
+                    amount: Uint128::from(100u128),
+                    memo: None,
+                    msg,
+                };
+                assert_eq!(*receive_msg, to_binary(&expected).unwrap());
+            }
+            _ => panic!("Expected a Receive callback"),
+        }
+
+        // Balances reflect the transfers
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        let balance = |address: &Addr| balances.read_account_balance(&deps.api.addr_canonicalize(address.as_str()).unwrap()).unwrap();
+        assert_eq!(balance(&owner), 500);
+        assert_eq!(balance(&guest), 300);
+        assert_eq!(balance(&receiver), 200);
+
+        // Token info is queryable
+        let resp: TokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap();
+        assert_eq!(resp.token_info.symbol, TOKEN_SYMBOL);
+        assert_eq!(resp.token_info.decimals, 6);
+    }
+
+    #[test]
+    fn allowances_proper() {
+        // Instantiate contract and deposit tokens
+        let (owner, mut deps, _, _) = instantiate_test();
+        let spender = deps.api.addr_validate("spender").unwrap();
+        let recipient = deps.api.addr_validate("recipient").unwrap();
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Spender cannot transfer without an allowance
+        let info = mock_info(spender.as_str(), &[]);
+        let resp = try_transfer_from(deps.as_mut(), mock_env(), info, owner.to_string(), recipient.to_string(), Uint128::from(1u128));
+        assert!(resp.is_err());
+
+        // Allowance increases and decreases, expiring at the given time
+        let expiration = Some(mock_env().block.time.seconds() + 100);
+        let info = mock_info(owner.as_str(), &[]);
+        try_change_allowance(deps.as_mut(), info, spender.to_string(), Uint128::from(500u128), expiration, true).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_change_allowance(deps.as_mut(), info, spender.to_string(), Uint128::from(200u128), None, false).unwrap();
+        let data: AllowanceResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.allowance, Uint128::from(300u128));
+        assert_eq!(data.expiration, expiration);
+
+        // Spender can transfer within the allowance, but not beyond it
+        let info = mock_info(spender.as_str(), &[]);
+        try_transfer_from(deps.as_mut(), mock_env(), info, owner.to_string(), recipient.to_string(), Uint128::from(200u128)).unwrap();
+        let info = mock_info(spender.as_str(), &[]);
+        let resp = try_transfer_from(deps.as_mut(), mock_env(), info, owner.to_string(), recipient.to_string(), Uint128::from(101u128));
+        assert!(resp.is_err());
+
+        // Allowance cannot be used after it expires
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let info = mock_info(spender.as_str(), &[]);
+        assert!(try_transfer_from(deps.as_mut(), env, info, owner.to_string(), recipient.to_string(), Uint128::from(1u128)).is_err());
+
+        // Balances reflect the transfer
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        let balance = |address: &Addr| balances.read_account_balance(&deps.api.addr_canonicalize(address.as_str()).unwrap()).unwrap();
+        assert_eq!(balance(&owner), 800);
+        assert_eq!(balance(&recipient), 200);
+        assert_eq!(balance(&spender), 0);
+    }
+
+    #[test]
+    fn allowance_queries() {
+        // Instantiate contract and give allowances to two spenders
+        let (owner, mut deps, _, _) = instantiate_test();
+        let spender = deps.api.addr_validate("spender").unwrap();
+        let other = deps.api.addr_validate("other").unwrap();
+        for address in [&owner, &spender, &other] {
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        for (address, amount) in [(&spender, 100u128), (&other, 200)] {
+            let info = mock_info(owner.as_str(), &[]);
+            try_change_allowance(deps.as_mut(), info, address.to_string(), Uint128::from(amount), None, true).unwrap();
+        }
+        let info = mock_info(owner.as_str(), &[]);
+        try_change_allowance(deps.as_mut(), info, spender.to_string(), Uint128::from(50u128), None, true).unwrap();
+
+        // Owner and spender can query the allowance, others cannot
+        let allowance_query = |address: &Addr| QueryMsg::Allowance {
+            owner: owner.clone(),
+            spender: spender.clone(),
+            address: address.clone(),
+            key: "key".to_string(),
+        };
+        let expected = AllowanceResponse {
+            owner: owner.clone(),
+            spender: spender.clone(),
+            allowance: Uint128::from(150u128),
+            expiration: None,
+        };
+        let resp: AllowanceResponse = from_binary(&query(deps.as_ref(), mock_env(), allowance_query(&spender)).unwrap()).unwrap();
+        assert_eq!(resp, expected);
+        let resp: AllowanceResponse = from_binary(&query(deps.as_ref(), mock_env(), allowance_query(&owner)).unwrap()).unwrap();
+        assert_eq!(resp, expected);
+        assert!(query(deps.as_ref(), mock_env(), allowance_query(&other)).is_err());
+
+        // Allowances given are paginated by spender
+        let msg = QueryMsg::AllowancesGiven { start_after: None, limit: Some(1), address: owner.clone(), key: "key".to_string() };
+        let resp: AllowancesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.allowances.len(), 1);
+        assert_eq!(resp.allowances[0].spender, other);
+        let msg = QueryMsg::AllowancesGiven { start_after: Some(other.clone()), limit: None, address: owner.clone(), key: "key".to_string() };
+        let resp: AllowancesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.allowances, vec![expected.clone()]);
+
+        // Allowances received are listed for the spender
+        let msg = QueryMsg::AllowancesReceived { start_after: None, limit: None, address: spender, key: "key".to_string() };
+        let resp: AllowancesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.allowances, vec![expected]);
+        let msg = QueryMsg::AllowancesReceived { start_after: None, limit: None, address: owner, key: "key".to_string() };
+        let resp: AllowancesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(resp.allowances.is_empty());
+    }
+
+    #[test]
+    fn sscrt_deposit_proper() {
+        // Instantiate contract with an sSCRT contract
+        let mut deps = mock_dependencies_funded();
+        let owner = deps.api.addr_validate("owner").unwrap();
+        let sscrt = deps.api.addr_validate("sscrt").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = InstantiateMsg {
+            organiser_bond: None,
+            entropy: "b16b00b5".to_string(),
+            sscrt: Some(Snip20Contract { address: sscrt.to_string(), code_hash: "sscrt code hash".to_string() }),
+            denoms: None,
+        };
+        let resp = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Contract registers to receive with the sSCRT contract
+        assert_eq!(resp.messages.len(), 1);
+        match &resp.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) => assert_eq!(*contract_addr, sscrt.to_string()),
+            _ => panic!("Expected a RegisterReceive message"),
+        }
+
+        // Only the sSCRT contract can credit deposits
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_receive(deps.as_mut(), mock_env(), info, guest.clone(), Uint128::from(500u128)).is_err());
+        let info = mock_info(sscrt.as_str(), &[]);
+        assert!(try_receive(deps.as_mut(), mock_env(), info, guest.clone(), Uint128::zero()).is_err());
+        let info = mock_info(sscrt.as_str(), &[]);
+        try_receive(deps.as_mut(), mock_env(), info, guest.clone(), Uint128::from(500u128)).unwrap();
+
+        // Sender of the sSCRT is credited
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 500);
+    }
+
+    #[test]
+    fn withdraw_original_asset() {
+        // Instantiate contract with an sSCRT contract
+        let mut deps = mock_dependencies_funded();
+        let owner = deps.api.addr_validate("owner").unwrap();
+        let sscrt = deps.api.addr_validate("sscrt").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = InstantiateMsg {
+            organiser_bond: None,
+            entropy: "b16b00b5".to_string(),
+            sscrt: Some(Snip20Contract { address: sscrt.to_string(), code_hash: "sscrt code hash".to_string() }),
+            denoms: None,
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Guest deposits uscrt then sSCRT, and buys and refunds a ticket
+        let info = mock_info(guest.as_str(), &coins(100, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        let info = mock_info(sscrt.as_str(), &[]);
+        try_receive(deps.as_mut(), mock_env(), info, guest.clone(), Uint128::from(50u128)).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(120u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            refund_deadline: Some(mock_env().block.time.seconds() + 100),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        try_refund_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+
+        // Withdrawals pay back the deposited assets, oldest first
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(120u128)), None).unwrap();
+        assert_eq!(resp.messages.len(), 2);
+        match &resp.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                assert_eq!(*contract_addr, sscrt.to_string());
+                assert_eq!(
+                    from_binary::<Snip20Msg>(msg).unwrap(),
+                    Snip20Msg::Transfer { recipient: guest.to_string(), amount: Uint128::from(20u128), padding: None }
+                );
+            }
+            _ => panic!("Expected an sSCRT transfer"),
+        }
+        assert_eq!(
+            resp.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: guest.to_string(), amount: coins(100, "uscrt") })
+        );
+
+        // Requesting a denom pays it instead, as far as it is held
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_withdraw(deps.as_mut(), mock_env(), info, None, Some("uscrt".to_string())).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_withdraw(deps.as_mut(), mock_env(), info, None, None).unwrap();
+        match &resp.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                from_binary::<Snip20Msg>(msg).unwrap(),
+                Snip20Msg::Transfer { recipient: guest.to_string(), amount: Uint128::from(30u128), padding: None }
+            ),
+            _ => panic!("Expected an sSCRT transfer"),
+        }
+    }
+
+    #[test]
+    fn create_event_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        register_organiser_test(&mut deps, &owner);
+
+        // Create event
+        let price = Uint128::from(500u128);
+        let max_tickets = Uint128::from(500u128);
+        let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+        let entropy = TEST_EVENT_ENTROPY.to_string();
+        let msg = CreateEventMsg { price, max_tickets, entropy, ..Default::default() };
+        let resp = try_create_event(deps.as_mut(), info, msg).unwrap();
+
+        // Check proper event ID returned, and not emitted publicly
+        assert!(resp.attributes.is_empty());
+        let event_id = from_binary::<CreateEventResponse>(&resp.data.unwrap()).unwrap().event_id.u128();
+
+        // Check in storage
+        assert_eq!(event_id, 1);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let event = events.may_load_event(event_id).unwrap().unwrap();
+
+        assert_eq!(event.get_id(), event_id);
+        assert_eq!(event.get_price(), price.u128());
+        assert_eq!(event.get_max_tickets(), max_tickets.u128());
+        assert_eq!(event.get_tickets_sold(), 0);
+        assert_eq!(
+            deps.api.addr_humanize(event.get_organiser()).unwrap(),
+            owner
+        );
+
+        // Check in organisers events
+        let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.as_mut().storage);
+        let this_organisers_events = organisers_events.load_events(&owner_canon).unwrap();
+        assert_eq!(*this_organisers_events.get(0).unwrap(), event_id);
+
+        // Create event
+        let entropy = TEST_GUEST_ENTROPY.to_string();
+        let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+        let msg = CreateEventMsg { price, max_tickets, entropy, ..Default::default() };
+        let resp = try_create_event(deps.as_mut(), info, msg).unwrap();
+
+        // Check proper event ID returned
+        let data: CreateEventResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.event_id, Uint128::from(2u128));
+
+        let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.as_mut().storage);
+        let this_organisers_events = organisers_events.load_events(&owner_canon).unwrap();
+        assert_eq!(*this_organisers_events.get(1).unwrap(), 2);
+    }
+
+    #[test]
+    fn entropy_validation() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        register_organiser_test(&mut deps, &owner);
+
+        // Short, overlong and non-hex entropy is rejected
+        let short = "3457263458762".to_string();
+        let long = format!("{}00", TEST_EVENT_ENTROPY);
+        let non_hex = TEST_EVENT_ENTROPY.replace('a', "g");
+        for entropy in [short, long, non_hex] {
+            let info = mock_info(owner.as_str(), &[]);
+            let msg = CreateEventMsg { price: Uint128::from(50u128), max_tickets: Uint128::from(10u128), entropy, ..Default::default() };
+            assert!(try_create_event(deps.as_mut(), info, msg).is_err());
+        }
+
+        // A full 32 byte hex string is accepted
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(0u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+
+        // Buying with short entropy fails
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: "1827391824".to_string(),
+            pk: test_pk(),
+            ..Default::default()
+        };
+        assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg).is_err());
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+    }
+
+    // #[test]
+    // fn buy_ticket_proper() {
+    //     // Instantiate contract
+    //     let (owner, mut deps, _, _) = instantiate_test();
+
+    //     // Deposit tokens
+    //     let guest = deps.api.addr_validate("guest").unwrap();
+    //     let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+    //     let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+    //     // Create event
+    //     let price = Uint128::from(50u128);
+    //     let max_tickets = Uint128::from(500u128);
+    //     let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+    //     let entropy = Uint128::from(3457263458762u128);
+    //     let mut resp = try_create_event(deps.as_mut(), info, price, max_tickets, entropy).unwrap();
+    //     let attribute = resp.attributes.pop().unwrap();
+    //     let event_id: u128 = attribute.value.parse().unwrap();
+
+    //     // Buy ticket
+    //     let entropy = Uint128::from(1827391824732872934872u128);
+    //     let info = mock_info(guest.as_str(), &coins(0, "uscrt"));
+    //     let mut resp = try_buy_ticket(deps.as_mut(), info, Uint128::from(event_id), entropy).unwrap();
+
+    //     // Check proper ticket ID emitted
+    //     let attribute = resp.attributes.pop().unwrap();
+    //     assert_eq!(attribute.key, "ticket_id");
+    //     assert_eq!(attribute.value, "1");
+
+    //     // Check ticket in storage
+    //     let ticket_id: u128 = attribute.value.parse().unwrap();
+    //     assert_eq!(ticket_id, 1);
+    //     let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+    //     let ticket = tickets.may_load_ticket(ticket_id).unwrap();
+    //     assert_eq!(ticket.get_id(), ticket_id);
+    //     assert_eq!(ticket.get_event_id(), event_id);
+    //     assert_eq!(deps.api.addr_humanize(ticket.get_guest()).unwrap(), guest);
+
+    //     // Check event ticket count incremented
+    //     let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+    //     let event = events.may_load_event(event_id).unwrap();
+    //     assert_eq!(event.get_tickets_sold(), 1);
+
+    //     // Check guest balance decreased
+    //     let guest_address = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+    //     let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+    //     let guest_balance = balances.read_account_balance(&guest_address);
+    //     assert_eq!(guest_balance, 950);
+
+    //     // Check organiser balance decreased
+    //     let organiser_address = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+    //     let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+    //     let organiser_balance = balances.read_account_balance(&organiser_address);
+    //     assert_eq!(organiser_balance, 50);
+    // }
+
+    // #[test]
+    // fn verify_ticket_proper() {
+    //     // Instantiate contract
+    //     let (owner, mut deps, _, _) = instantiate_test();
+
+    //     // Deposit tokens
+    //     let guest = deps.api.addr_validate("guest").unwrap();
+    //     let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+    //     let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+    //     // Create event
+    //     let price = Uint128::from(50u128);
+    //     let max_tickets = Uint128::from(500u128);
+    //     let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+    //     let entropy = Uint128::from(3457263458762u128);
+    //     let mut resp = try_create_event(deps.as_mut(), info, price, max_tickets, entropy).unwrap();
+    //     let attribute = resp.attributes.pop().unwrap();
+    //     let event_id: u128 = attribute.value.parse().unwrap();
+
+    //     // Buy ticket
+    //     let entropy = Uint128::from(1827391824732872934872u128);
+    //     let info = mock_info(guest.as_str(), &coins(0, "uscrt"));
+    //     let mut resp = try_buy_ticket(deps.as_mut(), info, Uint128::from(event_id), entropy).unwrap();
+
+    //     // Get ticket
+    //     let attribute = resp.attributes.pop().unwrap();
+    //     let ticket_id: u128 = attribute.value.parse().unwrap();
+
+    //     // Begin to verify ticket and get secret
+    //     let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+    //     let mut resp = try_verify_ticket(deps.as_mut(), info, Uint128::from(ticket_id)).unwrap();
+    //     let attribute = resp.attributes.pop().unwrap();
+    //     assert_eq!(attribute.key, "secret_encrypted");
+    //     assert_eq!(attribute.value, "9662036190035425912");
+    //     let _secret_encrypted: u128 = attribute.value.parse().unwrap();
+
+    //     // Check ticket is in validating state
+    //     let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+    //     let ticket = tickets.may_load_ticket(ticket_id).unwrap();
+    //     assert_eq!(ticket.get_state(), 1);
+
+    //     // // Validate guest
+    //     // let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
+    //     // try_verify_guest(
+    //     //     deps.as_mut(),
+    //     //     info,
+    //     //     Uint128::from(ticket_id),
+    //     //     Uint128::from(9662036190035425912u128.div_euclid(2)),
+    //     // )
+    //     // .unwrap();
+
+    //     // // Check ticket is in used state
+    //     // let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+    //     // let ticket = tickets.may_load_ticket(ticket_id).unwrap();
+    //     // assert_eq!(ticket.get_state(), 2);
+    // }
+
+    #[test]
+    fn buy_ticket_sale_window() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with sales opening in the future
+        let now = mock_env().block.time.seconds();
+        create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(500u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            sale_start: Some(now + 100),
+            sale_end: Some(now + 200),
+            ..Default::default()
+        });
+
+        // Buying before the sale starts should fail
+        let resp = buy_ticket_test(&mut deps, mock_env(), &guest, 1);
+        assert!(resp.is_err());
+
+        // Buying during the sale should succeed
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(150);
+        buy_ticket_test(&mut deps, env, &guest, 1).unwrap();
+
+        // Buying after the sale ends should fail
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(200);
+        let resp = buy_ticket_test(&mut deps, env, &owner, 1);
+        assert!(resp.is_err());
+    }
+
+    #[test]
+    fn verify_ticket_checkin_window() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event starting in an hour with a 10 minute check-in window
+        let now = mock_env().block.time.seconds();
+        create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(500u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(now + 3600),
+            checkin_window: Some(600),
+            ..Default::default()
+        });
+
+        // Buy ticket
+        buy_ticket_test(&mut deps, mock_env(), &guest, 1).unwrap();
+
+        // Verifying too early should fail
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128));
+        assert!(resp.is_err());
+
+        // Verifying inside the window should succeed
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(3300);
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), env, info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), 1);
+
+        // Verifying after the window should fail
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(4300);
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let resp = try_verify_guest(deps.as_mut(), env, info, Uint128::from(1u128), "45".to_string(), nonce);
+        assert!(resp.is_err());
+    }
+
+    #[test]
+    fn revoke_ticket_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event and buy ticket
+        create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, 1).unwrap();
+
+        // Only the organiser may revoke
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+
+        // Revoke ticket
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+
+        // Check guest refunded and organiser debited
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 1000);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 0);
+
+        // Check ticket voided, removed from guest and capacity freed
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_REVOKED);
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.as_mut().storage);
+        assert!(guests_tickets.load_tickets(&guest_canon).unwrap().is_empty());
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        assert_eq!(events.may_load_event(1).unwrap().unwrap().get_tickets_sold(), 0);
+
+        // Revoking twice should fail
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+    }
+
+    #[test]
+    fn set_capacity_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event and buy ticket
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Only the organiser may change capacity
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_set_capacity(deps.as_mut(), info, Uint128::from(event_id), Uint128::from(10u128), None);
+        assert!(resp.is_err());
+
+        // Capacity cannot drop below tickets sold
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_set_capacity(deps.as_mut(), info, Uint128::from(event_id), Uint128::zero(), None);
+        assert!(resp.is_err());
+
+        // Lower capacity to tickets sold
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_capacity(deps.as_mut(), info, Uint128::from(event_id), Uint128::from(1u128), None).unwrap();
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let event = events.may_load_event(event_id).unwrap().unwrap();
+        assert_eq!(event.get_max_tickets(), 1);
+        assert!(event.is_sold_out());
+    }
+
+    #[test]
+    fn pause_sales_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event and pause sales
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), true).unwrap();
+
+        // Buying while paused should fail
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+
+        // Resume sales and buy
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), false).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+    }
+
+    #[test]
+    fn event_info_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with sales opening later
+        let sale_start = mock_env().block.time.seconds() + 100;
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            sale_start: Some(sale_start),
+            ..Default::default()
+        });
+        let msg = QueryMsg::EventInfo { event_id: Uint128::from(event_id) };
+        let resp: EventInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), msg.clone()).unwrap()).unwrap();
+        assert_eq!(resp.organiser, owner);
+        assert_eq!(resp.status, EventStatus::Upcoming);
+        assert_eq!(resp.price, Uint128::from(50u128));
+        assert_eq!(resp.capacity, Uint128::from(2u128));
+        assert_eq!(resp.tickets_sold, Uint128::zero());
+        assert_eq!(resp.sale_start, Some(sale_start));
+
+        // Status follows sales
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let resp: EventInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+        assert_eq!(resp.status, EventStatus::OnSale);
+        buy_ticket_test(&mut deps, env.clone(), &guest, event_id).unwrap();
+        let resp: EventInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg.clone()).unwrap()).unwrap();
+        assert_eq!(resp.tickets_sold, Uint128::from(1u128));
+        assert_eq!(resp.tiers[0].tickets_sold, Uint128::from(1u128));
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), true).unwrap();
+        let resp: EventInfoResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(resp.status, EventStatus::Paused);
+
+        // Unknown events fail
+        let msg = QueryMsg::EventInfo { event_id: Uint128::from(99u128) };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn organiser_profile_proper() {
+        // Instantiate contract and sell a ticket to an event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let treasury = deps.api.addr_validate("treasury").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(mock_env().block.time.seconds()),
+            end_time: Some(mock_env().block.time.seconds() + 100),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Only registered organisers can set a profile, within the length limits
+        let set_profile = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, sender: &Addr, display_name: String| {
+            let info = mock_info(sender.as_str(), &[]);
+            try_set_organiser_profile(deps.as_mut(), info, display_name, "https://example.com".to_string(), Some(treasury.to_string()))
+        };
+        assert!(set_profile(&mut deps, &guest, "Guest".to_string()).is_err());
+        assert!(set_profile(&mut deps, &owner, "x".repeat(MAX_NAME_LEN + 1)).is_err());
+        set_profile(&mut deps, &owner, "Acme Events".to_string()).unwrap();
+
+        // Profile is public and shown with the organiser's events
+        let expected = OrganiserProfileResponse {
+            display_name: "Acme Events".to_string(),
+            contact_uri: "https://example.com".to_string(),
+        };
+        let msg = QueryMsg::OrganiserProfile { organiser: owner.clone() };
+        let resp: OrganiserProfileResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp, expected);
+        let msg = QueryMsg::EventInfo { event_id: Uint128::from(event_id) };
+        let resp: EventInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.organiser_profile, Some(expected));
+        let msg = QueryMsg::OrganiserProfile { organiser: guest };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Payouts are credited to the payout address
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100 + DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(owner.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        let treasury_canon = deps.api.addr_canonicalize(treasury.as_str()).unwrap();
+        assert!(balances.read_account_balance(&treasury_canon).unwrap() > 0);
+    }
+
+    #[test]
+    fn list_events_proper() {
+        // Instantiate contract and create events
+        let (owner, mut deps, _, _) = instantiate_test();
+        for _ in 0..3 {
+            create_event_test(&mut deps, &owner, CreateEventMsg {
+                price: Uint128::from(50u128),
+                max_tickets: Uint128::from(2u128),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                ..Default::default()
+            });
+        }
+
+        // Page through the events
+        let msg = QueryMsg::ListEvents { start_after: None, limit: Some(2), status: None };
+        let resp: ListEventsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let ids: Vec<Uint128> = resp.events.iter().map(|event| event.event_id).collect();
+        assert_eq!(ids, vec![Uint128::from(1u128), Uint128::from(2u128)]);
+        assert_eq!(resp.events[0].organiser, owner);
+        let msg = QueryMsg::ListEvents { start_after: Some(Uint128::from(2u128)), limit: Some(2), status: None };
+        let resp: ListEventsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let ids: Vec<Uint128> = resp.events.iter().map(|event| event.event_id).collect();
+        assert_eq!(ids, vec![Uint128::from(3u128)]);
+        let msg = QueryMsg::ListEvents { start_after: Some(Uint128::from(3u128)), limit: None, status: None };
+        let resp: ListEventsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(resp.events.is_empty());
+    }
+
+    #[test]
+    fn list_events_by_status() {
+        // Instantiate contract and create an event on sale and one running later
+        let (owner, mut deps, _, _) = instantiate_test();
+        let now = mock_env().block.time.seconds();
+        create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(now + 100),
+            end_time: Some(now + 200),
+            ..Default::default()
+        });
+        let list_ids = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, env: Env, status: EventStatus| {
+            let msg = QueryMsg::ListEvents { start_after: None, limit: None, status: Some(status) };
+            let resp: ListEventsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+            resp.events.iter().map(|event| event.event_id.u128()).collect::<Vec<u128>>()
+        };
+
+        // Both are on sale before the second starts
+        assert_eq!(list_ids(&deps, mock_env(), EventStatus::OnSale), vec![1, 2]);
+
+        // Then in progress, then finished
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(150);
+        assert_eq!(list_ids(&deps, env.clone(), EventStatus::OnSale), vec![1]);
+        assert_eq!(list_ids(&deps, env.clone(), EventStatus::InProgress), vec![2]);
+        env.block.time = env.block.time.plus_seconds(100);
+        assert_eq!(list_ids(&deps, env.clone(), EventStatus::Finished), vec![2]);
+
+        // Organisers can filter their own events too
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        let msg = QueryMsg::Events {
+            address: owner,
+            key: "key".to_string(),
+            status: Some(EventStatus::Finished),
+            start_after: None,
+            limit: None,
+        };
+        let resp: EventsResponse = from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+        assert_eq!(resp.events.len(), 1);
+        assert_eq!(resp.events[0].event_id, Uint128::from(2u128));
+        assert_eq!(resp.events[0].status, EventStatus::Finished);
+    }
+
+    #[test]
+    fn search_events_proper() {
+        // Instantiate contract and create events with public details
+        let (owner, mut deps, _, _) = instantiate_test();
+        for (name, venue, tags) in [
+            ("Jazz Night", "Blue Room", vec!["Music", "jazz"]),
+            ("Rust Meetup", "Town Hall", vec!["tech"]),
+            ("Summer Festival", "Town Park", vec!["music", "outdoor"]),
+        ] {
+            create_event_test(&mut deps, &owner, CreateEventMsg {
+                price: Uint128::from(50u128),
+                max_tickets: Uint128::from(2u128),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                name: Some(name.to_string()),
+                venue: Some(venue.to_string()),
+                tags: Some(tags.into_iter().map(String::from).collect()),
+                ..Default::default()
+            });
+        }
+        let search_ids = |text: Option<&str>, tag: Option<&str>, start_after: Option<u128>, limit: Option<u32>| {
+            let msg = QueryMsg::SearchEvents {
+                text: text.map(String::from),
+                tag: tag.map(String::from),
+                start_after: start_after.map(Uint128::from),
+                limit,
+            };
+            let resp: ListEventsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            resp.events.iter().map(|event| event.event_id.u128()).collect::<Vec<u128>>()
+        };
+
+        // Text matches names and venues, tags match exactly, both ignoring case
+        assert_eq!(search_ids(Some("town"), None, None, None), vec![2, 3]);
+        assert_eq!(search_ids(Some("JAZZ"), None, None, None), vec![1]);
+        assert_eq!(search_ids(None, Some("Music"), None, None), vec![1, 3]);
+        assert_eq!(search_ids(Some("town"), Some("music"), None, None), vec![3]);
+        assert_eq!(search_ids(None, Some("mus"), None, None), Vec::<u128>::new());
+
+        // Results are paginated
+        assert_eq!(search_ids(None, Some("music"), None, Some(1)), vec![1]);
+        assert_eq!(search_ids(None, Some("music"), Some(1), Some(1)), vec![3]);
+
+        // Details are bounded
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            tags: Some(vec![String::new()]),
+            ..Default::default()
+        };
+        assert!(try_create_event(deps.as_mut(), info, msg).is_err());
+    }
+
+    #[test]
+    fn buy_ticket_tiers() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with general admission and VIP tiers
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            tiers: Some(vec![
+                TierMsg { name: "ga".to_string(), price: Uint128::from(50u128), max_tickets: Uint128::from(100u128), schedule: None },
+                TierMsg { name: "vip".to_string(), price: Uint128::from(200u128), max_tickets: Uint128::from(1u128), schedule: None },
+            ]),
+            ..Default::default()
+        });
+
+        // Buy VIP ticket
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            tier: Some("vip".to_string()),
+            ..Default::default()
+        };
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Check ticket tier, price charged and tier sold count
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        assert_eq!(ticket.get_tier(), "vip");
+        assert_eq!(ticket.get_price(), 200);
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 800);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let event = events.may_load_event(event_id).unwrap().unwrap();
+        assert!(event.get_tier(1).is_sold_out());
+        assert!(!event.is_sold_out());
+
+        // Sold out query should report availability of each tier
+        let resp = query_event_sold_out(deps.as_ref(), Uint128::from(event_id)).unwrap();
+        assert!(!resp.sold_out);
+        assert_eq!(resp.tiers, vec![
+            TierAvailability { name: "ga".to_string(), tickets_left: Uint128::from(100u128), sold_out: false },
+            TierAvailability { name: "vip".to_string(), tickets_left: Uint128::from(0u128), sold_out: true },
+        ]);
+
+        // Buying an unknown tier should fail
+        let info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            tier: Some("backstage".to_string()),
+            ..Default::default()
+        };
+        assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg).is_err());
+    }
+
+    #[test]
+    fn events_sold_out_batch() {
+        // Instantiate contract and sell out one of two events
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let mut event_ids = vec![];
+        for max_tickets in [1u128, 5] {
+            event_ids.push(create_event_test(&mut deps, &owner, CreateEventMsg {
+                price: Uint128::from(50u128),
+                max_tickets: Uint128::from(max_tickets),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                ..Default::default()
+            }));
+        }
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_ids[0]).unwrap();
+
+        // Availability is returned in the order asked for
+        let msg = QueryMsg::EventsSoldOut { event_ids: vec![Uint128::from(event_ids[1]), Uint128::from(event_ids[0])] };
+        let resp: EventsSoldOutResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.events.len(), 2);
+        assert_eq!(resp.events[0].event_id, Uint128::from(event_ids[1]));
+        assert!(!resp.events[0].sold_out);
+        assert_eq!(resp.events[0].tiers[0].tickets_left, Uint128::from(5u128));
+        assert!(resp.events[1].sold_out);
+
+        // Unknown events fail the query
+        let msg = QueryMsg::EventsSoldOut { event_ids: vec![Uint128::from(99u128)] };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn buy_ticket_reserved_seat() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with reserved seating
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(2u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            seats: Some(vec!["A1".to_string(), "A2".to_string()]),
+            ..Default::default()
+        });
+
+        // Buying without a seat should fail
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+
+        // Buy seat A1
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            seat: Some("A1".to_string()),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+        // Buying a taken seat should fail
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // Only A2 should remain
+        let resp = query_remaining_seats(deps.as_ref(), Uint128::from(event_id)).unwrap();
+        assert_eq!(resp.seats, vec!["A2".to_string()]);
+    }
+
+    #[test]
+    fn buy_ticket_per_guest_limit() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event allowing two tickets per guest
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+
+        // Guest may buy two tickets but not a third
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+    }
+
+    #[test]
+    fn buy_ticket_attached_funds() {
+        // Instantiate contract and create event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            ..Default::default()
+        };
+
+        // Unsupported funds cannot buy a ticket
+        let info = mock_info(guest.as_str(), &coins(100, "earth"));
+        assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+
+        // Attached funds buy the ticket without a deposit, and the excess is kept as balance
+        let info = mock_info(guest.as_str(), &coins(150, "uscrt"));
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 50);
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(&deps.storage);
+        assert_eq!(guests_tickets.load_tickets(&guest_canon).unwrap(), vec![1]);
+    }
+
+
+    #[test]
+    fn buy_ticket_tip() {
+        // Instantiate contract and create event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        let owner_balance = balances.read_account_balance(&owner_canon).unwrap();
+
+        // Tip is paid to the organiser on top of the price, which goes into escrow
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            tip: Some(Uint128::from(30u128)),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &coins(150, "uscrt"));
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 20);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), owner_balance + 30);
+
+        // Tips are recorded separately from ticket revenue, which only organisers can query
+        let resp = query_event_revenue(deps.as_ref(), Uint128::from(event_id), &owner_canon).unwrap();
+        assert_eq!(resp, EventRevenueResponse { revenue: Uint128::from(100u128), tips: Uint128::from(30u128) });
+        assert!(query_event_revenue(deps.as_ref(), Uint128::from(event_id), &guest_canon).is_err());
+    }
+    #[test]
+    fn buy_tickets_batch() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(5u128)),
+            ..Default::default()
+        });
+
+        // Mismatched key count should fail
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketsMsg {
+            event_id: Uint128::from(event_id),
+            quantity: Uint128::from(3u128),
+            pks: vec![test_pk(); 2],
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        assert!(try_buy_tickets(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // Buy three tickets at once
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketsMsg {
+            event_id: Uint128::from(event_id),
+            quantity: Uint128::from(3u128),
+            pks: vec![test_pk(); 3],
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        let resp = try_buy_tickets(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let data: TicketIdsResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.ticket_ids, vec![Uint128::from(1u128), Uint128::from(2u128), Uint128::from(3u128)]);
+
+        // Check total price charged once and all tickets owned by guest
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 850);
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(guests_tickets.load_tickets(&guest_canon).unwrap(), vec![1, 2, 3]);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        assert_eq!(events.may_load_event(event_id).unwrap().unwrap().get_tickets_sold(), 3);
+
+        // Exceeding the per guest limit should fail
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketsMsg {
+            event_id: Uint128::from(event_id),
+            quantity: Uint128::from(3u128),
+            pks: vec![test_pk(); 3],
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        assert!(try_buy_tickets(deps.as_mut(), mock_env(), info, msg).is_err());
+    }
+
+    #[test]
+    fn transfer_ticket_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event and buy ticket
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Only the owner may transfer
+        let info = mock_info(friend.as_str(), &[]);
+        let resp = try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend.clone(), test_pk(), None);
+        assert!(resp.is_err());
+
+        // Transfer to friend
+        let info = mock_info(guest.as_str(), &[]);
+        let friend_pk = hex::encode([1u8; 32]);
+        try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend.clone(), friend_pk, None).unwrap();
+
+        // Check ticket and guest lists updated
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let friend_canon = deps.api.addr_canonicalize(friend.as_str()).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        assert_eq!(*ticket.get_guest(), friend_canon);
+        assert_eq!(ticket.get_pk().get_key(), [1u8; 32]);
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.as_mut().storage);
+        assert!(guests_tickets.load_tickets(&guest_canon).unwrap().is_empty());
+        assert_eq!(guests_tickets.load_tickets(&friend_canon).unwrap(), vec![1]);
+
+        // Tickets being validated cannot be transferred
+        let info = mock_info(friend.as_str(), &[]);
+        try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), guest.clone(), test_pk(), None).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend.clone(), test_pk(), None);
+        assert!(resp.is_err());
+    }
+
+    // Mock dependencies holding storage as the contract wrote it before schema versioning: the
+    // config as JSON, and events, tickets and address lists bincode encoded. An organiser has
+    // sold two tickets to a guest, one used and one being validated
+    fn legacy_storage_test() -> (Addr, Addr, OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>) {
+        use crate::state::{KEY_CONFIG, PREFIX_BALANCES, PREFIX_ORGANISERS_EVENTS};
+        use cosmwasm_storage::{singleton, PrefixedStorage};
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize)]
+        struct Config {
+            owner: CanonicalAddr,
+            num_events: u128,
+            num_tickets: u128,
+        }
+        #[derive(Serialize)]
+        struct Event {
+            id: u128,
+            organiser: CanonicalAddr,
+            price: u128,
+            max_tickets: u128,
+            tickets_sold: u128,
+            seed: [u8; 32],
+        }
+        #[derive(Serialize)]
+        struct Ticket {
+            id: u128,
+            guest: CanonicalAddr,
+            event_id: u128,
+            state: u8,
+            secret: u64,
+            pk: String,
+        }
+
+        // Deposits stayed with the contract, ticket sales only moved balances
+        let mut deps = mock_dependencies_with_balance(&coins(1000, "uscrt"));
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let pk = "-----BEGIN PUBLIC KEY-----\nMFwwDQYJKoZIhvcNAQEBBQADSwAwSAJBAL\n-----END PUBLIC KEY-----".to_string();
+
+        let config = Config { owner: organiser_canon.clone(), num_events: 1, num_tickets: 2 };
+        singleton(&mut deps.storage, KEY_CONFIG).save(&config).unwrap();
+        let mut balances = PrefixedStorage::new(&mut deps.storage, PREFIX_BALANCES);
+        balances.set(organiser_canon.as_slice(), &100u128.to_be_bytes());
+        balances.set(guest_canon.as_slice(), &900u128.to_be_bytes());
+        let event = Event {
+            id: 1,
+            organiser: organiser_canon.clone(),
+            price: 50,
+            max_tickets: 10,
+            tickets_sold: 2,
+            seed: [9; 32],
+        };
+        PrefixedStorage::new(&mut deps.storage, PREFIX_EVENTS).set(&1u128.to_be_bytes(), &bincode::serialize(&event).unwrap());
+        let mut tickets = PrefixedStorage::new(&mut deps.storage, PREFIX_TICKETS);
+        for (id, state, secret) in [(1u128, 2u8, 0u64), (2, 1, 0x63F3A89C45DE97FA)] {
+            let ticket = Ticket { id, guest: guest_canon.clone(), event_id: 1, state, secret, pk: pk.clone() };
+            tickets.set(&id.to_be_bytes(), &bincode::serialize(&ticket).unwrap());
+        }
+        PrefixedStorage::new(&mut deps.storage, PREFIX_ORGANISERS_EVENTS)
+            .set(organiser_canon.to_string().as_bytes(), &bincode::serialize(&vec![1u128]).unwrap());
+        PrefixedStorage::new(&mut deps.storage, PREFIX_GUESTS_TICKETS)
+            .set(guest_canon.to_string().as_bytes(), &bincode::serialize(&vec![1u128, 2]).unwrap());
+
+        (organiser, guest, deps)
+    }
+
+    #[test]
+    fn migrate_legacy_entries_proper() {
+        let (organiser, guest, mut deps) = legacy_storage_test();
+        let resp = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(resp.attributes[0].value, SCHEMA_VERSION.to_string());
+
+        // Config keeps its owner and ids, and backs balances with the contract's holdings
+        let config = get_config_readonly(&deps.storage).load().unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        assert_eq!(config.get_owner(), &organiser_canon);
+        assert_eq!((config.get_num_events(), config.get_num_tickets()), (1, 2));
+        assert_eq!(config.get_total_supply(), 1000);
+        assert_ne!(config.get_prng_seed(), &[0; 32]);
+
+        // Event becomes a single tier with its sales and seed
+        let event = ReadonlyEvents::from_storage(&deps.storage).load_event(1).unwrap();
+        assert_eq!(event.get_organiser(), &organiser_canon);
+        assert_eq!(event.get_tier(0).get_name(), DEFAULT_TIER);
+        assert_eq!(event.get_tier(0).get_price(), 50);
+        assert_eq!(event.get_tier(0).get_max_tickets(), 10);
+        assert_eq!(event.get_tickets_sold(), 2);
+        assert_eq!(event.get_seed(), &[9; 32]);
+
+        // Tickets keep their guest and whether they were used, with fresh seeds
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        let used = tickets.load_ticket(1).unwrap();
+        let validating = tickets.load_ticket(2).unwrap();
+        assert_eq!(used.get_state(), TICKET_USED);
+        assert_eq!(validating.get_state(), TICKET_VALID);
+        assert_eq!(validating.get_secret(), 0);
+        assert_eq!(validating.get_guest(), &deps.api.addr_canonicalize(guest.as_str()).unwrap());
+        assert_eq!(validating.get_price(), 50);
+        assert_eq!(validating.get_pk().get_key_type(), KEY_RSA);
+        assert_ne!(used.get_seed(), validating.get_seed());
+
+        // Balances can be withdrawn in full, and secrets are not encrypted to RSA keys
+        let info = mock_info(guest.as_str(), &[]);
+        try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(900u128)), None).unwrap();
+        assert_eq!(get_config_readonly(&deps.storage).load().unwrap().get_total_supply(), 100);
+        let info = mock_info(organiser.as_str(), &[]);
+        let err = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(2u128)).unwrap_err();
+        assert_eq!(err, ContractError::Std(StdError::generic_err("Ticket has a legacy RSA key, transfer it to a new key")));
+
+        // Corrupted legacy entries fail the migration rather than being skipped
+        let (_, _, mut deps) = legacy_storage_test();
+        let mut tickets = cosmwasm_storage::PrefixedStorage::new(&mut deps.storage, PREFIX_TICKETS);
+        let mut ticket_bytes = tickets.get(&2u128.to_be_bytes()).unwrap();
+        ticket_bytes.pop();
+        tickets.set(&2u128.to_be_bytes(), &ticket_bytes);
+        assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg {}).is_err());
+    }
+
+    #[test]
+    fn migrate_address_lists_proper() {
+        use cosmwasm_storage::ReadonlyPrefixedStorage;
+        use crate::state::PREFIX_ORGANISERS_EVENTS;
+
+        // Migrating moves the lists after upgrading events and tickets, and can be repeated
+        let (organiser, guest, mut deps) = legacy_storage_test();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        for _ in 0..2 {
+            migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+            let guests_tickets = ReadonlyGuestsTickets::from_storage(&deps.storage);
+            assert_eq!(guests_tickets.load_tickets(&guest_canon).unwrap(), vec![1, 2]);
+            let events_tickets = ReadonlyEventsTickets::from_storage(&deps.storage);
+            assert_eq!(events_tickets.iter_tickets(1, 0).unwrap().collect::<StdResult<Vec<u128>>>().unwrap(), vec![1, 2]);
+            let organisers_events = ReadonlyOrganisersEvents::from_storage(&deps.storage);
+            assert_eq!(organisers_events.load_events(&organiser_canon).unwrap(), vec![1]);
+        }
+        let legacy_key = guest_canon.to_string().into_bytes();
+        assert!(ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_GUESTS_TICKETS).get(&legacy_key).is_none());
+        let legacy_key = organiser_canon.to_string().into_bytes();
+        assert!(ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_ORGANISERS_EVENTS).get(&legacy_key).is_none());
+
+        // Ticket queries read the upgraded tickets from the moved list
+        let tickets = query_tickets(deps.as_ref(), &guest_canon, None, None).unwrap();
+        assert_eq!(tickets.tickets.len(), 2);
+    }
+
+    #[test]
+    fn schema_version_proper() {
+        use cosmwasm_std::{from_slice, to_vec};
+        use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+        use serde::{Deserialize, Serialize};
+
+        // Instantiated contracts are at the current version, and migrating keeps them there
+        let (_, mut deps, _, _) = instantiate_test();
+        assert_eq!(get_schema_version_readonly(&deps.storage).load().unwrap(), SCHEMA_VERSION);
+        let resp = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(resp.attributes[0].value, SCHEMA_VERSION.to_string());
+
+        // Cannot migrate storage written by newer code
+        get_schema_version(&mut deps.storage).save(&(SCHEMA_VERSION + 1)).unwrap();
+        assert!(migrate(deps.as_mut(), mock_env(), MigrateMsg {}).is_err());
+
+        // Entries are upgraded from an older layout, skipping missing ids
+        #[derive(Serialize, Deserialize)]
+        struct Old {
+            capacity: u32,
+        }
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct New {
+            capacity: u32,
+            waitlist: bool,
+        }
+        let mut entries = PrefixedStorage::new(&mut deps.storage, PREFIX_EVENTS);
+        entries.set(&1u128.to_be_bytes(), &to_vec(&Old { capacity: 10 }).unwrap());
+        entries.set(&3u128.to_be_bytes(), &to_vec(&Old { capacity: 30 }).unwrap());
+        upgrade_entries(&mut deps.storage, PREFIX_EVENTS, 3, from_slice, |old: Old| New { capacity: old.capacity, waitlist: false }).unwrap();
+        let entries = ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_EVENTS);
+        let new: New = from_slice(&entries.get(&1u128.to_be_bytes()).unwrap()).unwrap();
+        assert_eq!(new, New { capacity: 10, waitlist: false });
+        let new: New = from_slice(&entries.get(&3u128.to_be_bytes()).unwrap()).unwrap();
+        assert_eq!(new, New { capacity: 30, waitlist: false });
+        assert!(entries.get(&2u128.to_be_bytes()).is_none());
+    }
+
+    #[test]
+    fn contract_error_kinds() {
+        // Instantiate contract and create an event with a single ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+
+        // Unknown events are not found
+        let err = buy_ticket_test(&mut deps, mock_env(), &guest, 99).unwrap_err();
+        assert_eq!(err, ContractError::EventNotFound {});
+
+        // Guests without enough funds are told their balance
+        let err = buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds { balance: 0, required: 50 });
+
+        // Once the ticket is sold the event is sold out
+        for buyer in [&guest, &friend] {
+            let deposit_info = mock_info(buyer.as_str(), &coins(100, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let err = buy_ticket_test(&mut deps, mock_env(), &friend, event_id).unwrap_err();
+        assert_eq!(err, ContractError::SoldOut { item: "Event" });
+
+        // Only the contract owner can set arbiters
+        let info = mock_info(guest.as_str(), &[]);
+        let err = try_set_arbiters(deps.as_mut(), info, vec![friend.clone()]).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
+    }
+
+    #[test]
+    fn ticket_error_kinds() {
+        // Instantiate contract and sell a ticket to a guest
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(100, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Unknown tickets are not found, by execute and queries alike
+        let info = mock_info(guest.as_str(), &[]);
+        let err = try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(99u128), friend.clone(), test_pk(), None).unwrap_err();
+        assert_eq!(err, ContractError::TicketNotFound {});
+        let err = query_event_info(deps.as_ref(), mock_env(), Uint128::from(99u128)).unwrap_err();
+        assert_eq!(err, StdError::from(ContractError::EventNotFound {}));
+
+        // Withdrawals and transfers beyond the balance are told the balance
+        let info = mock_info(guest.as_str(), &[]);
+        let err = try_withdraw(deps.as_mut(), mock_env(), info, Some(Uint128::from(80u128)), None).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds { balance: 50, required: 80 });
+        let info = mock_info(guest.as_str(), &[]);
+        let err = try_transfer(deps.as_mut(), info, friend.to_string(), Uint128::from(60u128)).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds { balance: 50, required: 60 });
+
+        // Tickets cannot go to banned guests
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_guest_banned(deps.as_mut(), info, Uint128::from(event_id), friend.clone(), true).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        let err = try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend.clone(), test_pk(), None).unwrap_err();
+        assert_eq!(err, ContractError::Banned { who: "Recipient" });
+
+        // Revoked tickets cannot be revoked again or transferred
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let err = try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap_err();
+        assert_eq!(err, ContractError::TicketRevoked {});
+        let info = mock_info(owner.as_str(), &[]);
+        let err = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap_err();
+        assert_eq!(err, ContractError::TicketRevoked {});
+    }
+
+    #[test]
+    fn corrupted_storage_errors() {
+        use crate::state::{PREFIX_BALANCES, PREFIX_EVENTS, PREFIX_TICKETS};
+        use cosmwasm_storage::PrefixedStorage;
+
+        // Instantiate contract and buy a ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info.clone()).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // A malformed ticket fails to load rather than panicking, as does its event once missing
+        PrefixedStorage::new(&mut deps.storage, PREFIX_TICKETS).set(&1u128.to_be_bytes(), b"malformed");
+        assert!(ReadonlyTickets::from_storage(&deps.storage).may_load_ticket(1).is_err());
+        PrefixedStorage::new(&mut deps.storage, PREFIX_EVENTS).remove(&event_id.to_be_bytes());
+        match ReadonlyEvents::from_storage(&deps.storage).load_event(event_id) {
+            Err(err) => assert_eq!(err, StdError::generic_err(format!("Event {} does not exist", event_id))),
+            Ok(_) => panic!("Missing event loaded"),
+        }
+
+        // A truncated balance fails deposits
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        PrefixedStorage::new(&mut deps.storage, PREFIX_BALANCES).set(guest_canon.as_slice(), &[1, 2, 3]);
+        assert!(try_deposit(deps.as_mut(), mock_env(), deposit_info).is_err());
+    }
+
+    #[test]
+    fn guests_tickets_removal() {
+        // Instantiate contract and buy three tickets
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(3u128)),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        }
+
+        // Transferring the first ticket away moves the last into its place
+        let friend_pk = hex::encode([1u8; 32]);
+        let info = mock_info(guest.as_str(), &[]);
+        try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend.clone(), friend_pk.clone(), None).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(&deps.storage);
+        assert_eq!(guests_tickets.load_tickets(&guest_canon).unwrap(), vec![3, 2]);
+
+        // Removing the moved ticket and taking the first back keeps the list consistent
+        let info = mock_info(guest.as_str(), &[]);
+        try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(3u128), friend.clone(), friend_pk.clone(), None).unwrap();
+        let info = mock_info(friend.as_str(), &[]);
+        try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), guest.clone(), test_pk(), None).unwrap();
+        let friend_canon = deps.api.addr_canonicalize(friend.as_str()).unwrap();
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(&deps.storage);
+        assert_eq!(guests_tickets.load_tickets(&guest_canon).unwrap(), vec![2, 1]);
+        assert_eq!(guests_tickets.load_tickets(&friend_canon).unwrap(), vec![3]);
+
+        // Tickets query still pages in id order
+        let info = mock_info(guest.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        let msg = QueryMsg::Tickets { address: guest, key: "key".to_string(), start_after: None, limit: None };
+        let resp: TicketsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let ids: Vec<Uint128> = resp.tickets.iter().map(|ticket| ticket.ticket_id).collect();
+        assert_eq!(ids, vec![Uint128::from(1u128), Uint128::from(2u128)]);
+    }
+
+    #[test]
+    fn resale_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let buyer = deps.api.addr_validate("buyer").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let deposit_info = mock_info(buyer.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event allowing resale at up to 1.5x face value with a 10% royalty and buy ticket
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_resale_bps: Some(15_000),
+            royalty_bps: Some(1_000),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Listing above the cap should fail
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_list_ticket_for_sale(deps.as_mut(), info, Uint128::from(1u128), Uint128::from(151u128));
+        assert!(resp.is_err());
+
+        // List at the cap and transfers should be blocked
+        let info = mock_info(guest.as_str(), &[]);
+        try_list_ticket_for_sale(deps.as_mut(), info, Uint128::from(1u128), Uint128::from(150u128)).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), buyer.clone(), test_pk(), None);
+        assert!(resp.is_err());
+
+        // Buy resale ticket
+        let info = mock_info(buyer.as_str(), &[]);
+        try_buy_resale_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), hex::encode([2u8; 32]), None).unwrap();
+
+        // Check funds moved, ticket owned by buyer and listing removed
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let buyer_canon = deps.api.addr_canonicalize(buyer.as_str()).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 1035);
+        assert_eq!(balances.read_account_balance(&buyer_canon).unwrap(), 850);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 15);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(*tickets.may_load_ticket(1).unwrap().unwrap().get_guest(), buyer_canon);
+        let listings = ReadonlyListings::from_storage(deps.as_mut().storage);
+        assert!(listings.may_load_listing(1).unwrap().is_none());
+    }
+
+    #[test]
+    fn listings_query_proper() {
+        // Instantiate contract and buy tickets to two events
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let other = deps.api.addr_validate("other").unwrap();
+        for address in [&guest, &other] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let mut event_ids = vec![];
+        for _ in 0..2 {
+            event_ids.push(create_event_test(&mut deps, &owner, CreateEventMsg {
+                price: Uint128::from(100u128),
+                max_tickets: Uint128::from(10u128),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                max_resale_bps: Some(15_000),
+                ..Default::default()
+            }));
+        }
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_ids[0]).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &other, event_ids[0]).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_ids[1]).unwrap();
+
+        // List every ticket
+        for (seller, ticket_id, price) in [(&guest, 1u128, 120u128), (&other, 2, 130), (&guest, 3, 140)] {
+            let info = mock_info(seller.as_str(), &[]);
+            try_list_ticket_for_sale(deps.as_mut(), info, Uint128::from(ticket_id), Uint128::from(price)).unwrap();
+        }
+
+        // Listings of an event are paginated and show asking price and face value
+        let msg = QueryMsg::Listings { event_id: Uint128::from(event_ids[0]), start_after: None, limit: Some(1) };
+        let resp: ListingsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.listings, vec![ListingInfo {
+            ticket_id: Uint128::from(1u128),
+            event_id: Uint128::from(event_ids[0]),
+            tier: DEFAULT_TIER.to_string(),
+            seat: None,
+            price: Uint128::from(120u128),
+            face_value: Uint128::from(100u128),
+        }]);
+        let msg = QueryMsg::Listings { event_id: Uint128::from(event_ids[0]), start_after: Some(Uint128::from(1u128)), limit: None };
+        let resp: ListingsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let ids: Vec<Uint128> = resp.listings.iter().map(|listing| listing.ticket_id).collect();
+        assert_eq!(ids, vec![Uint128::from(2u128)]);
+
+        // Sellers see their own listings across events
+        let msg = QueryMsg::SellerListings { start_after: None, limit: None, address: guest.clone(), key: "key".to_string() };
+        let resp: ListingsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        let ids: Vec<Uint128> = resp.listings.iter().map(|listing| listing.ticket_id).collect();
+        assert_eq!(ids, vec![Uint128::from(1u128), Uint128::from(3u128)]);
+        let msg = QueryMsg::SellerListings { start_after: None, limit: None, address: guest, key: "wrong".to_string() };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn waitlist_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let waiter = deps.api.addr_validate("waiter").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let deposit_info = mock_info(waiter.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create single ticket event
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+
+        // Cannot join the waitlist before selling out
+        let info = mock_info(waiter.as_str(), &[]);
+        let resp = try_join_waitlist(deps.as_mut(), info, Uint128::from(event_id), TEST_GUEST_ENTROPY.to_string(), test_pk(), None);
+        assert!(resp.is_err());
+
+        // Sell out and join waitlist
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(waiter.as_str(), &[]);
+        try_join_waitlist(deps.as_mut(), info, Uint128::from(event_id), TEST_GUEST_ENTROPY.to_string(), test_pk(), None).unwrap();
+
+        // Revoking the ticket should mint a new one for the waitlisted guest
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let waiter_canon = deps.api.addr_canonicalize(waiter.as_str()).unwrap();
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(guests_tickets.load_tickets(&waiter_canon).unwrap(), vec![2]);
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&waiter_canon).unwrap(), 950);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let event = events.may_load_event(event_id).unwrap().unwrap();
+        assert!(event.is_sold_out());
+
+        // Revenue and escrow count the waitlisted sale in place of the revoked one
+        assert_eq!(event.get_revenue(), 50);
+        assert_eq!(event.get_escrow(), 50);
+    }
+
+    #[test]
+    fn waitlist_stable_price() {
+        // Instantiate contract with an oracle pricing SCRT at 0.50 USD
+        let (owner, mut deps, _, _) = instantiate_test();
+        let oracle = OracleContract { address: "oracle".to_string(), code_hash: "hash".to_string() };
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_price_oracle(deps.as_mut(), info, Some(oracle)).unwrap();
+        let now = mock_env().block.time.seconds();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "oracle" => {
+                let data = ReferenceData {
+                    rate: Uint128::from(500_000_000_000_000_000u128),
+                    last_updated_base: now,
+                    last_updated_quote: now,
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&data).unwrap()))
+            }
+            _ => panic!("Unexpected query"),
+        });
+
+        // Sell out a single 2.50 USD ticket event and join its waitlist
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let waiter = deps.api.addr_validate("waiter").unwrap();
+        for address in [&guest, &waiter] {
+            let deposit_info = mock_info(address.as_str(), &coins(6_000_000, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(250u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            stable_price: Some(true),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(waiter.as_str(), &[]);
+        try_join_waitlist(deps.as_mut(), info, Uint128::from(event_id), TEST_GUEST_ENTROPY.to_string(), test_pk(), None).unwrap();
+
+        // Waitlisted guest pays 5 SCRT for the freed ticket, not 250 sEVNT
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let waiter_canon = deps.api.addr_canonicalize(waiter.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&waiter_canon).unwrap(), 1_000_000);
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        assert_eq!(tickets.may_load_ticket(2).unwrap().unwrap().get_price(), 5_000_000);
+    }
+
+    #[test]
+    fn waitlist_insured() {
+        // Instantiate contract and sell out a single ticket insured event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let waiter = deps.api.addr_validate("waiter").unwrap();
+        for address in [&guest, &waiter] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(500u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            insured: Some(true),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(waiter.as_str(), &[]);
+        try_join_waitlist(deps.as_mut(), info, Uint128::from(event_id), TEST_GUEST_ENTROPY.to_string(), test_pk(), None).unwrap();
+
+        // Waitlisted guest pays the premium into the pool along with the price
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let waiter_canon = deps.api.addr_canonicalize(waiter.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&waiter_canon).unwrap(), 490);
+        let pool = query_insurance_pool(deps.as_ref()).unwrap();
+        assert_eq!(pool.balance, Uint128::from(20u128));
+    }
+
+    #[test]
+    fn waitlist_queries() {
+        // Instantiate contract and sell out a single ticket event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let first = deps.api.addr_validate("first").unwrap();
+        let second = deps.api.addr_validate("second").unwrap();
+        for address in [&owner, &guest, &first, &second] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(50u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        for address in [&first, &second] {
+            let info = mock_info(address.as_str(), &[]);
+            try_join_waitlist(deps.as_mut(), info, Uint128::from(event_id), TEST_GUEST_ENTROPY.to_string(), test_pk(), None).unwrap();
+        }
+
+        // Guests see their position and the depth
+        let position_query = |address: &Addr| QueryMsg::WaitlistPosition {
+            event_id: Uint128::from(event_id),
+            address: address.clone(),
+            key: "key".to_string(),
+        };
+        let resp: WaitlistPositionResponse = from_binary(&query(deps.as_ref(), mock_env(), position_query(&second)).unwrap()).unwrap();
+        assert_eq!(resp, WaitlistPositionResponse { position: Some(2), depth: 2 });
+        let resp: WaitlistPositionResponse = from_binary(&query(deps.as_ref(), mock_env(), position_query(&guest)).unwrap()).unwrap();
+        assert_eq!(resp, WaitlistPositionResponse { position: None, depth: 2 });
+
+        // Organisers page through the waitlist without addresses
+        let waitlist_query = |address: &Addr, start_after: Option<u32>| QueryMsg::Waitlist {
+            event_id: Uint128::from(event_id),
+            start_after,
+            limit: Some(1),
+            address: address.clone(),
+            key: "key".to_string(),
+        };
+        let resp: WaitlistResponse = from_binary(&query(deps.as_ref(), mock_env(), waitlist_query(&owner, Some(1))).unwrap()).unwrap();
+        assert_eq!(resp, WaitlistResponse { entries: vec![WaitlistEntryInfo { position: 2, guest: None }], depth: 2 });
+        assert!(query(deps.as_ref(), mock_env(), waitlist_query(&first, None)).is_err());
+
+        // Revoking the ticket moves the waitlist up
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let resp: WaitlistPositionResponse = from_binary(&query(deps.as_ref(), mock_env(), position_query(&second)).unwrap()).unwrap();
+        assert_eq!(resp, WaitlistPositionResponse { position: Some(1), depth: 1 });
+    }
+
+    #[test]
+    fn buy_ticket_promo_code() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event and register a single use 20% off code
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let code_hash = hex::encode(Sha256::digest(b"EARLYFAN"));
+        let info = mock_info(owner.as_str(), &[]);
+        try_add_promo_code(deps.as_mut(), info, Uint128::from(event_id), code_hash, 20, 1).unwrap();
+
+        // Buy with the code
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            promo_code: Some("EARLYFAN".to_string()),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 920);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_price(), 80);
+
+        // Code is used up
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg).is_err());
+    }
+
+    #[test]
+    fn buy_ticket_early_bird() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with first two tickets at 60 and the rest at 100
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(3u128)),
+            price_schedule: Some(vec![PriceStepMsg {
+                price: Uint128::from(60u128),
+                until_sold: Some(Uint128::from(2u128)),
+                until_time: None,
+            }]),
+            ..Default::default()
+        });
+
+        // Buying three tickets should cost 60 + 60 + 100
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = BuyTicketsMsg {
+            event_id: Uint128::from(event_id),
+            quantity: Uint128::from(3u128),
+            pks: vec![test_pk(); 3],
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        try_buy_tickets(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 780);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_price(), 60);
+        assert_eq!(tickets.may_load_ticket(3).unwrap().unwrap().get_price(), 100);
+    }
+
+    #[test]
+    fn buy_ticket_dutch_auction() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with auction decaying from 500 to 100 over 40 blocks
+        let start_height = mock_env().block.height;
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(3u128)),
+            dutch_auction: Some(DutchAuctionMsg {
+                start_price: Uint128::from(500u128),
+                floor_price: Uint128::from(100u128),
+                start_height,
+                decay_per_block: Uint128::from(10u128),
+            }),
+            ..Default::default()
+        });
+
+        // Buy at start, after 10 blocks, and after the floor is reached
+        let mut env = mock_env();
+        buy_ticket_test(&mut deps, env.clone(), &guest, event_id).unwrap();
+        env.block.height = start_height + 10;
+        buy_ticket_test(&mut deps, env.clone(), &guest, event_id).unwrap();
+        env.block.height = start_height + 100;
+        buy_ticket_test(&mut deps, env, &guest, event_id).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_price(), 500);
+        assert_eq!(tickets.may_load_ticket(2).unwrap().unwrap().get_price(), 400);
+        assert_eq!(tickets.may_load_ticket(3).unwrap().unwrap().get_price(), 100);
+
+        // Floor above start price is rejected
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            dutch_auction: Some(DutchAuctionMsg {
+                start_price: Uint128::from(100u128),
+                floor_price: Uint128::from(500u128),
+                start_height,
+                decay_per_block: Uint128::from(10u128),
+            }),
+            ..Default::default()
+        };
+        assert!(try_create_event(deps.as_mut(), info, msg).is_err());
+    }
+
+    #[test]
+    fn buy_ticket_presale() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let fan = deps.api.addr_validate("fan").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        for address in [&fan, &guest] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+
+        // Create event with presale starting now and public sale in 100 seconds
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            presale_start: Some(now),
+            sale_start: Some(now + 100),
+            ..Default::default()
+        });
+
+        // Only the organiser can set the allowlist
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_set_allowlist(deps.as_mut(), info, Uint128::from(event_id), vec![guest.clone()]);
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_allowlist(deps.as_mut(), info, Uint128::from(event_id), vec![fan.clone()]).unwrap();
+
+        // During presale only allowlisted guests can buy
+        let resp = buy_ticket_test(&mut deps, mock_env(), &guest, event_id);
+        assert!(resp.is_err());
+        buy_ticket_test(&mut deps, mock_env(), &fan, event_id).unwrap();
+
+        // Everyone can buy after the public sale starts
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        buy_ticket_test(&mut deps, env, &guest, event_id).unwrap();
+
+        // Presale must start before the public sale
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            presale_start: Some(now + 100),
+            sale_start: Some(now + 100),
+            ..Default::default()
+        };
+        assert!(try_create_event(deps.as_mut(), info, msg).is_err());
+    }
+
+    #[test]
+    fn refund_ticket_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+
+        // Deposit tokens
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event with refunds allowed for 100 seconds
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            refund_deadline: Some(now + 100),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Only the owner can refund
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_refund_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+
+        // Refund before deadline
+        let info = mock_info(guest.as_str(), &[]);
+        try_refund_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 900);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_REFUNDED);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        assert_eq!(events.may_load_event(event_id).unwrap().unwrap().get_tickets_sold(), 1);
+
+        // Refunding twice should fail
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_refund_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+
+        // Refunding after the deadline should fail
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_refund_ticket(deps.as_mut(), env, info, Uint128::from(2u128)).is_err());
+    }
+
+    #[test]
+    fn co_organiser_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let co_organiser = deps.api.addr_validate("co_organiser").unwrap();
+
+        // Only the organiser can add co-organisers
+        let info = mock_info(co_organiser.as_str(), &[]);
+        let resp = try_add_co_organiser(deps.as_mut(), info, Uint128::from(event_id), co_organiser.clone());
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_add_co_organiser(deps.as_mut(), info, Uint128::from(event_id), co_organiser.clone()).unwrap();
+
+        // Co-organiser can manage sales but not add further co-organisers
+        let info = mock_info(co_organiser.as_str(), &[]);
+        try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), true).unwrap();
+        let info = mock_info(co_organiser.as_str(), &[]);
+        let resp = try_add_co_organiser(deps.as_mut(), info, Uint128::from(event_id), owner.clone());
+        assert!(resp.is_err());
+
+        // Co-organiser can verify tickets
+        let info = mock_info(co_organiser.as_str(), &[]);
+        try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), false).unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(co_organiser.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+
+        // Removed co-organiser loses access
+        let info = mock_info(owner.as_str(), &[]);
+        try_remove_co_organiser(deps.as_mut(), info, Uint128::from(event_id), co_organiser.clone()).unwrap();
+        let info = mock_info(co_organiser.as_str(), &[]);
+        let resp = try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), true);
+        assert!(resp.is_err());
+    }
+
+    #[test]
+    fn verifier_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let verifier = deps.api.addr_validate("verifier").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Verification fails before verifier is added
+        let info = mock_info(verifier.as_str(), &[]);
+        assert!(try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+
+        // Only organisers can add verifiers
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_add_verifier(deps.as_mut(), info, Uint128::from(event_id), verifier.clone());
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_add_verifier(deps.as_mut(), info, Uint128::from(event_id), verifier.clone()).unwrap();
+
+        // Verifier can verify tickets but not manage sales
+        let info = mock_info(verifier.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let info = mock_info(verifier.as_str(), &[]);
+        let resp = try_set_sales_paused(deps.as_mut(), info, Uint128::from(event_id), true);
+        assert!(resp.is_err());
+
+        // Removed verifier loses access
+        let info = mock_info(owner.as_str(), &[]);
+        try_remove_verifier(deps.as_mut(), info, Uint128::from(event_id), verifier.clone()).unwrap();
+        let info = mock_info(verifier.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "0".to_string(), nonce);
+        assert!(resp.is_err());
+    }
+
+    #[test]
+    fn ban_guest_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Only organisers can ban guests
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_set_guest_banned(deps.as_mut(), info, Uint128::from(event_id), guest.clone(), true);
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_guest_banned(deps.as_mut(), info, Uint128::from(event_id), guest.clone(), true).unwrap();
+
+        // Banned guest cannot buy or check in
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "0".to_string(), nonce);
+        assert_eq!(resp.unwrap_err(), ContractError::Banned { who: "Guest" });
+
+        // Unbanned guest can buy again
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_guest_banned(deps.as_mut(), info, Uint128::from(event_id), guest.clone(), false).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+    }
+
+    #[test]
+    fn issue_comp_ticket_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let msg = IssueCompTicketMsg {
+            event_id: Uint128::from(event_id),
+            recipient: guest.clone(),
+            pk: test_pk(),
+            pk_type: None,
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            tier: None,
+            seat: None,
+        };
+
+        // Only organisers can issue comp tickets
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_issue_comp_ticket(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+
+        // Issue ticket without guest holding any funds
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_issue_comp_ticket(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        let data: TicketIdResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.ticket_id, Uint128::from(1u128));
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        assert_eq!(ticket.get_price(), 0);
+        assert_eq!(*ticket.get_guest(), deps.api.addr_canonicalize(guest.as_str()).unwrap());
+
+        // Comp tickets count against capacity
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        assert!(events.may_load_event(event_id).unwrap().unwrap().is_sold_out());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_issue_comp_ticket(deps.as_mut(), mock_env(), info, msg).is_err());
+    }
+
+    #[test]
+    fn comp_ticket_and_waitlist_key_type() {
+        // Instantiate contract with a single ticket event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let waiter = deps.api.addr_validate("waiter").unwrap();
+        let deposit_info = mock_info(waiter.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(1u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+
+        // Issue a comp ticket and join the waitlist with 32 byte ed25519 keys
+        let pk = hex::encode(ed25519_dalek::SigningKey::from_bytes(&[9; 32]).verifying_key().to_bytes());
+        let msg = IssueCompTicketMsg {
+            event_id: Uint128::from(event_id),
+            recipient: guest,
+            pk: pk.clone(),
+            pk_type: Some(PublicKeyType::Ed25519),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            tier: None,
+            seat: None,
+        };
+        let info = mock_info(owner.as_str(), &[]);
+        try_issue_comp_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info(waiter.as_str(), &[]);
+        let pk_type = Some(PublicKeyType::Ed25519);
+        try_join_waitlist(deps.as_mut(), info, Uint128::from(event_id), TEST_GUEST_ENTROPY.to_string(), pk, pk_type).unwrap();
+
+        // Both tickets keep the given key type rather than being read as x25519
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        for ticket_id in [1, 2] {
+            let ticket = tickets.may_load_ticket(ticket_id).unwrap().unwrap();
+            assert_eq!(ticket.get_pk().get_key_type(), KEY_ED25519);
+        }
+    }
+
+    #[test]
+    fn season_pass_proper() {
+        // Instantiate contract with two of the organiser's events and one of another's
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let event_msg = CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        let first = create_event_test(&mut deps, &organiser, event_msg.clone());
+        let second = create_event_test(&mut deps, &organiser, event_msg.clone());
+        let other = create_event_test(&mut deps, &owner, event_msg);
+
+        // Passes can only list the organiser's own events
+        let info = mock_info(organiser.as_str(), &[]);
+        let msg = CreatePassMsg {
+            price: Uint128::from(150u128),
+            max_passes: Uint128::from(1u128),
+            event_ids: Some(vec![Uint128::from(first), Uint128::from(other)]),
+            ..Default::default()
+        };
+        assert!(try_create_pass(deps.as_mut(), info, msg.clone()).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        let msg = CreatePassMsg { event_ids: Some(vec![Uint128::from(first), Uint128::from(second)]), ..msg };
+        let resp = try_create_pass(deps.as_mut(), info, msg).unwrap();
+        let pass_id = from_binary::<PassIdResponse>(&resp.data.unwrap()).unwrap().pass_id;
+
+        // Buy pass with attached funds, it sells out after one
+        let info = mock_info(guest.as_str(), &coins(200, "uscrt"));
+        try_buy_pass(deps.as_mut(), mock_env(), info, pass_id).unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&organiser_canon).unwrap(), 150);
+        let info = mock_info(owner.as_str(), &coins(200, "uscrt"));
+        assert!(try_buy_pass(deps.as_mut(), mock_env(), info, pass_id).is_err());
+
+        // Redeem once for each covered event
+        let redeem = |event_id: u128| RedeemPassMsg {
+            pass_id,
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_redeem_pass(deps.as_mut(), mock_env(), info, redeem(first)).unwrap();
+        let ticket_id = from_binary::<TicketIdResponse>(&resp.data.unwrap()).unwrap().ticket_id;
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        assert_eq!(tickets.may_load_ticket(ticket_id.u128()).unwrap().unwrap().get_event_id(), first);
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_redeem_pass(deps.as_mut(), mock_env(), info, redeem(first)).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_redeem_pass(deps.as_mut(), mock_env(), info, redeem(other)).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_redeem_pass(deps.as_mut(), mock_env(), info, redeem(second)).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_redeem_pass(deps.as_mut(), mock_env(), info, redeem(second)).unwrap();
+
+        // Pass and holdings are queryable
+        let resp = query_pass(deps.as_ref(), pass_id).unwrap();
+        assert_eq!(resp.event_ids, vec![Uint128::from(first), Uint128::from(second)]);
+        assert_eq!(resp.passes_sold, Uint128::from(1u128));
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let resp = query_passes(deps.as_ref(), &guest_canon).unwrap();
+        assert_eq!(resp.passes, vec![PassHoldingInfo {
+            pass_id,
+            redeemed: vec![Uint128::from(first), Uint128::from(second)],
+        }]);
+    }
+
+    #[test]
+    fn season_pass_period() {
+        // Instantiate contract with events starting inside and outside a period
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let now = mock_env().block.time.seconds();
+        let event_msg = |start: u64| CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(start),
+            end_time: Some(start + 100),
+            ..Default::default()
+        };
+        let inside = create_event_test(&mut deps, &organiser, event_msg(now + 1_000));
+        let outside = create_event_test(&mut deps, &organiser, event_msg(now + 10_000));
+
+        // Pass must cover either events or a period, not both
+        let info = mock_info(organiser.as_str(), &[]);
+        let msg = CreatePassMsg {
+            price: Uint128::from(150u128),
+            max_passes: Uint128::from(10u128),
+            event_ids: Some(vec![Uint128::from(inside)]),
+            period_start: Some(now),
+            period_end: Some(now + 5_000),
+        };
+        assert!(try_create_pass(deps.as_mut(), info, msg.clone()).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        let resp = try_create_pass(deps.as_mut(), info, CreatePassMsg { event_ids: None, ..msg }).unwrap();
+        let pass_id = from_binary::<PassIdResponse>(&resp.data.unwrap()).unwrap().pass_id;
+
+        // Events created later within the period are covered too
+        let later = create_event_test(&mut deps, &organiser, event_msg(now + 2_000));
+        let info = mock_info(guest.as_str(), &coins(150, "uscrt"));
+        try_buy_pass(deps.as_mut(), mock_env(), info, pass_id).unwrap();
+        for (event_id, covered) in [(inside, true), (later, true), (outside, false)] {
+            let info = mock_info(guest.as_str(), &[]);
+            let msg = RedeemPassMsg {
+                pass_id,
+                event_id: Uint128::from(event_id),
+                entropy: TEST_GUEST_ENTROPY.to_string(),
+                pk: test_pk(),
+                ..Default::default()
+            };
+            assert_eq!(try_redeem_pass(deps.as_mut(), mock_env(), info, msg).is_ok(), covered);
+        }
+    }
+
+    #[test]
+    fn season_pass_large_price() {
+        // Instantiate contract with a fee and a pass priced near the largest amount
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_update_fee_config(deps.as_mut(), info, 250, owner.clone()).unwrap();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let price = u128::MAX / 2;
+        let info = mock_info(organiser.as_str(), &[]);
+        let msg = CreatePassMsg {
+            price: Uint128::from(price),
+            max_passes: Uint128::from(10u128),
+            event_ids: Some(vec![Uint128::from(event_id)]),
+            period_start: None,
+            period_end: None,
+        };
+        let resp = try_create_pass(deps.as_mut(), info, msg).unwrap();
+        let pass_id = from_binary::<PassIdResponse>(&resp.data.unwrap()).unwrap().pass_id;
+
+        // Fee is taken without overflowing
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let mut balances = Balances::from_storage(deps.as_mut().storage);
+        balances.set_account_balance(&guest_canon, price);
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_pass(deps.as_mut(), mock_env(), info, pass_id).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), price / 40);
+    }
+
+    #[test]
+    fn group_order_proper() {
+        // Instantiate contract with an event
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let opener = deps.api.addr_validate("opener").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        let event_msg = CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(6u128)),
+            ..Default::default()
+        };
+        let event_id = create_event_test(&mut deps, &organiser, event_msg);
+
+        // Open an order for three tickets, quoted at the current price
+        let deadline = mock_env().block.time.seconds() + 1_000;
+        let msg = OpenGroupOrderMsg {
+            event_id: Uint128::from(event_id),
+            pks: vec![test_pk(); 3],
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            deadline,
+            ..Default::default()
+        };
+        let info = mock_info(opener.as_str(), &[]);
+        let resp = try_open_group_order(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        let order_id = from_binary::<GroupOrderIdResponse>(&resp.data.unwrap()).unwrap().order_id;
+        let resp = query_group_order(deps.as_ref(), order_id).unwrap();
+        assert_eq!(resp.target, Uint128::from(300u128));
+        assert_eq!(resp.quantity, Uint128::from(3u128));
+
+        // Contributions cannot exceed the remaining amount
+        let info = mock_info(opener.as_str(), &coins(100, "uscrt"));
+        try_contribute_group_order(deps.as_mut(), mock_env(), info, order_id, Some(Uint128::from(100u128))).unwrap();
+        let info = mock_info(friend.as_str(), &coins(500, "uscrt"));
+        assert!(try_contribute_group_order(deps.as_mut(), mock_env(), info, order_id, Some(Uint128::from(201u128))).is_err());
+        let resp = query_group_order(deps.as_ref(), order_id).unwrap();
+        assert_eq!(resp.funded, Uint128::from(100u128));
+
+        // Remaining contribution buys the tickets for the opener
+        let info = mock_info(friend.as_str(), &[]);
+        let resp = try_contribute_group_order(deps.as_mut(), mock_env(), info, order_id, None).unwrap();
+        let ticket_ids = from_binary::<TicketIdsResponse>(&resp.data.unwrap()).unwrap().ticket_ids;
+        assert_eq!(ticket_ids.len(), 3);
+        let opener_canon = deps.api.addr_canonicalize(opener.as_str()).unwrap();
+        let friend_canon = deps.api.addr_canonicalize(friend.as_str()).unwrap();
+        let guests_tickets = ReadonlyGuestsTickets::from_storage(&deps.storage);
+        assert_eq!(guests_tickets.load_tickets(&opener_canon).unwrap().len(), 3);
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&opener_canon).unwrap(), 0);
+        assert_eq!(balances.read_account_balance(&friend_canon).unwrap(), 300);
+        assert!(query_group_order(deps.as_ref(), order_id).is_err());
+
+        // Unfunded order is refunded once its deadline passes
+        let info = mock_info(opener.as_str(), &[]);
+        let resp = try_open_group_order(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let order_id = from_binary::<GroupOrderIdResponse>(&resp.data.unwrap()).unwrap().order_id;
+        let info = mock_info(friend.as_str(), &[]);
+        try_contribute_group_order(deps.as_mut(), mock_env(), info, order_id, Some(Uint128::from(50u128))).unwrap();
+        let info = mock_info(friend.as_str(), &[]);
+        assert!(try_cancel_group_order(deps.as_mut(), mock_env(), info, order_id).is_err());
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1_000);
+        let info = mock_info(friend.as_str(), &[]);
+        assert!(try_contribute_group_order(deps.as_mut(), env.clone(), info, order_id, None).is_err());
+        let info = mock_info(friend.as_str(), &[]);
+        try_cancel_group_order(deps.as_mut(), env, info, order_id).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&friend_canon).unwrap(), 300);
+        assert!(query_group_order(deps.as_ref(), order_id).is_err());
+    }
+
+    #[test]
+    fn installments_proper() {
+        // Instantiate contract with an event offering a 20% down payment and two installments
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let event_msg = CreateEventMsg {
+            price: Uint128::from(1000u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            installment_plan: Some(InstallmentPlanMsg { down_payment_bps: 2_000, installments: 2, interval: 100 }),
+            ..Default::default()
+        };
+        let event_id = create_event_test(&mut deps, &organiser, event_msg);
+        let buy = |guest: &Addr, deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            let info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+            let msg = BuyTicketMsg {
+                event_id: Uint128::from(event_id),
+                entropy: TEST_GUEST_ENTROPY.to_string(),
+                pk: test_pk(),
+                installments: Some(true),
+                ..Default::default()
+            };
+            let resp = try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+            from_binary::<TicketIdResponse>(&resp.data.unwrap()).unwrap().ticket_id
+        };
+
+        // Reserve a ticket with the down payment, it cannot be verified or transferred yet
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let ticket_id = buy(&guest, &mut deps);
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 800);
+        let resp = query_installments(deps.as_ref(), ticket_id, &guest_canon).unwrap();
+        let now = mock_env().block.time.seconds();
+        assert_eq!(resp, InstallmentsResponse {
+            paid: Uint128::from(200u128),
+            unpaid: Uint128::from(800u128),
+            next_amount: Uint128::from(400u128),
+            next_due: Some(now + 100),
+        });
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_verify_ticket(deps.as_mut(), mock_env(), info, ticket_id).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_transfer_ticket(deps.as_mut(), mock_env(), info, ticket_id, organiser.clone(), test_pk(), None).is_err());
+
+        // Paying both installments makes the ticket valid
+        for _ in 0..2 {
+            let info = mock_info(guest.as_str(), &[]);
+            try_pay_installment(deps.as_mut(), mock_env(), info, ticket_id).unwrap();
+        }
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_pay_installment(deps.as_mut(), mock_env(), info, ticket_id).is_err());
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        assert_eq!(tickets.may_load_ticket(ticket_id.u128()).unwrap().unwrap().get_state(), TICKET_VALID);
+        let info = mock_info(organiser.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, ticket_id).unwrap();
+
+        // Ticket can only be forfeited once an installment is overdue, keeping the down payment
+        let late = deps.api.addr_validate("late").unwrap();
+        let late_canon = deps.api.addr_canonicalize(late.as_str()).unwrap();
+        let ticket_id = buy(&late, &mut deps);
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_forfeit_ticket(deps.as_mut(), mock_env(), info, ticket_id).is_err());
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(101);
+        let info = mock_info(late.as_str(), &[]);
+        assert!(try_forfeit_ticket(deps.as_mut(), env.clone(), info, ticket_id).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        try_forfeit_ticket(deps.as_mut(), env, info, ticket_id).unwrap();
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        assert_eq!(tickets.may_load_ticket(ticket_id.u128()).unwrap().unwrap().get_state(), TICKET_REVOKED);
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&late_canon).unwrap(), 800);
+
+        // Cancelling refunds installments paid but not the down payment
+        let quitter = deps.api.addr_validate("quitter").unwrap();
+        let quitter_canon = deps.api.addr_canonicalize(quitter.as_str()).unwrap();
+        let ticket_id = buy(&quitter, &mut deps);
+        let info = mock_info(quitter.as_str(), &[]);
+        try_pay_installment(deps.as_mut(), mock_env(), info, ticket_id).unwrap();
+        let info = mock_info(quitter.as_str(), &[]);
+        try_cancel_installments(deps.as_mut(), mock_env(), info, ticket_id).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&quitter_canon).unwrap(), 800);
+        let events = ReadonlyEvents::from_storage(&deps.storage);
+        assert_eq!(events.may_load_event(event_id).unwrap().unwrap().get_tickets_sold(), 1);
+    }
+
+    #[test]
+    fn installments_large_price() {
+        // Down payment on a price too large to multiply by the basis points directly
+        let plan = InstallmentPlan::new(2_000, 2, 100).unwrap();
+        assert_eq!(plan.down_payment(u128::MAX).unwrap(), u128::MAX / 5);
+        assert_eq!(plan.down_payment(1000).unwrap(), 200);
+    }
+
+    #[test]
+    fn buy_ticket_invite_only() {
+        use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create invite-only event
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]).unwrap();
+        let invite_key = signing_key.verifying_key().to_bytes();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            invite_key: Some(hex::encode(invite_key)),
+            ..Default::default()
+        });
+
+        // Buying without an invite fails
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+
+        // Buying with an invite for another guest fails
+        let sign = |address: &str| {
+            // Signer hashes the message with SHA-256 itself
+            let mut message = event_id.to_be_bytes().to_vec();
+            message.extend_from_slice(address.as_bytes());
+            let signature: Signature = signing_key.sign(&message);
+            hex::encode(signature.as_ref())
+        };
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: test_pk(),
+            invite: Some(sign("other")),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+
+        // Buying with own invite succeeds
+        let msg = BuyTicketMsg { invite: Some(sign(guest.as_str())), ..msg };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn verify_guest_sessions() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create two day event
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            sessions: Some(vec![
+                SessionMsg { start: now, end: now + 100 },
+                SessionMsg { start: now + 1000, end: now + 1100 },
+            ]),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let ticket_id = Uint128::from(1u128);
+
+        // Check in to first session, ticket remains valid
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, ticket_id).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let first_secret = tickets.may_load_ticket(1).unwrap().unwrap().get_secret();
+        let secret = format!("{:X}", first_secret);
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, ticket_id);
+        try_verify_guest(deps.as_mut(), mock_env(), info, ticket_id, secret, nonce).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_VALID);
+
+        // Cannot check in to the same session twice
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_ticket(deps.as_mut(), mock_env(), info, ticket_id).is_err());
+
+        // Cannot check in between sessions
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(500);
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_ticket(deps.as_mut(), env, info, ticket_id).is_err());
+
+        // Check in to second session uses up the ticket
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1000);
+        let info = mock_info(owner.as_str(), &[]);
+        execute(deps.as_mut(), env.clone(), info, ExecuteMsg::VerifyTicket { ticket_id }).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let second_secret = tickets.may_load_ticket(1).unwrap().unwrap().get_secret();
+        assert_ne!(second_secret, first_secret);
+
+        // The first session's secret no longer verifies
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, ticket_id);
+        let resp = try_verify_guest(deps.as_mut(), env.clone(), info, ticket_id, format!("{:X}", first_secret), nonce);
+        assert_eq!(resp.unwrap().attributes[0].value, "false");
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, ticket_id);
+        try_verify_guest(deps.as_mut(), env, info, ticket_id, format!("{:X}", second_secret), nonce).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+    }
+
+    #[test]
+    fn buy_ticket_escrow() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event ending in 100 seconds with a 50 second payout delay
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            start_time: Some(now),
+            end_time: Some(now + 100),
+            payout_delay: Some(50),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Proceeds are held in escrow rather than credited to the organiser
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 0);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let event = events.may_load_event(event_id).unwrap().unwrap();
+        assert_eq!(event.get_escrow(), 200);
+        assert!(event.check_payout_open(now + 100, 50).is_err());
+        assert!(event.check_payout_open(now + 150, 50).is_ok());
+
+        // Revoking refunds the guest from escrow
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        assert_eq!(events.may_load_event(event_id).unwrap().unwrap().get_escrow(), 100);
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 900);
+    }
+
+    #[test]
+    fn payee_split_proceeds() {
+        // Instantiate contract with a 1% platform fee
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_update_fee_config(deps.as_mut(), info, 100, owner.clone()).unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Payee shares must be valid
+        register_organiser_test(&mut deps, &organiser);
+        let payees = |shares: &[(&str, u32)]| {
+            Some(shares.iter().map(|(address, bps)| PayeeMsg { address: address.to_string(), bps: *bps }).collect())
+        };
+        let msg = CreateEventMsg {
+            price: Uint128::from(103u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(mock_env().block.time.seconds()),
+            end_time: Some(mock_env().block.time.seconds() + 100),
+            ..Default::default()
+        };
+        for shares in [vec![("venue", 5_000), ("artist", 4_000)], vec![("venue", 5_000), ("venue", 5_000)], vec![("venue", 10_000), ("artist", 0)]] {
+            let info = mock_info(organiser.as_str(), &[]);
+            let msg = CreateEventMsg { payees: payees(&shares), ..msg.clone() };
+            let err = try_create_event(deps.as_mut(), info, msg).unwrap_err();
+            assert!(err.to_string().to_lowercase().contains("payee"), "{}", err);
+        }
+
+        // Create event split among venue, artist and promoter, and sell a ticket
+        let msg = CreateEventMsg { payees: payees(&[("venue", 5_000), ("artist", 3_000), ("promoter", 2_000)]), ..msg };
+        let event_id = create_event_test(&mut deps, &organiser, msg);
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Claimed proceeds less the fee are split, the venue receiving the rounding remainder
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100 + DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(organiser.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).unwrap();
+        let balance = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, address: &str| {
+            let address = deps.api.addr_canonicalize(address).unwrap();
+            ReadonlyBalances::from_storage(&deps.storage).read_account_balance(&address).unwrap()
+        };
+        assert_eq!(balance(&deps, "venue"), 52);
+        assert_eq!(balance(&deps, "artist"), 30);
+        assert_eq!(balance(&deps, "promoter"), 20);
+        assert_eq!(balance(&deps, organiser.as_str()), 0);
+
+        // Large proceeds split without overflowing
+        let events = ReadonlyEvents::from_storage(&deps.storage);
+        let event = events.may_load_event(event_id).unwrap().unwrap();
+        let amount = u128::MAX / 2;
+        let shares: Vec<u128> = event.split_proceeds(amount).unwrap().into_iter().map(|(_, share)| share).collect();
+        let artist = amount / 10 * 3 + amount % 10 * 3 / 10;
+        assert_eq!(shares, vec![amount - artist - amount / 5, artist, amount / 5]);
+    }
+
+    #[test]
+    fn claim_payout_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create event ending in 100 seconds and sell a ticket
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(now),
+            end_time: Some(now + 100),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Cannot claim before the event ends and the dispute window closes, or as someone else
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), env.clone(), info, Uint128::from(event_id)).is_err());
+        env.block.time = env.block.time.plus_seconds(DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), env.clone(), info, Uint128::from(event_id)).is_err());
+
+        // Claim after the dispute window closes
+        let info = mock_info(organiser.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env.clone(), info, Uint128::from(event_id)).unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&organiser_canon).unwrap(), 100);
+        let resp = query_payouts(deps.as_ref(), Uint128::from(event_id)).unwrap();
+        assert_eq!(resp.payouts, vec![PayoutInfo {
+            amount: Uint128::from(100u128),
+            fee: Uint128::from(0u128),
+            time: now + 100 + DEFAULT_DISPUTE_WINDOW,
+        }]);
+
+        // Nothing left to claim
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).is_err());
+    }
+
+    #[test]
+    fn claim_payout_large_escrow() {
+        // Instantiate contract with a fee and an event holding a large escrow
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_update_fee_config(deps.as_mut(), info, 250, owner.clone()).unwrap();
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            start_time: Some(now),
+            end_time: Some(now + 100),
+            ..Default::default()
+        });
+        let amount = u128::MAX / 2;
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let mut event = events.may_load_event(event_id).unwrap().unwrap();
+        event.add_escrow(amount);
+        let mut events = Events::from_storage(deps.as_mut().storage);
+        events.store_event(event_id, &event).unwrap();
+
+        // Fee is taken without overflowing
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100 + DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(organiser.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), amount / 40);
+        assert_eq!(balances.read_account_balance(&organiser_canon).unwrap(), amount - amount / 40);
+    }
+
+    #[test]
+    fn complete_event_proper() {
+        // Instantiate contract, create an event without scheduled times and sell a ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Proceeds stay in escrow until the event is completed
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+
+        // Only the organiser can complete it, and only once
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_complete_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_complete_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_complete_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+
+        // Sales close and proceeds can be claimed once the dispute window closes
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(owner.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 100);
+    }
+
+    #[test]
+    fn update_fee_config_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let treasury = deps.api.addr_validate("treasury").unwrap();
+
+        // Defaults to no fee paid to the owner
+        let resp = query_fee_config(deps.as_ref()).unwrap();
+        assert_eq!(resp, FeeConfigResponse { rate_bps: 0, recipient: owner.clone() });
+
+        // Only the owner can update, and within bounds
+        let info = mock_info(treasury.as_str(), &[]);
+        assert!(try_update_fee_config(deps.as_mut(), info, 250, treasury.clone()).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_update_fee_config(deps.as_mut(), info, MAX_FEE_BPS + 1, treasury.clone()).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_update_fee_config(deps.as_mut(), info, 250, treasury.clone()).unwrap();
+        let resp = query_fee_config(deps.as_ref()).unwrap();
+        assert_eq!(resp, FeeConfigResponse { rate_bps: 250, recipient: treasury.clone() });
+
+        // Fee is taken from payouts
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(400u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_complete_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(owner.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let treasury_canon = deps.api.addr_canonicalize(treasury.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 390);
+        assert_eq!(balances.read_account_balance(&treasury_canon).unwrap(), 10);
+    }
+
+    #[test]
+    fn set_contract_status_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let withdraw = ExecuteMsg::Withdraw { amount: Some(Uint128::from(10u128)), denom: None };
+        let deposit = ExecuteMsg::Deposit {};
+
+        // Only the owner can set the status
+        let info = mock_info(guest.as_str(), &[]);
+        let msg = ExecuteMsg::SetContractStatus { level: ContractStatusLevel::StopAll };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // Stopping purchases halts withdrawals but not deposits
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = ExecuteMsg::SetContractStatus { level: ContractStatusLevel::StopPurchases };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(execute(deps.as_mut(), mock_env(), info, withdraw.clone()).is_err());
+        let info = mock_info(guest.as_str(), &coins(10, "uscrt"));
+        execute(deps.as_mut(), mock_env(), info, deposit.clone()).unwrap();
+
+        // Stopping all halts deposits too
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = ExecuteMsg::SetContractStatus { level: ContractStatusLevel::StopAll };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let resp = query_contract_status(deps.as_ref()).unwrap();
+        assert_eq!(resp.level, ContractStatusLevel::StopAll);
+        let info = mock_info(guest.as_str(), &coins(10, "uscrt"));
+        assert!(execute(deps.as_mut(), mock_env(), info, deposit).is_err());
+
+        // Resuming allows withdrawals again
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = ExecuteMsg::SetContractStatus { level: ContractStatusLevel::NormalRun };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info(guest.as_str(), &[]);
+        execute(deps.as_mut(), mock_env(), info, withdraw).unwrap();
+    }
+
+    #[test]
+    fn register_organiser_proper() {
+        // Instantiate contract
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+
+        // Cannot create events before registering
+        let msg = CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_create_event(deps.as_mut(), info, msg.clone()).is_err());
+
+        // Registering requires the full bond
+        let info = mock_info(organiser.as_str(), &coins(DEFAULT_ORGANISER_BOND - 1, "uscrt"));
+        assert!(try_register_organiser(deps.as_mut(), info).is_err());
+        let info = mock_info(organiser.as_str(), &coins(DEFAULT_ORGANISER_BOND, "uscrt"));
+        try_register_organiser(deps.as_mut(), info).unwrap();
+        let info = mock_info(organiser.as_str(), &coins(DEFAULT_ORGANISER_BOND, "uscrt"));
+        assert!(try_register_organiser(deps.as_mut(), info).is_err());
+
+        // Create event ending in 100 seconds and sell a ticket
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            end_time: Some(now + 100),
+            start_time: Some(now),
+            ..msg.clone()
+        });
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Cannot unregister until the event settles
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100 + DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_unregister_organiser(deps.as_mut(), env.clone(), info).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env.clone(), info, Uint128::from(event_id)).unwrap();
+
+        // Unregister and receive bond back
+        let info = mock_info(organiser.as_str(), &[]);
+        let resp = try_unregister_organiser(deps.as_mut(), env, info).unwrap();
+        assert_eq!(resp.messages.len(), 1);
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_create_event(deps.as_mut(), info, msg).is_err());
+    }
+
+    #[test]
+    fn slash_organiser_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(3u128)),
+            ..Default::default()
+        });
+
+        // Guests buy one and three tickets
+        let alice = deps.api.addr_validate("alice").unwrap();
+        let bob = deps.api.addr_validate("bob").unwrap();
+        for guest in [&alice, &bob] {
+            let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+            let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+        buy_ticket_test(&mut deps, mock_env(), &alice, event_id).unwrap();
+        for _ in 0..3 {
+            buy_ticket_test(&mut deps, mock_env(), &bob, event_id).unwrap();
+        }
+
+        // Only the owner can slash
+        let info = mock_info(alice.as_str(), &[]);
+        assert!(try_slash_organiser(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_slash_organiser(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+
+        // Bond distributed pro-rata to ticket holders
+        let alice_canon = deps.api.addr_canonicalize(alice.as_str()).unwrap();
+        let bob_canon = deps.api.addr_canonicalize(bob.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&alice_canon).unwrap(), 900 + DEFAULT_ORGANISER_BOND / 4);
+        assert_eq!(balances.read_account_balance(&bob_canon).unwrap(), 700 + DEFAULT_ORGANISER_BOND * 3 / 4);
+
+        // Slashed organiser cannot slash twice, create events or unregister
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_slash_organiser(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        let msg = CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        };
+        assert!(try_create_event(deps.as_mut(), info, msg).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_unregister_organiser(deps.as_mut(), mock_env(), info).is_err());
+    }
+
+    #[test]
+    fn slash_organiser_large_prices() {
+        // Instantiate contract and sell two tickets priced near the largest amount
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let price = u128::MAX / 4;
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(price),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let mut balances = Balances::from_storage(deps.as_mut().storage);
+        balances.set_account_balance(&guest_canon, price * 2);
+        for _ in 0..2 {
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        }
+
+        // Bond is shared without overflowing
+        let info = mock_info(owner.as_str(), &[]);
+        try_slash_organiser(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), DEFAULT_ORGANISER_BOND);
+    }
+
+    #[test]
+    fn dispute_proper() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let arbiter = deps.api.addr_validate("arbiter").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Only the owner can set the dispute window, and within bounds
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_set_dispute_window(deps.as_mut(), info, 50).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_set_dispute_window(deps.as_mut(), info, 0).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_set_dispute_window(deps.as_mut(), info, MAX_DISPUTE_WINDOW + 1).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_dispute_window(deps.as_mut(), info, 50).unwrap();
+
+        // Create event ending in 100 seconds, disputable for the contract's 50 second window
+        let now = mock_env().block.time.seconds();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            start_time: Some(now),
+            end_time: Some(now + 100),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Cannot dispute before the event ends
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_open_dispute(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "No show".to_string());
+        assert!(resp.is_err());
+
+        // Dispute after the event, freezing escrow past the window
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let info = mock_info(guest.as_str(), &[]);
+        try_open_dispute(deps.as_mut(), env.clone(), info, Uint128::from(1u128), "No show".to_string()).unwrap();
+        env.block.time = env.block.time.plus_seconds(50);
+        let info = mock_info(organiser.as_str(), &[]);
+        assert!(try_claim_payout(deps.as_mut(), env.clone(), info, Uint128::from(event_id)).is_err());
+
+        // Only the owner or an arbiter can resolve
+        let info = mock_info(arbiter.as_str(), &[]);
+        let resp = try_resolve_disputes(deps.as_mut(), mock_env(), info, Uint128::from(event_id), DisputeOutcome::Refund);
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_arbiters(deps.as_mut(), info, vec![arbiter.clone()]).unwrap();
+        let info = mock_info(arbiter.as_str(), &[]);
+        try_resolve_disputes(deps.as_mut(), mock_env(), info, Uint128::from(event_id), DisputeOutcome::Refund).unwrap();
+
+        // Disputed ticket refunded, organiser can claim the rest
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 900);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_REFUNDED);
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        assert_eq!(events.may_load_event(event_id).unwrap().unwrap().get_tickets_sold(), 1);
+        let histories = ReadonlyTicketHistories::from_storage(deps.as_mut().storage);
+        assert_eq!(histories.load_history(1).unwrap().last().unwrap().get_action(), ACTION_REFUNDED);
+        let info = mock_info(organiser.as_str(), &[]);
+        try_claim_payout(deps.as_mut(), env, info, Uint128::from(event_id)).unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&organiser_canon).unwrap(), 100);
+    }
+
+    #[test]
+    fn dispute_refund_shortfall() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let organiser_canon = deps.api.addr_canonicalize(organiser.as_str()).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let mut balances = Balances::from_storage(deps.as_mut().storage);
+        balances.set_account_balance(&organiser_canon, 50);
+
+        // Create events ending in 100 seconds, each with a disputed ticket and part of their
+        // escrow drained
+        let now = mock_env().block.time.seconds();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let mut event_ids = vec![];
+        for ticket_id in 1..=2u128 {
+            let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+                price: Uint128::from(100u128),
+                max_tickets: Uint128::from(10u128),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                start_time: Some(now),
+                end_time: Some(now + 100),
+                ..Default::default()
+            });
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+            let info = mock_info(guest.as_str(), &[]);
+            try_open_dispute(deps.as_mut(), env.clone(), info, Uint128::from(ticket_id), "No show".to_string()).unwrap();
+            let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+            let mut event = events.may_load_event(event_id).unwrap().unwrap();
+            event.take_escrow(40);
+            let mut events = Events::from_storage(deps.as_mut().storage);
+            events.store_event(event_id, &event).unwrap();
+            event_ids.push(event_id);
+        }
+
+        // Organiser covers the shortfall, refunding the guest in full
+        let info = mock_info(owner.as_str(), &[]);
+        try_resolve_disputes(deps.as_mut(), env.clone(), info, Uint128::from(event_ids[0]), DisputeOutcome::Refund).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 900);
+        assert_eq!(balances.read_account_balance(&organiser_canon).unwrap(), 10);
+
+        // Resolution fails once the organiser cannot cover it
+        let info = mock_info(owner.as_str(), &[]);
+        let err = try_resolve_disputes(deps.as_mut(), env, info, Uint128::from(event_ids[1]), DisputeOutcome::Refund).unwrap_err();
+        assert_eq!(err, ContractError::InsufficientFunds { balance: 10, required: 40 });
+    }
+
+    #[test]
+    fn dispute_window_proper() {
+        // Instantiate contract, create an event without scheduled times and sell a ticket
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Disputes open once the organiser completes it, and close after the contract's window
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_open_dispute(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "No show".to_string()).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        try_complete_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DEFAULT_DISPUTE_WINDOW);
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_open_dispute(deps.as_mut(), env, info, Uint128::from(1u128), "No show".to_string()).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_open_dispute(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "No show".to_string()).unwrap();
+
+        // A payout delay longer than the contract's window holds proceeds for longer
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let mut event = events.may_load_event(event_id).unwrap().unwrap();
+        let now = mock_env().block.time.seconds();
+        event.set_disputed(false);
+        assert!(event.check_payout_open(now + DEFAULT_DISPUTE_WINDOW, DEFAULT_DISPUTE_WINDOW).is_ok());
+        event.set_payout_delay(DEFAULT_DISPUTE_WINDOW * 2);
+        assert!(event.check_payout_open(now + DEFAULT_DISPUTE_WINDOW, DEFAULT_DISPUTE_WINDOW).is_err());
+        assert!(event.check_dispute_open(now + DEFAULT_DISPUTE_WINDOW, DEFAULT_DISPUTE_WINDOW).is_ok());
+    }
+
+    #[test]
+    fn buy_ticket_insured_overflow() {
+        // Instantiate contract and create an insured event priced so the premium overflows
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(u128::MAX - 100),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            insured: Some(true),
+            ..Default::default()
+        });
+
+        // Purchase fails rather than panicking
+        let err = buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap_err();
+        assert_eq!(err, StdError::generic_err("Total price overflows").into());
+    }
+
+    #[test]
+    fn cancel_event_insured() {
+        // Instantiate contract
+        let (_, mut deps, _, _) = instantiate_test();
+        let organiser = deps.api.addr_validate("organiser").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Create insured event and buy a ticket, paying the premium
+        let event_id = create_event_test(&mut deps, &organiser, CreateEventMsg {
+            price: Uint128::from(500u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            insured: Some(true),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 490);
+        let pool = query_insurance_pool(deps.as_ref()).unwrap();
+        assert_eq!(pool.balance, Uint128::from(10u128));
+
+        // Drain part of the escrow, so the pool must cover the shortfall
+        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
+        let mut event = events.may_load_event(event_id).unwrap().unwrap();
+        event.take_escrow(5);
+        let mut events = Events::from_storage(deps.as_mut().storage);
+        events.store_event(event_id, &event).unwrap();
+
+        // Only the organiser can cancel
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_cancel_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).is_err());
+        let info = mock_info(organiser.as_str(), &[]);
+        try_cancel_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+
+        // Guest refunded face value, partly from the pool
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 990);
+        let pool = query_insurance_pool(deps.as_ref()).unwrap();
+        assert_eq!(pool.balance, Uint128::from(5u128));
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_REFUNDED);
+        let histories = ReadonlyTicketHistories::from_storage(deps.as_mut().storage);
+        assert_eq!(histories.load_history(1).unwrap().last().unwrap().get_action(), ACTION_REFUNDED);
+
+        // No more sales for a cancelled event
+        assert!(buy_ticket_test(&mut deps, mock_env(), &guest, event_id).is_err());
+    }
+
+    #[test]
+    fn cancel_event_checked_in() {
+        // Instantiate contract and buy two tickets
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+        for _ in 0..2 {
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        }
+
+        // Check in the first ticket
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let secret = ReadonlyTickets::from_storage(deps.as_mut().storage).may_load_ticket(1).unwrap().unwrap().get_secret();
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), format!("{:X}", secret), nonce).unwrap();
+
+        // Cancelling refunds only the ticket that was not checked in
+        let info = mock_info(owner.as_str(), &[]);
+        try_cancel_event(deps.as_mut(), mock_env(), info, Uint128::from(event_id)).unwrap();
+        let guest_canon = deps.api.addr_canonicalize(guest.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
+        assert_eq!(balances.read_account_balance(&guest_canon).unwrap(), 900);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+        assert_eq!(tickets.may_load_ticket(2).unwrap().unwrap().get_state(), TICKET_REFUNDED);
+    }
+
+    #[test]
+    fn verify_ticket_challenge_expiry() {
+        // Instantiate contract and buy ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            challenge_ttl: Some(60),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Challenge records when it was issued
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        assert_eq!(ticket.get_challenge_height(), mock_env().block.height);
+        assert_eq!(ticket.get_challenge_time(), mock_env().block.time.seconds());
+
+        // Expired challenge cannot be answered, but verification can be retried
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(60);
+        let secret = format!("{:X}", ticket.get_secret());
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        assert!(try_verify_guest(deps.as_mut(), env.clone(), info, Uint128::from(1u128), secret, nonce).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), env.clone(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        try_verify_guest(deps.as_mut(), env, info, Uint128::from(1u128), secret, nonce).unwrap();
+    }
+
+    #[test]
+    fn verify_ticket_reissue_challenge() {
+        // Instantiate contract and buy ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Issue a challenge, then re-issue it as if the scanner crashed
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let first_secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let data: VerifyTicketResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.challenges, 2);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        let second_secret = format!("{:X}", ticket.get_secret());
+        assert_ne!(first_secret, second_secret);
+        assert_eq!(ticket.get_challenges(), 2);
+
+        // Only the latest secret verifies
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), first_secret, nonce).unwrap();
+        assert_eq!(resp.attributes[0].value, "false");
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), second_secret, nonce).unwrap();
+    }
+
+    #[test]
+    fn verify_guest_nonce_replay() {
+        // Instantiate contract and buy ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+
+        // Issue two challenges, recording the first's secret and nonce
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let first_secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let first_nonce = nonce_test(&deps, Uint128::from(1u128));
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let second_secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let second_nonce = nonce_test(&deps, Uint128::from(1u128));
+        assert_ne!(first_nonce, second_nonce);
+
+        // Replaying the first check-in, or pairing the new secret with the old nonce, fails
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), first_secret, first_nonce.clone());
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), second_secret.clone(), first_nonce);
+        assert!(resp.is_err());
+
+        // Current secret and nonce verify once, consuming the nonce
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), second_secret, second_nonce).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_nonce(), 0);
+    }
+
+    #[test]
+    fn verify_guest_lockout() {
+        // Instantiate contract and buy ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            challenge_ttl: Some(3600),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        let secret = format!("{:X}", ticket.get_secret());
+        let wrong_secret = format!("{:X}", ticket.get_secret() ^ 1);
+
+        // Wrong answers are recorded until the ticket locks
+        for attempt in 1..=MAX_VERIFY_ATTEMPTS {
+            let info = mock_info(owner.as_str(), &[]);
+            let nonce = nonce_test(&deps, Uint128::from(1u128));
+            let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), wrong_secret.clone(), nonce);
+            let resp = resp.unwrap();
+            assert_eq!(resp.attributes[0].value, "false");
+            assert_eq!(resp.attributes[1].value, attempt.to_string());
+        }
+
+        // Locked ticket rejects even the right secret and new challenges
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        assert!(try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret.clone(), nonce).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+
+        // Lock lifts after the cooldown
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(VERIFY_LOCKOUT);
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let resp = try_verify_guest(deps.as_mut(), env, info, Uint128::from(1u128), secret, nonce).unwrap();
+        assert_eq!(resp.attributes[0].value, "true");
+    }
+
+    #[test]
+    fn verify_guest_signature_proper() {
+        use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+
+        // Buy a ticket with a secp256k1 key and one with an x25519 key
+        let signing_key = SigningKey::from_bytes(&[5u8; 32]).unwrap();
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: hex::encode(signing_key.verifying_key().to_bytes()),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let sign = |ticket_id: u128, nonce: u64| {
+            let mut message = ticket_id.to_be_bytes().to_vec();
+            message.extend_from_slice(&nonce.to_be_bytes());
+            let signature: Signature = signing_key.sign(&message);
+            hex::encode(signature.as_ref())
+        };
+        let nonce = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, ticket_id: u128| {
+            u64::from_str_radix(&nonce_test(deps, Uint128::from(ticket_id)), 16).unwrap()
+        };
+
+        // Signatures are only accepted once the contract has issued a challenge
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest_signature(deps.as_mut(), mock_env(), info, Uint128::from(1u128), sign(1, 1));
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let issued = nonce(&deps, 1);
+
+        // Signature over another nonce is recorded as a failed attempt
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest_signature(deps.as_mut(), mock_env(), info, Uint128::from(1u128), sign(1, issued + 1)).unwrap();
+        assert_eq!(resp.attributes[0].value, "false");
+
+        // Signed nonce checks the guest in without a secret, consuming the nonce
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest_signature(deps.as_mut(), mock_env(), info, Uint128::from(1u128), sign(1, issued)).unwrap();
+        assert_eq!(resp.attributes[0].value, "true");
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let ticket = tickets.may_load_ticket(1).unwrap().unwrap();
+        assert_eq!(ticket.get_state(), TICKET_USED);
+        assert_eq!(ticket.get_nonce(), 0);
+
+        // Tickets with other key types must use the secret round trip
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(2u128)).unwrap();
+        let signature = sign(2, nonce(&deps, 2));
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest_signature(deps.as_mut(), mock_env(), info, Uint128::from(2u128), signature);
+        assert!(resp.is_err());
+    }
+
+    #[test]
+    fn qr_payload_proper() {
+        use ed25519_dalek::Signer;
+
+        // Instantiate contract and buy ticket with an ed25519 key
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: hex::encode(signing_key.verifying_key().to_bytes()),
+            pk_type: Some(PublicKeyType::Ed25519),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Request payload, signed with the ticket key
+        let expiry = mock_env().block.time.seconds() + 600;
+        let signature = hex::encode(signing_key.sign(&qr_request_hash(1, expiry)).to_bytes());
+        let resp = query_qr_payload(deps.as_ref(), mock_env(), Uint128::from(1u128), expiry, signature).unwrap();
+        let payload = resp.payload;
+
+        // Wrong signature is rejected
+        let resp = query_qr_payload(deps.as_ref(), mock_env(), Uint128::from(1u128), expiry, hex::encode([0u8; 64]));
+        assert!(resp.is_err());
+
+        // Tampered payload or MAC is rejected
+        for index in [39, QR_PAYLOAD_LEN - 1] {
+            let mut tampered = Binary::from_base64(&payload).unwrap().to_vec();
+            tampered[index] ^= 1;
+            let info = mock_info(owner.as_str(), &[]);
+            let resp = try_verify_qr_payload(deps.as_mut(), mock_env(), info, Binary::from(tampered).to_base64());
+            assert!(resp.is_err());
+        }
+
+        // Payload checks the guest in once
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_qr_payload(deps.as_mut(), mock_env(), info, payload.clone()).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_qr_payload(deps.as_mut(), mock_env(), info, payload).is_err());
+    }
+
+    #[test]
+    fn ticket_code_proper() {
+        use ed25519_dalek::Signer;
+
+        // Instantiate contract and buy ticket with an ed25519 key
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: hex::encode(signing_key.verifying_key().to_bytes()),
+            pk_type: Some(PublicKeyType::Ed25519),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Guest fetches the current code with a signature for this window
+        let window = mock_env().block.time.seconds() / CODE_WINDOW;
+        let signature = hex::encode(signing_key.sign(&code_request_hash(1, window)).to_bytes());
+        let resp = query_ticket_code(deps.as_ref(), mock_env(), Uint128::from(1u128), signature.clone()).unwrap();
+        assert_eq!(resp.code.len(), CODE_DIGITS as usize);
+        assert_eq!(resp.valid_until, (window + 1) * CODE_WINDOW);
+        let resp_bad = query_ticket_code(deps.as_ref(), mock_env(), Uint128::from(1u128), hex::encode([0u8; 64]));
+        assert!(resp_bad.is_err());
+
+        // Code rotates with the window
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(CODE_WINDOW * 2);
+        let signature_next = hex::encode(signing_key.sign(&code_request_hash(1, window + 2)).to_bytes());
+        let resp_next = query_ticket_code(deps.as_ref(), env.clone(), Uint128::from(1u128), signature_next).unwrap();
+        assert_ne!(resp.code, resp_next.code);
+
+        // Expired or wrong codes don't check in and count towards a lockout
+        let info = mock_info(owner.as_str(), &[]);
+        let resp_verify = try_verify_ticket_code(deps.as_mut(), env, info, Uint128::from(1u128), resp.code.clone()).unwrap();
+        assert_eq!(resp_verify.attributes[0].value, "false");
+        assert_eq!(resp_verify.attributes[1].value, "1");
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_ticket_code(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "12ab".to_string()).is_err());
+
+        // Code from the previous window is still accepted
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(CODE_WINDOW);
+        let info = mock_info(owner.as_str(), &[]);
+        let resp_verify = try_verify_ticket_code(deps.as_mut(), env, info, Uint128::from(1u128), resp.code).unwrap();
+        assert_eq!(resp_verify.attributes[0].value, "true");
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+    }
+
+    #[test]
+    fn transfer_ticket_rotates_secrets() {
+        use ed25519_dalek::Signer;
+
+        // Instantiate contract and buy ticket with an ed25519 key
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        let guest_pk = hex::encode(signing_key.verifying_key().to_bytes());
+        let msg = BuyTicketMsg {
+            event_id: Uint128::from(event_id),
+            entropy: TEST_GUEST_ENTROPY.to_string(),
+            pk: guest_pk.clone(),
+            pk_type: Some(PublicKeyType::Ed25519),
+            ..Default::default()
+        };
+        let info = mock_info(guest.as_str(), &[]);
+        try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Guest learns the current code before transferring
+        let window = mock_env().block.time.seconds() / CODE_WINDOW;
+        let signature = hex::encode(signing_key.sign(&code_request_hash(1, window)).to_bytes());
+        let code = query_ticket_code(deps.as_ref(), mock_env(), Uint128::from(1u128), signature).unwrap().code;
+
+        // Transfer must come with a new key
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_transfer_ticket(
+            deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend.clone(), guest_pk, Some(PublicKeyType::Ed25519),
+        );
+        assert!(resp.is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_transfer_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128), friend, test_pk(), None).unwrap();
+
+        // Previous guest's code no longer checks in
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_ticket_code(deps.as_mut(), mock_env(), info, Uint128::from(1u128), code).unwrap();
+        assert_eq!(resp.attributes[0].value, "false");
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_VALID);
+    }
+
+    #[test]
+    fn viewing_key_proper() {
+        // Instantiate contract and deposit
+        let (_, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Queries fail before a key is set
+        let msg = QueryMsg::Balance { address: guest.clone(), key: "api_key_".to_string() };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Create a key and query balance with it
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_create_viewing_key(deps.as_mut(), info, "e9b3a1".to_string()).unwrap();
+        let key = from_binary::<ViewingKeyResponse>(&resp.data.unwrap()).unwrap().key;
+        assert!(key.starts_with(VIEWING_KEY_PREFIX));
+        let msg = QueryMsg::Balance { address: guest.clone(), key: key.clone() };
+        let balance: BalanceResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(balance.balance, Uint128::from(1000u128));
+
+        // Key is bound to the address that created it
+        let other = deps.api.addr_validate("other").unwrap();
+        let msg = QueryMsg::Tickets { address: other, key: key.clone(), start_after: None, limit: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Setting a key replaces the old one
+        let info = mock_info(guest.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "my key".to_string()).unwrap();
+        let msg = QueryMsg::Events { address: guest.clone(), key, status: None, start_after: None, limit: None };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let msg = QueryMsg::Events { address: guest, key: "my key".to_string(), status: None, start_after: None, limit: None };
+        query(deps.as_ref(), mock_env(), msg).unwrap();
+    }
+
+    #[test]
+    fn tickets_pagination() {
+        // Instantiate contract and buy three tickets
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(3u128)),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        }
+        let info = mock_info(guest.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+
+        // Page through the tickets
+        let tickets_query = |start_after: Option<u128>| QueryMsg::Tickets {
+            address: guest.clone(),
+            key: "key".to_string(),
+            start_after: start_after.map(Uint128::from),
+            limit: Some(2),
+        };
+        let resp: TicketsResponse = from_binary(&query(deps.as_ref(), mock_env(), tickets_query(None)).unwrap()).unwrap();
+        let ids: Vec<Uint128> = resp.tickets.iter().map(|ticket| ticket.ticket_id).collect();
+        assert_eq!(ids, vec![Uint128::from(1u128), Uint128::from(2u128)]);
+        let resp: TicketsResponse = from_binary(&query(deps.as_ref(), mock_env(), tickets_query(Some(2))).unwrap()).unwrap();
+        assert_eq!(resp.tickets, vec![TicketInfo {
+            ticket_id: Uint128::from(3u128),
+            event_id: Uint128::from(event_id),
+            tier: DEFAULT_TIER.to_string(),
+            seat: None,
+            state: TicketState::Valid,
+        }]);
+        let resp: TicketsResponse = from_binary(&query(deps.as_ref(), mock_env(), tickets_query(Some(3))).unwrap()).unwrap();
+        assert!(resp.tickets.is_empty());
+    }
+
+    #[test]
+    fn balances_proper() {
+        // Instantiate contract and deposit from two accounts
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let other = deps.api.addr_validate("other").unwrap();
+        for (address, amount) in [(&guest, 1000), (&other, 2000)] {
+            let deposit_info = mock_info(address.as_str(), &coins(amount, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+
+        // Accounts can only query their own balance
+        let msg = QueryMsg::Balances { addresses: vec![guest.clone()], address: guest.clone(), key: "key".to_string() };
+        let resp: BalancesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.balances, vec![AccountBalance { address: guest.clone(), balance: Uint128::from(1000u128) }]);
+        let msg = QueryMsg::Balances { addresses: vec![guest.clone(), other.clone()], address: guest.clone(), key: "key".to_string() };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // The owner can query any accounts
+        let msg = QueryMsg::Balances { addresses: vec![guest.clone(), other.clone()], address: owner, key: "key".to_string() };
+        let resp: BalancesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.balances, vec![
+            AccountBalance { address: guest, balance: Uint128::from(1000u128) },
+            AccountBalance { address: other, balance: Uint128::from(2000u128) },
+        ]);
+    }
+
+    #[test]
+    fn permit_proper() {
+        use crate::msg::{PermitPubKey, PermitSignature};
+        use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+        // Instantiate contract and deposit from the permit signer's address
+        let (_, mut deps, _, _) = instantiate_test();
+        let signing_key = SigningKey::from_bytes(&[5u8; 32]).unwrap();
+        let pubkey = signing_key.verifying_key().to_bytes().to_vec();
+        let address = bech32::encode(ADDRESS_PREFIX, Ripemd160::digest(Sha256::digest(&pubkey)).to_base32(), Variant::Bech32).unwrap();
+        let deposit_info = mock_info(&address, &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+
+        // Sign permit for balance queries on this contract
+        let sign_permit = |params: PermitParams| {
+            let signature: Signature = signing_key.sign(to_binary(&permit_sign_doc(&params)).unwrap().as_slice());
+            Permit {
+                params,
+                signature: PermitSignature {
+                    pub_key: PermitPubKey { r#type: "tendermint/PubKeySecp256k1".to_string(), value: Binary::from(pubkey.clone()) },
+                    signature: Binary::from(signature.as_ref()),
+                },
+            }
+        };
+        let params = PermitParams {
+            allowed_tokens: vec![mock_env().contract.address.to_string()],
+            chain_id: mock_env().block.chain_id,
+            permissions: vec![Permission::Balance],
+            permit_name: "wallet".to_string(),
+        };
+        let permit = sign_permit(params.clone());
+
+        // Permit authenticates the balance query
+        let msg = QueryMsg::WithPermit { permit: permit.clone(), query: QueryWithPermit::Balance {} };
+        let balance: BalanceResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(balance.balance, Uint128::from(1000u128));
+
+        // But not queries it lacks permission for, or a tampered permit
+        let msg = QueryMsg::WithPermit { permit: permit.clone(), query: QueryWithPermit::Tickets { start_after: None, limit: None } };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let mut tampered = permit.clone();
+        tampered.params.permissions.push(Permission::Owner);
+        let msg = QueryMsg::WithPermit { permit: tampered, query: QueryWithPermit::Tickets { start_after: None, limit: None } };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Permits for other contracts are rejected
+        let other_contract = sign_permit(PermitParams { allowed_tokens: vec!["other".to_string()], ..params });
+        let msg = QueryMsg::WithPermit { permit: other_contract, query: QueryWithPermit::Balance {} };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Revoked permits are rejected
+        let info = mock_info(&address, &[]);
+        try_revoke_permit(deps.as_mut(), info, "wallet".to_string()).unwrap();
+        let msg = QueryMsg::WithPermit { permit, query: QueryWithPermit::Balance {} };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+    }
+
+    #[test]
+    fn ticket_validity_proper() {
+        use crate::msg::{PermitPubKey, PermitSignature};
+        use k256::ecdsa::{signature::Signer, Signature, SigningKey};
 
-    // Check if secret is correct
-    match ticket.try_verify(secret_raw) {
-        Ok(()) => {
-            let mut tickets = Tickets::from_storage(deps.storage);
-            tickets.store_ticket(ticket_id_raw, &ticket);
-            Ok(Response::default())
+        // Instantiate contract and buy tickets to two events as the permit signer
+        let (owner, mut deps, _, _) = instantiate_test();
+        let signing_key = SigningKey::from_bytes(&[5u8; 32]).unwrap();
+        let pubkey = signing_key.verifying_key().to_bytes().to_vec();
+        let address = bech32::encode(ADDRESS_PREFIX, Ripemd160::digest(Sha256::digest(&pubkey)).to_base32(), Variant::Bech32).unwrap();
+        let guest = deps.api.addr_validate(&address).unwrap();
+        let other = deps.api.addr_validate("other").unwrap();
+        for address in [&guest, &other] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
         }
-        Err(err) => Err(err),
-    }
-}
+        let mut event_ids = vec![];
+        for _ in 0..2 {
+            event_ids.push(create_event_test(&mut deps, &owner, CreateEventMsg {
+                price: Uint128::from(100u128),
+                max_tickets: Uint128::from(10u128),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                ..Default::default()
+            }));
+        }
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_ids[0]).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_ids[1]).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &other, event_ids[0]).unwrap();
 
-fn query_event_sold_out(deps: Deps, event_id: Uint128) -> StdResult<SoldOutResponse> {
-    let event_id_raw = event_id.u128();
-    let events = ReadonlyEvents::from_storage(deps.storage);
-    match events.may_load_event(event_id_raw) {
-        Some(event) => Ok(SoldOutResponse {
-            sold_out: event.is_sold_out(),
-        }),
-        None => Err(StdError::generic_err(format!("Event does not exist",))),
+        // Sign permit granting only ticket validity checks
+        let params = PermitParams {
+            allowed_tokens: vec![mock_env().contract.address.to_string()],
+            chain_id: mock_env().block.chain_id,
+            permissions: vec![Permission::TicketValidity],
+            permit_name: "hotel".to_string(),
+        };
+        let signature: Signature = signing_key.sign(to_binary(&permit_sign_doc(&params)).unwrap().as_slice());
+        let permit = Permit {
+            params,
+            signature: PermitSignature {
+                pub_key: PermitPubKey { r#type: "tendermint/PubKeySecp256k1".to_string(), value: Binary::from(pubkey) },
+                signature: Binary::from(signature.as_ref()),
+            },
+        };
+        let validity = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, ticket_id: u128| {
+            let msg = QueryMsg::WithPermit {
+                permit: permit.clone(),
+                query: QueryWithPermit::TicketValidity { ticket_id: Uint128::from(ticket_id) },
+            };
+            from_binary::<TicketValidityResponse>(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap().valid
+        };
+
+        // Signer's tickets are valid, others' and unknown tickets are not
+        assert!(validity(&deps, 1));
+        assert!(validity(&deps, 2));
+        assert!(!validity(&deps, 3));
+        assert!(!validity(&deps, 99));
+
+        // Revoked tickets and tickets to cancelled events are not valid
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        assert!(!validity(&deps, 1));
+        let info = mock_info(owner.as_str(), &[]);
+        try_cancel_event(deps.as_mut(), mock_env(), info, Uint128::from(event_ids[1])).unwrap();
+        assert!(!validity(&deps, 2));
+
+        // The permit grants nothing else
+        let msg = QueryMsg::WithPermit { permit, query: QueryWithPermit::Tickets { start_after: None, limit: None } };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
     }
-}
 
-fn query_balance(deps: Deps, address: Addr) -> StdResult<BalanceResponse> {
-    let address_canon = deps.api.addr_canonicalize(address.as_str())?;
-    let balances = ReadonlyBalances::from_storage(deps.storage);
-    Ok(BalanceResponse {
-        balance: Uint128::from(balances.read_account_balance(&address_canon)),
-    })
-}
+    #[test]
+    fn private_metadata_proper() {
+        // Instantiate contract and create event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let stranger = deps.api.addr_validate("stranger").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
 
-fn query_events(deps: Deps, address: Addr) -> StdResult<EventsResponse> {
-    let address_canon = deps.api.addr_canonicalize(address.as_str())?;
-    let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.storage);
-    let this_organisers_events = organisers_events.load_events(&address_canon);
-    let events = ReadonlyEvents::from_storage(deps.storage);
+        // Only the organiser can set metadata
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_set_private_metadata(deps.as_mut(), info, Uint128::from(event_id), "Door code 1234".to_string());
+        assert!(resp.is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        try_set_private_metadata(deps.as_mut(), info, Uint128::from(event_id), "Door code 1234".to_string()).unwrap();
 
-    let mut events_vec = vec![];
-    let mut tickets_vec = vec![];
-    for event_id in this_organisers_events {
+        // Set viewing keys
+        for address in [&guest, &stranger] {
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let metadata_query = |address: &Addr| QueryMsg::PrivateMetadata {
+            event_id: Uint128::from(event_id),
+            address: address.clone(),
+            key: "key".to_string(),
+        };
 
-        let event = events.may_load_event(event_id).unwrap();
-        events_vec.push(Uint128::from(event_id));
-        tickets_vec.push(Uint128::from(event.get_tickets_left()));
+        // Guests without a ticket cannot see metadata
+        assert!(query(deps.as_ref(), mock_env(), metadata_query(&guest)).is_err());
+
+        // Ticket holders can
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        let resp: PrivateMetadataResponse = from_binary(&query(deps.as_ref(), mock_env(), metadata_query(&guest)).unwrap()).unwrap();
+        assert_eq!(resp.metadata, Some("Door code 1234".to_string()));
+        assert!(query(deps.as_ref(), mock_env(), metadata_query(&stranger)).is_err());
     }
-    Ok(EventsResponse { events: events_vec, tickets_left: tickets_vec })
-}
 
-fn query_tickets(deps: Deps, address: Addr) -> StdResult<TicketsResponse> {
-    let address_canon = deps.api.addr_canonicalize(address.as_str())?;
-    let guests_tickets = ReadonlyGuestsTickets::from_storage(deps.storage);
-    let this_guests_tickets = guests_tickets.load_tickets(&address_canon);
-    let tickets = ReadonlyTickets::from_storage(deps.storage);
+    #[test]
+    fn attendees_proper() {
+        // Instantiate contract and buy tickets to an event that hides guests and one that shares them
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let hidden_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        let shared_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            share_guests: Some(true),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, hidden_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, shared_id).unwrap();
 
-    let mut tickets_vec = vec![];
-    let mut events_vec = vec![];
-    let mut state_vec: Vec<Uint128> = vec![];
-    for ticket_id in this_guests_tickets {
+        // Check in the shared event's ticket
+        let ticket_id = Uint128::from(2u128);
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, ticket_id).unwrap();
+        let secret = ReadonlyTickets::from_storage(deps.as_mut().storage).may_load_ticket(2).unwrap().unwrap().get_secret();
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, ticket_id);
+        try_verify_guest(deps.as_mut(), mock_env(), info, ticket_id, format!("{:X}", secret), nonce).unwrap();
 
-        // Load ticket
-        let ticket = tickets.may_load_ticket(ticket_id).unwrap();
+        // Set viewing keys
+        for address in [&owner, &guest] {
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let attendees_query = |address: &Addr, event_id: u128| QueryMsg::Attendees {
+            event_id: Uint128::from(event_id),
+            start_after: None,
+            limit: None,
+            address: address.clone(),
+            key: "key".to_string(),
+        };
 
-        // Create return vectors
-        tickets_vec.push(Uint128::from(ticket_id));
-        events_vec.push(Uint128::from(ticket.get_event_id()));
-        state_vec.push(Uint128::from(ticket.get_state()));
-    }
-    Ok(TicketsResponse {
-        tickets: tickets_vec,
-        events: events_vec,
-        states: state_vec,
-    })
-}
+        // Only organisers can list attendees
+        assert!(query(deps.as_ref(), mock_env(), attendees_query(&guest, hidden_id)).is_err());
 
-#[cfg(test)]
-mod tests {
+        // Guests are hidden unless the event shares them
+        let resp: AttendeesResponse = from_binary(&query(deps.as_ref(), mock_env(), attendees_query(&owner, hidden_id)).unwrap()).unwrap();
+        assert_eq!(resp.attendees.len(), 1);
+        assert_eq!(resp.attendees[0].ticket_id, Uint128::from(1u128));
+        assert_eq!(resp.attendees[0].guest, None);
+        assert!(!resp.attendees[0].checked_in);
+        let resp: AttendeesResponse = from_binary(&query(deps.as_ref(), mock_env(), attendees_query(&owner, shared_id)).unwrap()).unwrap();
+        assert_eq!(resp.attendees.len(), 1);
+        assert_eq!(resp.attendees[0].ticket_id, ticket_id);
+        assert_eq!(resp.attendees[0].guest, Some(guest));
+        assert_eq!(resp.attendees[0].state, TicketState::Used);
+        assert!(resp.attendees[0].checked_in);
 
-    use super::*;
+        // Paging past the last ticket returns nothing
+        let msg = QueryMsg::Attendees {
+            event_id: Uint128::from(shared_id),
+            start_after: Some(ticket_id),
+            limit: Some(5),
+            address: owner,
+            key: "key".to_string(),
+        };
+        let resp: AttendeesResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(resp.attendees.is_empty());
+    }
 
-    use crate::state::{get_config_readonly, ReadonlyBalances};
-    use cosmwasm_std::coins;
-    use cosmwasm_std::testing::{
-        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
-    };
-    use cosmwasm_std::{Addr, Api, Empty, OwnedDeps};
+    #[test]
+    fn check_in_stats_proper() {
+        // Instantiate contract and buy three tickets
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(3u128)),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        }
 
-    fn instantiate_test() -> (
-        Addr,
-        OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
-        MessageInfo,
-        InstantiateMsg,
-    ) {
-        let mut deps = mock_dependencies();
+        // Check in two tickets, ten minutes apart
+        for (ticket_id, offset) in [(1u128, 0u64), (2, 600)] {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(offset);
+            let info = mock_info(owner.as_str(), &[]);
+            try_verify_ticket(deps.as_mut(), env.clone(), info, Uint128::from(ticket_id)).unwrap();
+            let secret = ReadonlyTickets::from_storage(deps.as_mut().storage).may_load_ticket(ticket_id).unwrap().unwrap().get_secret();
+            let info = mock_info(owner.as_str(), &[]);
+            let nonce = nonce_test(&deps, Uint128::from(ticket_id));
+            try_verify_guest(deps.as_mut(), env, info, Uint128::from(ticket_id), format!("{:X}", secret), nonce).unwrap();
+        }
 
-        let owner = deps.api.addr_validate("owner").unwrap();
-        let info = mock_info(owner.as_str(), &coins(1000, "earth"));
-        let msg = InstantiateMsg {};
+        // Only organisers can see stats, in buckets of at least a minute
+        for address in [&owner, &guest] {
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let stats_query = |address: &Addr, bucket: Option<u64>| QueryMsg::CheckInStats {
+            event_id: Uint128::from(event_id),
+            bucket,
+            start_after: None,
+            limit: None,
+            address: address.clone(),
+            key: "key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), stats_query(&guest, None)).is_err());
+        assert!(query(deps.as_ref(), mock_env(), stats_query(&owner, Some(10))).is_err());
 
-        let res = instantiate(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
-        assert_eq!(0, res.messages.len());
+        // Check-ins fall into separate buckets
+        let resp: CheckInStatsResponse = from_binary(&query(deps.as_ref(), mock_env(), stats_query(&owner, None)).unwrap()).unwrap();
+        assert_eq!(resp.tickets_sold, Uint128::from(3u128));
+        assert_eq!(resp.checked_in, Uint128::from(2u128));
+        assert_eq!(resp.check_ins, Uint128::from(2u128));
+        let now = mock_env().block.time.seconds();
+        assert_eq!(resp.buckets, vec![
+            CheckInBucket { start: now - now % 300, check_ins: Uint128::from(1u128) },
+            CheckInBucket { start: (now + 600) - (now + 600) % 300, check_ins: Uint128::from(1u128) },
+        ]);
+        assert_eq!(resp.next_start_after, None);
 
-        return (owner, deps, info, msg);
+        // Stats can be paged through the event's tickets
+        let page_query = |start_after: Option<u128>, limit: u32| QueryMsg::CheckInStats {
+            event_id: Uint128::from(event_id),
+            bucket: None,
+            start_after: start_after.map(Uint128::from),
+            limit: Some(limit),
+            address: owner.clone(),
+            key: "key".to_string(),
+        };
+        let resp: CheckInStatsResponse = from_binary(&query(deps.as_ref(), mock_env(), page_query(None, 2)).unwrap()).unwrap();
+        assert_eq!(resp.checked_in, Uint128::from(2u128));
+        assert_eq!(resp.next_start_after, Some(Uint128::from(2u128)));
+        let resp: CheckInStatsResponse = from_binary(&query(deps.as_ref(), mock_env(), page_query(Some(2), 2)).unwrap()).unwrap();
+        assert_eq!(resp.checked_in, Uint128::from(0u128));
+        assert_eq!(resp.check_ins, Uint128::from(0u128));
+        assert_eq!(resp.next_start_after, None);
+
+        // A limit of zero still pages one ticket at a time rather than scanning them all
+        let resp: CheckInStatsResponse = from_binary(&query(deps.as_ref(), mock_env(), page_query(None, 0)).unwrap()).unwrap();
+        assert_eq!(resp.checked_in, Uint128::from(1u128));
+        assert_eq!(resp.next_start_after, Some(Uint128::from(1u128)));
     }
 
     #[test]
-    fn instantiate_proper() {
-        let (owner, deps, _, _) = instantiate_test();
+    fn attendance_proof_proper() {
+        // Instantiate contract and buy tickets to two events
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let mut event_ids = vec![];
+        for _ in 0..2 {
+            let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+                price: Uint128::from(100u128),
+                max_tickets: Uint128::from(10u128),
+                entropy: TEST_EVENT_ENTROPY.to_string(),
+                ..Default::default()
+            });
+            buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+            event_ids.push(event_id);
+        }
+        let info = mock_info(guest.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        let proof_query = || QueryMsg::AttendanceProof {
+            ticket_id: Uint128::from(1u128),
+            address: guest.clone(),
+            key: "key".to_string(),
+        };
 
-        // Check if owner is correct
-        let config = get_config_readonly(&deps.storage).load().unwrap();
-        assert_eq!(deps.api.addr_humanize(config.get_owner()).unwrap(), owner);
+        // No proof before check-in
+        assert!(query(deps.as_ref(), mock_env(), proof_query()).is_err());
+
+        // Check in and get proof
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret, nonce).unwrap();
+        let resp: AttendanceProofResponse = from_binary(&query(deps.as_ref(), mock_env(), proof_query()).unwrap()).unwrap();
+        assert_eq!(resp.event_id, Uint128::from(event_ids[0]));
+
+        // Third party verifies proof for the attended event only
+        let verify = |event_id: u128, proof: String| {
+            let msg = QueryMsg::VerifyAttendance { event_id: Uint128::from(event_id), proof };
+            from_binary::<VerifyAttendanceResponse>(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap().attended
+        };
+        assert!(verify(event_ids[0], resp.proof.clone()));
+        assert!(!verify(event_ids[1], resp.proof.clone()));
+        let mut forged = hex::decode(&resp.proof).unwrap();
+        forged[0] ^= 1;
+        assert!(!verify(event_ids[0], hex::encode(forged)));
     }
 
     #[test]
-    fn deposit_proper() {
-        // Instantiate contract
+    fn two_factor_proper() {
+        // Instantiate contract and buy ticket to a two-factor event
         let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            two_factor: Some(true),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
 
-        // Deposit tokens
-        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
-        let _deposit_resp = try_deposit(deps.as_mut(), deposit_info).unwrap();
+        // Guest cannot confirm before a challenge is issued
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_confirm_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
 
-        // Check if balance increased
-        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
-        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
-        let owner_balance = balances.read_account_balance(&owner_canon);
-        assert_eq!(owner_balance, 1000);
+        // Secret alone does not check in
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret.clone(), nonce.clone());
+        assert!(resp.is_err());
+
+        // Only the guest can confirm
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_confirm_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        try_confirm_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+
+        // Confirmation lapses after the window
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(TWO_FACTOR_WINDOW);
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest(deps.as_mut(), env, info, Uint128::from(1u128), secret.clone(), nonce.clone());
+        assert!(resp.is_err());
+
+        // Secret with a recent confirmation checks in
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret, nonce).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
     }
 
     #[test]
-    fn withdraw_proper() {
-        // Instantiate contract
+    fn offline_checkins_proper() {
+        // Instantiate contract and buy two tickets
         let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let friend = deps.api.addr_validate("friend").unwrap();
+        for address in [&guest, &friend] {
+            let deposit_info = mock_info(address.as_str(), &coins(1000, "uscrt"));
+            let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        }
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &friend, event_id).unwrap();
 
-        // Deposit tokens
-        let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
-        let _deposit_resp = try_deposit(deps.as_mut(), deposit_info).unwrap();
+        // Only organisers can issue codes
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_issue_offline_codes(deps.as_mut(), info, Uint128::from(event_id)).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_issue_offline_codes(deps.as_mut(), info, Uint128::from(event_id)).unwrap();
+        let codes = from_binary::<OfflineCodesResponse>(&resp.data.unwrap()).unwrap().codes;
+        assert_eq!(codes.len(), 2);
 
-        // Withdraw tokens
-        let deposit_info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-        let _deposit_resp =
-            try_withdraw(deps.as_mut(), deposit_info, Uint128::from(500u128)).unwrap();
+        // Guest can fetch their own code
+        let info = mock_info(guest.as_str(), &[]);
+        try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        let msg = QueryMsg::OfflineCode { ticket_id: Uint128::from(1u128), address: guest, key: "key".to_string() };
+        let resp: OfflineCodeResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(resp.code, Some(codes[0].code.clone()));
 
-        // Check if balance increased
-        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
-        let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
-        let owner_balance = balances.read_account_balance(&owner_canon);
-        assert_eq!(owner_balance, 500);
+        // Reconcile one valid and one wrong code
+        let checkins = vec![
+            codes[0].clone(),
+            OfflineCheckin { ticket_id: Uint128::from(2u128), code: "0".to_string() },
+        ];
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_submit_offline_checkins(deps.as_mut(), mock_env(), info, Uint128::from(event_id), checkins).unwrap();
+        let resp: OfflineCheckinsResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(resp.checked_in, vec![Uint128::from(1u128)]);
+        assert_eq!(resp.rejected, vec![Uint128::from(2u128)]);
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+        assert_eq!(tickets.may_load_ticket(2).unwrap().unwrap().get_state(), TICKET_VALID);
+
+        // Codes are one-time
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_submit_offline_checkins(deps.as_mut(), mock_env(), info, Uint128::from(event_id), vec![codes[0].clone()]).unwrap();
+        let resp: OfflineCheckinsResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(resp.rejected, vec![Uint128::from(1u128)]);
+
+        // Checking in online uses up the offline code
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(2u128)).unwrap();
+        let secret = ReadonlyTickets::from_storage(deps.as_mut().storage).may_load_ticket(2).unwrap().unwrap().get_secret();
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, Uint128::from(2u128));
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(2u128), format!("{:X}", secret), nonce).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_submit_offline_checkins(deps.as_mut(), mock_env(), info, Uint128::from(event_id), vec![codes[1].clone()]).unwrap();
+        let resp: OfflineCheckinsResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(resp.rejected, vec![Uint128::from(2u128)]);
+        let histories = ReadonlyTicketHistories::from_storage(deps.as_mut().storage);
+        let check_ins = histories.load_history(2).unwrap().iter().filter(|record| record.get_action() == ACTION_CHECKED_IN).count();
+        assert_eq!(check_ins, 1);
     }
 
     #[test]
-    fn create_event_proper() {
-        // Instantiate contract
+    fn guest_check_in_proper() {
+        // Instantiate contract and buy ticket
         let (owner, mut deps, _, _) = instantiate_test();
-        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
 
-        // Create event
-        let price = Uint128::from(500u128);
-        let max_tickets = Uint128::from(500u128);
-        let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-        let entropy = "986192837319283719".to_string();
-        let mut resp = try_create_event(deps.as_mut(), info, price, max_tickets, entropy).unwrap();
+        // Organiser cannot acknowledge before the guest checks in, nor can others check in
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_acknowledge_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128), "000000".to_string()).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
 
-        // Check proper event ID emitted
-        let attribute = resp.attributes.pop().unwrap();
-        assert_eq!(attribute.key, "event_id");
-        assert_eq!(attribute.value, "1");
+        // Guest checks in and receives a short code
+        let info = mock_info(guest.as_str(), &[]);
+        let resp = try_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let data: CheckInResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.code.len(), CODE_DIGITS as usize);
+        assert_eq!(data.valid_until, mock_env().block.time.seconds() + DEFAULT_CHALLENGE_TTL);
 
-        // Check in storage
-        let event_id: u128 = attribute.value.parse().unwrap();
-        assert_eq!(event_id, 1);
-        let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
-        let event = events.may_load_event(event_id).unwrap();
+        // Only verifiers can acknowledge, and wrong codes count as failed attempts
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_acknowledge_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128), data.code.clone()).is_err());
+        let wrong = format!("{:06}", (data.code.parse::<u32>().unwrap() + 1) % 1_000_000);
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_acknowledge_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128), wrong).unwrap();
+        assert_eq!(resp.attributes[0].value, "false");
 
-        assert_eq!(event.get_id(), event_id);
-        assert_eq!(event.get_price(), price.u128());
-        assert_eq!(event.get_max_tickets(), max_tickets.u128());
-        assert_eq!(event.get_tickets_sold(), 0);
-        assert_eq!(
-            deps.api.addr_humanize(event.get_organiser()).unwrap(),
-            owner
-        );
+        // Code expires after the challenge ttl
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DEFAULT_CHALLENGE_TTL);
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_acknowledge_check_in(deps.as_mut(), env, info, Uint128::from(1u128), data.code.clone()).is_err());
 
-        // Check in organisers events
-        let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.as_mut().storage);
-        let this_organisers_events = organisers_events.load_events(&owner_canon);
-        assert_eq!(*this_organisers_events.get(0).unwrap(), event_id);
+        // Correct code checks in, and cannot be acknowledged twice
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_acknowledge_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128), data.code.clone()).unwrap();
+        assert_eq!(resp.attributes[0].value, "true");
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_acknowledge_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128), data.code).is_err());
+        let info = mock_info(guest.as_str(), &[]);
+        assert!(try_check_in(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).is_err());
+    }
 
-        // Create event
-        let entropy = "12761237192837192".to_string();
-        let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-        let mut resp = try_create_event(deps.as_mut(), info, price, max_tickets, entropy).unwrap();
+    #[test]
+    fn check_in_receipts_proper() {
+        // Instantiate contract and buy ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let stranger = deps.api.addr_validate("stranger").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        for address in [&owner, &guest, &stranger] {
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
 
-        // Check proper event ID emitted
-        let attribute = resp.attributes.pop().unwrap();
-        assert_eq!(attribute.key, "event_id");
-        assert_eq!(attribute.value, "2");
+        // Verifying the guest returns a receipt of the check-in
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        let secret = format!("{:X}", tickets.may_load_ticket(1).unwrap().unwrap().get_secret());
+        let nonce = nonce_test(&deps, Uint128::from(1u128));
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret, nonce).unwrap();
+        let receipt: CheckInReceipt = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(receipt.ticket_id, Uint128::from(1u128));
+        assert_eq!(receipt.event_id, Uint128::from(event_id));
+        assert_eq!(receipt.time, mock_env().block.time.seconds());
+        assert_eq!(receipt.verifier, owner);
 
-        let organisers_events = ReadonlyOrganisersEvents::from_storage(deps.as_mut().storage);
-        let this_organisers_events = organisers_events.load_events(&owner_canon);
-        assert_eq!(*this_organisers_events.get(1).unwrap(), 2);
+        // Guest and organiser can query the receipt, others cannot
+        for address in [&guest, &owner] {
+            let msg = QueryMsg::CheckInReceipts { ticket_id: Uint128::from(1u128), address: address.clone(), key: "key".to_string() };
+            let resp: CheckInReceiptsResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+            assert_eq!(resp.receipts, vec![receipt.clone()]);
+        }
+        let msg = QueryMsg::CheckInReceipts { ticket_id: Uint128::from(1u128), address: stranger, key: "key".to_string() };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // Receipt verifies, but not once tampered with
+        let msg = QueryMsg::VerifyCheckInReceipt { receipt: receipt.clone() };
+        let resp: VerifyCheckInReceiptResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(resp.valid);
+        let tampered = CheckInReceipt { time: receipt.time + 1, ..receipt };
+        let msg = QueryMsg::VerifyCheckInReceipt { receipt: tampered };
+        let resp: VerifyCheckInReceiptResponse = from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!resp.valid);
     }
 
-    // #[test]
-    // fn buy_ticket_proper() {
-    //     // Instantiate contract
-    //     let (owner, mut deps, _, _) = instantiate_test();
+    #[test]
+    fn ticket_history_proper() {
+        // Instantiate contract and buy two tickets
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let stranger = deps.api.addr_validate("stranger").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
 
-    //     // Deposit tokens
-    //     let guest = deps.api.addr_validate("guest").unwrap();
-    //     let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
-    //     let _deposit_resp = try_deposit(deps.as_mut(), deposit_info).unwrap();
+        // Check in the first and revoke the second in a later block
+        let mut env = mock_env();
+        env.block.height += 10;
+        let ticket_id = Uint128::from(1u128);
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_ticket(deps.as_mut(), env.clone(), info, ticket_id).unwrap();
+        let secret = ReadonlyTickets::from_storage(deps.as_mut().storage).may_load_ticket(1).unwrap().unwrap().get_secret();
+        let info = mock_info(owner.as_str(), &[]);
+        let nonce = nonce_test(&deps, ticket_id);
+        try_verify_guest(deps.as_mut(), env.clone(), info, ticket_id, format!("{:X}", secret), nonce).unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        try_revoke_ticket(deps.as_mut(), env.clone(), info, Uint128::from(2u128)).unwrap();
 
-    //     // Create event
-    //     let price = Uint128::from(50u128);
-    //     let max_tickets = Uint128::from(500u128);
-    //     let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-    //     let entropy = Uint128::from(3457263458762u128);
-    //     let mut resp = try_create_event(deps.as_mut(), info, price, max_tickets, entropy).unwrap();
-    //     let attribute = resp.attributes.pop().unwrap();
-    //     let event_id: u128 = attribute.value.parse().unwrap();
+        // Set viewing keys
+        for address in [&owner, &guest, &stranger] {
+            let info = mock_info(address.as_str(), &[]);
+            try_set_viewing_key(deps.as_mut(), info, "key".to_string()).unwrap();
+        }
+        let history_query = |address: &Addr, ticket_id: u128| QueryMsg::TicketHistory {
+            ticket_id: Uint128::from(ticket_id),
+            address: address.clone(),
+            key: "key".to_string(),
+        };
 
-    //     // Buy ticket
-    //     let entropy = Uint128::from(1827391824732872934872u128);
-    //     let info = mock_info(guest.as_str(), &coins(0, "uscrt"));
-    //     let mut resp = try_buy_ticket(deps.as_mut(), info, Uint128::from(event_id), entropy).unwrap();
+        // The guest and organiser see each action and the block it was taken in
+        let resp: TicketHistoryResponse = from_binary(&query(deps.as_ref(), mock_env(), history_query(&guest, 1)).unwrap()).unwrap();
+        let actions: Vec<TicketAction> = resp.history.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, vec![TicketAction::Purchased, TicketAction::Challenged, TicketAction::CheckedIn]);
+        assert_eq!(resp.history[0].height, mock_env().block.height);
+        assert_eq!(resp.history[2].height, env.block.height);
+        let resp: TicketHistoryResponse = from_binary(&query(deps.as_ref(), mock_env(), history_query(&owner, 2)).unwrap()).unwrap();
+        let actions: Vec<TicketAction> = resp.history.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, vec![TicketAction::Purchased, TicketAction::Revoked]);
 
-    //     // Check proper ticket ID emitted
-    //     let attribute = resp.attributes.pop().unwrap();
-    //     assert_eq!(attribute.key, "ticket_id");
-    //     assert_eq!(attribute.value, "1");
+        // Others cannot
+        assert!(query(deps.as_ref(), mock_env(), history_query(&stranger, 1)).is_err());
+    }
 
-    //     // Check ticket in storage
-    //     let ticket_id: u128 = attribute.value.parse().unwrap();
-    //     assert_eq!(ticket_id, 1);
-    //     let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
-    //     let ticket = tickets.may_load_ticket(ticket_id).unwrap();
-    //     assert_eq!(ticket.get_id(), ticket_id);
-    //     assert_eq!(ticket.get_event_id(), event_id);
-    //     assert_eq!(deps.api.addr_humanize(ticket.get_guest()).unwrap(), guest);
+    #[test]
+    fn verify_tickets_batch() {
+        // Instantiate contract and buy three tickets, one to another organiser's event
+        let (owner, mut deps, _, _) = instantiate_test();
+        let other = deps.api.addr_validate("other").unwrap();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
+        let other_event_id = create_event_test(&mut deps, &other, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
+        buy_ticket_test(&mut deps, mock_env(), &guest, other_event_id).unwrap();
 
-    //     // Check event ticket count incremented
-    //     let events = ReadonlyEvents::from_storage(deps.as_mut().storage);
-    //     let event = events.may_load_event(event_id).unwrap();
-    //     assert_eq!(event.get_tickets_sold(), 1);
+        // Empty batches, repeated tickets and tickets the sender cannot verify fail the batch
+        let batch = |ticket_ids: &[u128]| ticket_ids.iter().map(|id| Uint128::from(*id)).collect::<Vec<Uint128>>();
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_tickets(deps.as_mut(), mock_env(), info, vec![]).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        assert!(try_verify_tickets(deps.as_mut(), mock_env(), info, batch(&[1, 1])).is_err());
+        let info = mock_info(owner.as_str(), &[]);
+        let err = try_verify_tickets(deps.as_mut(), mock_env(), info, batch(&[1, 3])).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized { .. }));
 
-    //     // Check guest balance decreased
-    //     let guest_address = deps.api.addr_canonicalize(guest.as_str()).unwrap();
-    //     let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
-    //     let guest_balance = balances.read_account_balance(&guest_address);
-    //     assert_eq!(guest_balance, 950);
+        // Every ticket's secret is returned, each of which verifies its guest
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_tickets(deps.as_mut(), mock_env(), info, batch(&[1, 2])).unwrap();
+        let resp: VerifyTicketsResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(resp.tickets.len(), 2);
+        for challenge in resp.tickets {
+            let ephemeral_pk: [u8; 32] = hex::decode(&challenge.ephemeral_pk).unwrap().try_into().unwrap();
+            let shared_secret = StaticSecret::from(TEST_SK).diffie_hellman(&PublicKey::from(ephemeral_pk));
+            let secret = decrypt_secret_test(&challenge, shared_secret.as_bytes());
+            let info = mock_info(owner.as_str(), &[]);
+            try_verify_guest(deps.as_mut(), mock_env(), info, challenge.ticket_id, secret, challenge.nonce.clone()).unwrap();
+        }
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+        assert_eq!(tickets.may_load_ticket(2).unwrap().unwrap().get_state(), TICKET_USED);
+        assert_eq!(tickets.may_load_ticket(3).unwrap().unwrap().get_state(), TICKET_VALID);
+    }
 
-    //     // Check organiser balance decreased
-    //     let organiser_address = deps.api.addr_canonicalize(owner.as_str()).unwrap();
-    //     let balances = ReadonlyBalances::from_storage(deps.as_mut().storage);
-    //     let organiser_balance = balances.read_account_balance(&organiser_address);
-    //     assert_eq!(organiser_balance, 50);
-    // }
+    // Hex encoded nonce of a ticket's current challenge
+    fn nonce_test(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>, ticket_id: Uint128) -> String {
+        let tickets = ReadonlyTickets::from_storage(&deps.storage);
+        format!("{:X}", tickets.may_load_ticket(ticket_id.u128()).unwrap().map_or(0, |ticket| ticket.get_nonce()))
+    }
 
-    // #[test]
-    // fn verify_ticket_proper() {
-    //     // Instantiate contract
-    //     let (owner, mut deps, _, _) = instantiate_test();
+    // Decrypt the secret in a VerifyTicket response, given the shared secret the guest derives
+    fn decrypt_secret_test(resp: &VerifyTicketResponse, shared_secret: &[u8]) -> String {
+        let secret_encrypted = hex::decode(&resp.secret_encrypted).unwrap();
+        let key: [u8; 32] = Sha256::digest(shared_secret).into();
+        let cipher = Aes256GcmSiv::new(&key.into());
+        let (nonce, ciphertext) = secret_encrypted.split_at(12);
+        let secret_bytes = cipher.decrypt(Nonce::from_slice(nonce), ciphertext).unwrap();
+        format!("{:X}", u64::from_be_bytes(secret_bytes.try_into().unwrap()))
+    }
 
-    //     // Deposit tokens
-    //     let guest = deps.api.addr_validate("guest").unwrap();
-    //     let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
-    //     let _deposit_resp = try_deposit(deps.as_mut(), deposit_info).unwrap();
+    #[test]
+    fn verify_ticket_encrypted_secret() {
+        // Instantiate contract and buy ticket
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+        buy_ticket_test(&mut deps, mock_env(), &guest, event_id).unwrap();
 
-    //     // Create event
-    //     let price = Uint128::from(50u128);
-    //     let max_tickets = Uint128::from(500u128);
-    //     let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-    //     let entropy = Uint128::from(3457263458762u128);
-    //     let mut resp = try_create_event(deps.as_mut(), info, price, max_tickets, entropy).unwrap();
-    //     let attribute = resp.attributes.pop().unwrap();
-    //     let event_id: u128 = attribute.value.parse().unwrap();
+        // Begin verification, receiving the encrypted secret
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let resp: VerifyTicketResponse = from_binary(&resp.data.unwrap()).unwrap();
+        let ephemeral_pk: [u8; 32] = hex::decode(&resp.ephemeral_pk).unwrap().try_into().unwrap();
 
-    //     // Buy ticket
-    //     let entropy = Uint128::from(1827391824732872934872u128);
-    //     let info = mock_info(guest.as_str(), &coins(0, "uscrt"));
-    //     let mut resp = try_buy_ticket(deps.as_mut(), info, Uint128::from(event_id), entropy).unwrap();
+        // Guest decrypts secret with their key
+        let shared_secret = StaticSecret::from(TEST_SK).diffie_hellman(&PublicKey::from(ephemeral_pk));
+        let secret = decrypt_secret_test(&resp, shared_secret.as_bytes());
 
-    //     // Get ticket
-    //     let attribute = resp.attributes.pop().unwrap();
-    //     let ticket_id: u128 = attribute.value.parse().unwrap();
+        // Decrypted secret and nonce verify the guest
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret, resp.nonce).unwrap();
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_state(), TICKET_USED);
+    }
 
-    //     // Begin to verify ticket and get secret
-    //     let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-    //     let mut resp = try_verify_ticket(deps.as_mut(), info, Uint128::from(ticket_id)).unwrap();
-    //     let attribute = resp.attributes.pop().unwrap();
-    //     assert_eq!(attribute.key, "secret_encrypted");
-    //     assert_eq!(attribute.value, "9662036190035425912");
-    //     let _secret_encrypted: u128 = attribute.value.parse().unwrap();
+    #[test]
+    fn verify_ticket_key_formats() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            max_per_guest: Some(Uint128::from(2u128)),
+            ..Default::default()
+        });
 
-    //     // Check ticket is in validating state
-    //     let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
-    //     let ticket = tickets.may_load_ticket(ticket_id).unwrap();
-    //     assert_eq!(ticket.get_state(), 1);
+        // Buy one ticket with a hex ed25519 key and one with a base64 secp256k1 key
+        let ed25519_sk = ed25519_dalek::SigningKey::from_bytes(&[9; 32]);
+        let secp256k1_sk = k256::SecretKey::from_be_bytes(&[5; 32]).unwrap();
+        let secp256k1_pk = secp256k1_sk.public_key().to_encoded_point(true);
+        let keys = vec![
+            (hex::encode(ed25519_sk.verifying_key().to_bytes()), Some(PublicKeyType::Ed25519)),
+            (Binary::from(secp256k1_pk.as_bytes()).to_base64(), None),
+        ];
+        for (pk, pk_type) in keys {
+            let msg = BuyTicketMsg {
+                event_id: Uint128::from(event_id),
+                entropy: TEST_GUEST_ENTROPY.to_string(),
+                pk,
+                pk_type,
+                ..Default::default()
+            };
+            let info = mock_info(guest.as_str(), &[]);
+            try_buy_ticket(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
+        assert_eq!(tickets.may_load_ticket(1).unwrap().unwrap().get_pk().get_key_type(), KEY_ED25519);
+        assert_eq!(tickets.may_load_ticket(2).unwrap().unwrap().get_pk().get_key_type(), KEY_SECP256K1);
 
-    //     // // Validate guest
-    //     // let info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-    //     // try_verify_guest(
-    //     //     deps.as_mut(),
-    //     //     info,
-    //     //     Uint128::from(ticket_id),
-    //     //     Uint128::from(9662036190035425912u128.div_euclid(2)),
-    //     // )
-    //     // .unwrap();
+        // Ed25519 guest decrypts with their key converted to x25519
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(1u128)).unwrap();
+        let resp: VerifyTicketResponse = from_binary(&resp.data.unwrap()).unwrap();
+        let ephemeral_pk: [u8; 32] = hex::decode(&resp.ephemeral_pk).unwrap().try_into().unwrap();
+        let x25519_sk = StaticSecret::from(ed25519_sk.to_scalar_bytes());
+        let shared_secret = x25519_sk.diffie_hellman(&PublicKey::from(ephemeral_pk));
+        let secret = decrypt_secret_test(&resp, shared_secret.as_bytes());
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(1u128), secret, resp.nonce).unwrap();
 
-    //     // // Check ticket is in used state
-    //     // let tickets = ReadonlyTickets::from_storage(deps.as_mut().storage);
-    //     // let ticket = tickets.may_load_ticket(ticket_id).unwrap();
-    //     // assert_eq!(ticket.get_state(), 2);
-    // }
+        // Secp256k1 guest decrypts with their key
+        let info = mock_info(owner.as_str(), &[]);
+        let resp = try_verify_ticket(deps.as_mut(), mock_env(), info, Uint128::from(2u128)).unwrap();
+        let resp: VerifyTicketResponse = from_binary(&resp.data.unwrap()).unwrap();
+        let ephemeral_pk = k256::PublicKey::from_sec1_bytes(&hex::decode(&resp.ephemeral_pk).unwrap()).unwrap();
+        let shared_secret = k256::elliptic_curve::ecdh::diffie_hellman(secp256k1_sk.to_nonzero_scalar(), ephemeral_pk.as_affine());
+        let secret = decrypt_secret_test(&resp, shared_secret.as_bytes());
+        let info = mock_info(owner.as_str(), &[]);
+        try_verify_guest(deps.as_mut(), mock_env(), info, Uint128::from(2u128), secret, resp.nonce).unwrap();
+    }
+
+    #[test]
+    fn buy_ticket_invalid_key() {
+        // Instantiate contract
+        let (owner, mut deps, _, _) = instantiate_test();
+        let guest = deps.api.addr_validate("guest").unwrap();
+        let deposit_info = mock_info(guest.as_str(), &coins(1000, "uscrt"));
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
+        let event_id = create_event_test(&mut deps, &owner, CreateEventMsg {
+            price: Uint128::from(100u128),
+            max_tickets: Uint128::from(10u128),
+            entropy: TEST_EVENT_ENTROPY.to_string(),
+            ..Default::default()
+        });
+
+        // Malformed keys and keys of the wrong type are rejected
+        let keys = vec![
+            ("not a key".to_string(), None),
+            (hex::encode([1u8; 20]), None),
+            (hex::encode([4u8; 33]), None),
+            (hex::encode([1u8; 33]), Some(PublicKeyType::Ed25519)),
+        ];
+        for (pk, pk_type) in keys {
+            let msg = BuyTicketMsg {
+                event_id: Uint128::from(event_id),
+                entropy: TEST_GUEST_ENTROPY.to_string(),
+                pk,
+                pk_type,
+                ..Default::default()
+            };
+            let info = mock_info(guest.as_str(), &[]);
+            assert!(try_buy_ticket(deps.as_mut(), mock_env(), info, msg).is_err());
+        }
+    }
 
     #[test]
     fn deposit_invalid_token() {
@@ -691,7 +10574,7 @@ mod tests {
         let (owner, mut deps, _, _) = instantiate_test();
         // Deposit token
         let deposit_info = mock_info(owner.as_str(), &coins(1000, "earth"));
-        let deposit_resp = try_deposit(deps.as_mut(), deposit_info);
+        let deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info);
 
         // Should be error
         assert_eq!(deposit_resp.is_err(), true);
@@ -703,12 +10586,46 @@ mod tests {
         let (owner, mut deps, _, _) = instantiate_test();
         // Deposit token
         let deposit_info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-        let deposit_resp = try_deposit(deps.as_mut(), deposit_info);
+        let deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info);
 
         // Should be error
         assert_eq!(deposit_resp.is_err(), true);
     }
 
+    #[test]
+    fn deposit_multiple_coins() {
+        // Instantiate contract accepting two denoms
+        let mut deps = mock_dependencies_funded();
+        let owner = deps.api.addr_validate("owner").unwrap();
+        let info = mock_info(owner.as_str(), &[]);
+        let msg = InstantiateMsg {
+            organiser_bond: None,
+            entropy: "b16b00b5".to_string(),
+            sscrt: None,
+            denoms: Some(vec!["uscrt".to_string(), "ibc/scrt".to_string()]),
+        };
+        instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // Every entry is summed, including repeated denoms
+        let funds = [coin(100, "uscrt"), coin(50, "uscrt"), coin(25, "ibc/scrt")];
+        let info = mock_info(owner.as_str(), &funds);
+        try_deposit(deps.as_mut(), mock_env(), info).unwrap();
+        let owner_canon = deps.api.addr_canonicalize(owner.as_str()).unwrap();
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 175);
+        let reserves = ReadonlyReserves::from_storage(&deps.storage);
+        assert_eq!(reserves.read_reserve("uscrt").unwrap(), 150);
+        assert_eq!(reserves.read_reserve("ibc/scrt").unwrap(), 25);
+
+        // Any unsupported entry rejects the whole deposit
+        let funds = [coin(100, "uscrt"), coin(50, "earth")];
+        let info = mock_info(owner.as_str(), &funds);
+        let err = try_deposit(deps.as_mut(), mock_env(), info).unwrap_err();
+        assert_eq!(err, ContractError::Std(StdError::generic_err("Tried to deposit an unsupported token: earth")));
+        let balances = ReadonlyBalances::from_storage(&deps.storage);
+        assert_eq!(balances.read_account_balance(&owner_canon).unwrap(), 175);
+    }
+
     #[test]
     fn withdraw_not_enough_funds() {
 
@@ -717,11 +10634,11 @@ mod tests {
 
         // Deposit token
         let deposit_info = mock_info(owner.as_str(), &coins(1000, "uscrt"));
-        let _deposit_resp = try_deposit(deps.as_mut(), deposit_info).unwrap();
+        let _deposit_resp = try_deposit(deps.as_mut(), mock_env(), deposit_info).unwrap();
 
         // Withdraw token
         let deposit_info = mock_info(owner.as_str(), &coins(0, "uscrt"));
-        let deposit_resp = try_withdraw(deps.as_mut(), deposit_info, Uint128::from(1500u128));
+        let deposit_resp = try_withdraw(deps.as_mut(), mock_env(), deposit_info, Some(Uint128::from(1500u128)), None);
 
         // Should be error
         assert_eq!(deposit_resp.is_err(), true);
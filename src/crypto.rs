@@ -0,0 +1,89 @@
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+// Every secret the contract hands out is derived here with HKDF-SHA256, chaining
+// master seed -> event seed -> ticket seed -> challenge secret. Each step extracts with the
+// newly contributed entropy as salt and expands with a label and the ids it is bound to, so
+// knowing one level's inputs reveals nothing about its siblings or parent.
+
+// Labels separating each derivation's output
+const LABEL_EVENT_SEED: &[u8] = b"secrettickets event seed";
+const LABEL_TICKET_SEED: &[u8] = b"secrettickets ticket seed";
+const LABEL_TICKET_SECRET: &[u8] = b"secrettickets ticket secret";
+
+// HKDF-SHA256 extract and expand to a 32 byte key
+pub fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut okm = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(salt), ikm)
+        .expand(info, &mut okm)
+        .expect("32 bytes is a valid HKDF-SHA256 output length");
+    okm
+}
+
+// Seed of an event, from the contract's master seed and the organiser's entropy
+pub fn derive_event_seed(master_seed: &[u8; 32], event_entropy: &[u8], event_id: u128) -> [u8; 32] {
+    hkdf_sha256(event_entropy, master_seed, &[LABEL_EVENT_SEED, &event_id.to_be_bytes()].concat())
+}
+
+// Seed of a ticket, from its event's seed and the buyer's entropy
+pub fn derive_ticket_seed(event_seed: &[u8; 32], buyer_entropy: &[u8], ticket_id: u128) -> [u8; 32] {
+    hkdf_sha256(buyer_entropy, event_seed, &[LABEL_TICKET_SEED, &ticket_id.to_be_bytes()].concat())
+}
+
+// Secret for one challenge issuance of a ticket. The master seed at issuance is folded in as
+// salt, so secrets stay unpredictable even to someone who learns the ticket seed
+pub fn derive_ticket_secret(ticket_seed: &[u8; 32], master_seed: &[u8; 32], challenge: u32) -> u64 {
+    let okm = hkdf_sha256(master_seed, ticket_seed, &[LABEL_TICKET_SECRET, &challenge.to_be_bytes()].concat());
+    u64::from_be_bytes(okm[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hkdf_rfc5869_vector() {
+        // RFC 5869 test case 1, first 32 bytes of the output key material
+        let ikm = [0x0b; 22];
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+        assert_eq!(
+            hex::encode(hkdf_sha256(&salt, &ikm, &info)),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+        );
+    }
+
+    #[test]
+    fn derivation_vectors() {
+        let master_seed = [1u8; 32];
+        let event_seed = derive_event_seed(&master_seed, &[2u8; 32], 1);
+        assert_eq!(hex::encode(event_seed), "43e372bf6d2636e5961158bf5d3144a8f59ac8fb932928c63854212075dce89b");
+        let ticket_seed = derive_ticket_seed(&event_seed, &[3u8; 32], 1);
+        assert_eq!(hex::encode(ticket_seed), "bf5b8bb93529f491f2e43a7ec97c59afeb0466e697dd4214853e04c4ed4c8558");
+        assert_eq!(derive_ticket_secret(&ticket_seed, &master_seed, 0), 0x286f7125070e7a19);
+    }
+
+    #[test]
+    fn derivations_are_separated() {
+        let master_seed = [1u8; 32];
+        let event_seed = derive_event_seed(&master_seed, &[2u8; 32], 1);
+
+        // Changing any input changes the output
+        assert_ne!(event_seed, derive_event_seed(&master_seed, &[2u8; 32], 2));
+        assert_ne!(event_seed, derive_event_seed(&master_seed, &[4u8; 32], 1));
+        assert_ne!(event_seed, derive_event_seed(&[5u8; 32], &[2u8; 32], 1));
+        let ticket_seed = derive_ticket_seed(&event_seed, &[3u8; 32], 1);
+        assert_ne!(ticket_seed, derive_ticket_seed(&event_seed, &[3u8; 32], 2));
+        assert_ne!(
+            derive_ticket_secret(&ticket_seed, &master_seed, 0),
+            derive_ticket_secret(&ticket_seed, &master_seed, 1)
+        );
+        assert_ne!(
+            derive_ticket_secret(&ticket_seed, &master_seed, 0),
+            derive_ticket_secret(&ticket_seed, &[6u8; 32], 0)
+        );
+
+        // Same inputs under different labels do not collide
+        assert_ne!(derive_event_seed(&master_seed, &[2u8; 32], 1), derive_ticket_seed(&master_seed, &[2u8; 32], 1));
+    }
+}
@@ -0,0 +1,65 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+// Errors returned by execute, so clients and tests can match on the kind of failure. Anything
+// without its own variant is still reported through the wrapped StdError
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Event does not exist")]
+    EventNotFound {},
+
+    #[error("Ticket does not exist")]
+    TicketNotFound {},
+
+    #[error("Pass does not exist")]
+    PassNotFound {},
+
+    #[error("Group order does not exist")]
+    GroupOrderNotFound {},
+
+    #[error("Event has been cancelled")]
+    EventCancelled {},
+
+    #[error("Ticket has already been used")]
+    TicketUsed {},
+
+    #[error("Ticket has been revoked")]
+    TicketRevoked {},
+
+    #[error("Ticket has been refunded")]
+    TicketRefunded {},
+
+    #[error("Ticket has not been fully paid for")]
+    TicketUnpaid {},
+
+    #[error("{who} is banned from this event")]
+    Banned { who: &'static str },
+
+    #[error("Purchase exceeds the maximum number of tickets per guest: {max}")]
+    TicketLimitExceeded { max: u128 },
+
+    #[error("{item} is sold out")]
+    SoldOut { item: &'static str },
+
+    #[error("Amount is too large to calculate {what}")]
+    Overflow { what: &'static str },
+
+    #[error("Insufficient funds: balance={balance}, required={required}")]
+    InsufficientFunds { balance: u128, required: u128 },
+
+    #[error("Unauthorized: {reason}")]
+    Unauthorized { reason: &'static str },
+}
+
+// Queries share helpers with execute but still return StdError
+impl From<ContractError> for StdError {
+    fn from(err: ContractError) -> Self {
+        match err {
+            ContractError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod contract;
+pub mod crypto;
+pub mod error;
 pub mod msg;
 pub mod state;
\ No newline at end of file
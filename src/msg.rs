@@ -1,57 +1,1309 @@
-use cosmwasm_std::{Uint128, Addr};
+use cosmwasm_std::{Binary, Decimal, Uint128, Addr};
 
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    // Bond organisers must stake to register, in uscrt, defaults to 1 SCRT
+    pub organiser_bond: Option<Uint128>,
+    // Hex encoded entropy seeding the contract's randomness
+    pub entropy: String,
+    // sSCRT contract to accept private deposits from, registered with at instantiation
+    pub sscrt: Option<Snip20Contract>,
+    // Native denoms deposits are accepted in, defaults to uscrt. The first is used for
+    // organiser bonds
+    pub denoms: Option<Vec<String>>,
+}
+
+// Migrating upgrades stored data to the layout this code expects
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MigrateMsg {}
+
+// Address and code hash of a SNIP-20 contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Snip20Contract {
+    pub address: String,
+    pub code_hash: String,
+}
+
+// Address and code hash of a price oracle implementing Band Protocol's standard reference queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OracleContract {
+    pub address: String,
+    pub code_hash: String,
+}
+
+// Queries this contract sends to the price oracle
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleQueryMsg {
+    GetReferenceData {
+        base_symbol: String,
+        quote_symbol: String,
+    },
+}
+
+// Price oracle response, the rate is the quote per base scaled by 10^18 and update times are in
+// seconds since epoch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReferenceData {
+    pub rate: Uint128,
+    pub last_updated_base: u64,
+    pub last_updated_quote: u64,
+}
+
+// Messages this contract sends to SNIP-20 contracts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Snip20Msg {
+    RegisterReceive {
+        code_hash: String,
+        padding: Option<String>,
+    },
+    Transfer {
+        recipient: String,
+        amount: Uint128,
+        padding: Option<String>,
+    },
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Deposit {},
+    RegisterOrganiser {},
+    UnregisterOrganiser {},
+    // Set the sender's organiser profile, replacing any previous one. Event proceeds are
+    // credited to the payout address if given, otherwise to the organiser
+    SetOrganiserProfile {
+        display_name: String,
+        contact_uri: String,
+        payout_address: Option<String>,
+    },
+    // Withdraw sEVNT as a native denom, defaults to the first accepted denom. Withdraws the
+    // whole balance if no amount is given
+    // Pays out in denom if given, otherwise in the assets the account deposited, oldest first,
+    // and the primary denom for the rest
     Withdraw {
+        amount: Option<Uint128>,
+        denom: Option<String>,
+    },
+    // SNIP-20 transfer of sEVNT to another address
+    Transfer {
+        recipient: String,
         amount: Uint128,
+        memo: Option<String>,
     },
-    CreateEvent {
-        price: Uint128,
-        max_tickets: Uint128,
-        entropy: String
+    // SNIP-20 transfer of sEVNT, calling the recipient's Receive if it is a registered contract
+    // or a code hash is given
+    Send {
+        recipient: String,
+        recipient_code_hash: Option<String>,
+        amount: Uint128,
+        msg: Option<Binary>,
+        memo: Option<String>,
     },
-    BuyTicket {
-        event_id: Uint128,
+    // SNIP-20 allowance for a spender to transfer the sender's sEVNT, optionally replacing its
+    // expiry (seconds since epoch)
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expiration: Option<u64>,
+    },
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expiration: Option<u64>,
+    },
+    // SNIP-20 transfer of an owner's sEVNT by a spender, within their allowance
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+        memo: Option<String>,
+    },
+    // SNIP-20 callback from the sSCRT contract, crediting the sender's deposit as sEVNT
+    Receive {
+        sender: Addr,
+        from: Addr,
+        amount: Uint128,
+        msg: Option<Binary>,
+        memo: Option<String>,
+    },
+    // SNIP-20 registration of the sending contract's code hash, to receive Send callbacks
+    RegisterReceive {
+        code_hash: String,
+    },
+    CreateViewingKey {
         entropy: String,
-        pk: String
+    },
+    SetViewingKey {
+        key: String,
+    },
+    RevokePermit {
+        permit_name: String,
+    },
+    // Attach details only ticket holders can query, such as the exact address or a stream link,
+    // an empty string removes them
+    SetPrivateMetadata {
+        event_id: Uint128,
+        metadata: String,
+    },
+    CreateEvent(Box<CreateEventMsg>),
+    // Any uscrt sent with a purchase is deposited first, so no separate Deposit is needed
+    BuyTicket(BuyTicketMsg),
+    IssueCompTicket(IssueCompTicketMsg),
+    BuyTickets(BuyTicketsMsg),
+    CreatePass(CreatePassMsg),
+    // Any uscrt sent with a purchase is deposited first, as with tickets
+    BuyPass {
+        pass_id: Uint128,
+    },
+    RedeemPass(RedeemPassMsg),
+    OpenGroupOrder(OpenGroupOrderMsg),
+    // Any uscrt sent is deposited first. Contributes the remaining amount if none is given, and
+    // buys the tickets once the order is fully funded
+    ContributeGroupOrder {
+        order_id: Uint128,
+        amount: Option<Uint128>,
+    },
+    // Refunds all contributions, by the opener at any time or by anyone after the deadline
+    CancelGroupOrder {
+        order_id: Uint128,
     },
     VerifyTicket {
         ticket_id: Uint128,
     },
+    // Start validation of many tickets at once, returning every encrypted secret in the
+    // response data
+    VerifyTickets {
+        ticket_ids: Vec<Uint128>,
+    },
     VerifyGuest {
         ticket_id: Uint128,
         secret: String,
+        // Hex encoded nonce returned with the challenge by VerifyTicket
+        nonce: String,
+    },
+    // Guest confirms a pending check-in on a two-factor event from their address
+    ConfirmCheckIn {
+        ticket_id: Uint128,
+    },
+    // Guest checks themselves in at a kiosk, receiving a short code in the response data for
+    // the organiser to acknowledge
+    CheckIn {
+        ticket_id: Uint128,
+    },
+    // Organiser or verifier acknowledges a guest-initiated check-in with the guest's code
+    AcknowledgeCheckIn {
+        ticket_id: Uint128,
+        code: String,
+    },
+    // Issue one-time codes for every valid ticket, returned in the response data, so the
+    // venue can check guests in without connectivity
+    IssueOfflineCodes {
+        event_id: Uint128,
+    },
+    // Reconcile check-ins made offline with issued codes
+    SubmitOfflineCheckins {
+        event_id: Uint128,
+        checkins: Vec<OfflineCheckin>,
+    },
+    // Check in a guest by their current rotating ticket code
+    VerifyTicketCode {
+        ticket_id: Uint128,
+        code: String,
+    },
+    // Check in a guest by a QR payload from the QrPayload query
+    VerifyQrPayload {
+        payload: String,
+    },
+    // Check in a guest holding a secp256k1 ticket key by their signature over the nonce VerifyTicket
+    // issued for the ticket's current challenge
+    VerifyGuestSignature {
+        ticket_id: Uint128,
+        // Hex encoded compact signature over sha256(ticket_id || nonce), the nonce as 8 big-endian bytes
+        signature: String,
+    },
+    RevokeTicket {
+        ticket_id: Uint128,
+    },
+    RefundTicket {
+        ticket_id: Uint128,
+    },
+    // Any uscrt sent is deposited first, as with purchases
+    PayInstallment {
+        ticket_id: Uint128,
+    },
+    // Gives up a reserved ticket, refunding all installments paid but not the down payment
+    CancelInstallments {
+        ticket_id: Uint128,
+    },
+    // Voids a reserved ticket with an overdue installment, keeping everything paid for it
+    ForfeitTicket {
+        ticket_id: Uint128,
+    },
+    SetCapacity {
+        event_id: Uint128,
+        max_tickets: Uint128,
+        tier: Option<String>,
+    },
+    TransferTicket {
+        ticket_id: Uint128,
+        recipient: Addr,
+        new_pk: String,
+        new_pk_type: Option<PublicKeyType>,
+    },
+    ListTicketForSale {
+        ticket_id: Uint128,
+        price: Uint128,
+    },
+    DelistTicket {
+        ticket_id: Uint128,
+    },
+    BuyResaleTicket {
+        ticket_id: Uint128,
+        new_pk: String,
+        new_pk_type: Option<PublicKeyType>,
+    },
+    AddPromoCode {
+        event_id: Uint128,
+        // Hex encoded SHA-256 hash of the code
+        code_hash: String,
+        discount_percent: u8,
+        max_uses: u32,
+    },
+    JoinWaitlist {
+        event_id: Uint128,
+        // Hex encoded 32 bytes of entropy
+        entropy: String,
+        pk: String,
+        // Type of pk, inferred from its length if not given
+        pk_type: Option<PublicKeyType>,
+    },
+    SetAllowlist {
+        event_id: Uint128,
+        addresses: Vec<Addr>,
+    },
+    AddCoOrganiser {
+        event_id: Uint128,
+        address: Addr,
+    },
+    RemoveCoOrganiser {
+        event_id: Uint128,
+        address: Addr,
+    },
+    AddVerifier {
+        event_id: Uint128,
+        address: Addr,
+    },
+    RemoveVerifier {
+        event_id: Uint128,
+        address: Addr,
+    },
+    BanGuest {
+        event_id: Uint128,
+        address: Addr,
+    },
+    UnbanGuest {
+        event_id: Uint128,
+        address: Addr,
+    },
+    ClaimPayout {
+        event_id: Uint128,
+    },
+    OpenDispute {
+        ticket_id: Uint128,
+        reason: String,
+    },
+    ResolveDisputes {
+        event_id: Uint128,
+        outcome: DisputeOutcome,
+    },
+    SetArbiters {
+        addresses: Vec<Addr>,
+    },
+    CancelEvent {
+        event_id: Uint128,
+    },
+    // Complete an event without scheduled times, so its proceeds can be released
+    CompleteEvent {
+        event_id: Uint128,
+    },
+    SlashOrganiser {
+        event_id: Uint128,
+    },
+    SetContractStatus {
+        level: ContractStatusLevel,
+    },
+    UpdateFeeConfig {
+        rate_bps: u32,
+        recipient: Addr,
+    },
+    // Minimum seconds after every event completes during which guests may open disputes
+    SetDisputeWindow {
+        seconds: u64,
+    },
+    // Minimum sEVNT amounts of deposits and withdrawals, and the most sEVNT that can be
+    // withdrawn across all accounts in one block
+    SetLimits {
+        min_deposit: Uint128,
+        min_withdrawal: Uint128,
+        withdrawal_cap: Option<Uint128>,
+    },
+    // sEVNT minted per 10^decimals native tokens deposited, taking effect after a delay while
+    // sEVNT is outstanding
+    SetExchangeRate {
+        rate: Uint128,
+        decimals: u8,
+    },
+    // Oracle pricing stable priced events, removed if not given
+    SetPriceOracle {
+        oracle: Option<OracleContract>,
+    },
+    // Delegate up to stake_bps of the primary denom's reserve to a validator, rewards being
+    // withdrawn to the treasury
+    SetStaking {
+        validator: String,
+        stake_bps: u32,
+        treasury: Addr,
+    },
+    // Delegate or undelegate so the staked amount matches the configured share of the reserve
+    RebalanceStake {},
+    ClaimStakingRewards {},
+    PauseSales {
+        event_id: Uint128,
+    },
+    ResumeSales {
+        event_id: Uint128,
     },
 }
 
+// Contract status levels, for halting the contract during incidents
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ContractStatusLevel {
+    NormalRun,
+    // Halt ticket purchases and withdrawals only
+    StopPurchases,
+    // Halt all executes except changing the status
+    StopAll,
+}
+
+// Type of a guest's public key
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicKeyType {
+    X25519,
+    Ed25519,
+    Secp256k1,
+}
+
+// Outcome of resolving an events disputes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    // Refund disputed tickets from escrow
+    Refund,
+    // Dismiss disputes, releasing escrow to the organiser
+    Release,
+}
+
+// Parameters for creating an event
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CreateEventMsg {
+    pub price: Uint128,
+    pub max_tickets: Uint128,
+    // Hex encoded 32 bytes of entropy
+    pub entropy: String,
+    // Public name, venue and tags events are searched by
+    pub name: Option<String>,
+    pub venue: Option<String>,
+    pub tags: Option<Vec<String>>,
+    // Ticket tiers, replacing the single tier given by price and max_tickets
+    pub tiers: Option<Vec<TierMsg>>,
+    // Early-bird price schedule for the single tier given by price and max_tickets
+    pub price_schedule: Option<Vec<PriceStepMsg>>,
+    // Dutch auction for the single tier given by price and max_tickets
+    pub dutch_auction: Option<DutchAuctionMsg>,
+    // Seat labels for reserved seating, general admission if not given
+    pub seats: Option<Vec<String>>,
+    // Maximum tickets one address may hold, defaults to 1
+    pub max_per_guest: Option<Uint128>,
+    // Resale price cap in basis points of face value, defaults to 10000 (face value)
+    pub max_resale_bps: Option<u32>,
+    // Organiser's cut of resale proceeds in basis points, defaults to 0
+    pub royalty_bps: Option<u32>,
+    // Ticket sales window, in seconds since epoch
+    pub sale_start: Option<u64>,
+    pub sale_end: Option<u64>,
+    // Start of the allowlist presale, which runs until sale_start
+    pub presale_start: Option<u64>,
+    // Event start and end, in seconds since epoch
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    // Seconds either side of the event during which check-in is allowed
+    pub checkin_window: Option<u64>,
+    // Seconds a verification challenge stays open before it can be retried, defaults to 300
+    pub challenge_ttl: Option<u64>,
+    // Sessions of a multi-day event, each checked in to separately
+    pub sessions: Option<Vec<SessionMsg>>,
+    // Guests may refund tickets before this time, in seconds since epoch
+    pub refund_deadline: Option<u64>,
+    // Seconds after the event ends before sale proceeds are released, during which
+    // guests may open disputes, defaults to and is at least the contract's dispute window
+    pub payout_delay: Option<u64>,
+    // Hex encoded secp256k1 public key signing invites, making the event invite-only
+    pub invite_key: Option<String>,
+    // Insure tickets against cancellation, charging guests a premium on each ticket
+    pub insured: Option<bool>,
+    // Require guests to confirm check-in from their address as well as present their secret
+    pub two_factor: Option<bool>,
+    // Prices are in USD cents, converted at the price oracle's rate when tickets are bought
+    pub stable_price: Option<bool>,
+    // Payees splitting sale proceeds and resale royalties, the organiser receives everything
+    // if not given
+    pub payees: Option<Vec<PayeeMsg>>,
+    // Let guests reserve tickets with a down payment and pay the rest in installments
+    pub installment_plan: Option<InstallmentPlanMsg>,
+    // Show guests' addresses to organisers in the attendee list, hidden by default
+    pub share_guests: Option<bool>,
+}
+
+// Installment plan, with the down payment in basis points of the ticket price and an
+// installment due every interval seconds after the purchase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InstallmentPlanMsg {
+    pub down_payment_bps: u32,
+    pub installments: u32,
+    pub interval: u64,
+}
+
+// Payee's share of an event's proceeds, in basis points
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PayeeMsg {
+    pub address: String,
+    pub bps: u32,
+}
+
+// Ticket tier definition
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TierMsg {
+    pub name: String,
+    pub price: Uint128,
+    pub max_tickets: Uint128,
+    pub schedule: Option<Vec<PriceStepMsg>>,
+}
+
+// Session of a multi-day event, in seconds since epoch
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SessionMsg {
+    pub start: u64,
+    pub end: u64,
+}
+
+// Dutch auction starting at start_height, with the price decaying by
+// decay_per_block each block until it reaches floor_price
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DutchAuctionMsg {
+    pub start_price: Uint128,
+    pub floor_price: Uint128,
+    pub start_height: u64,
+    pub decay_per_block: Uint128,
+}
+
+// Price step of an early-bird schedule, applying while fewer than until_sold
+// tickets are sold and before until_time (seconds since epoch)
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PriceStepMsg {
+    pub price: Uint128,
+    pub until_sold: Option<Uint128>,
+    pub until_time: Option<u64>,
+}
+
+// Parameters for buying a ticket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct BuyTicketMsg {
+    pub event_id: Uint128,
+    // Hex encoded 32 bytes of entropy
+    pub entropy: String,
+    // Hex or base64 encoded public key the ticket secret is encrypted to
+    pub pk: String,
+    // Type of pk, inferred from its length if not given
+    pub pk_type: Option<PublicKeyType>,
+    // Tier to buy, defaults to the event's first tier
+    pub tier: Option<String>,
+    // Requested seat, required for events with reserved seating
+    pub seat: Option<String>,
+    pub promo_code: Option<String>,
+    // Hex encoded invite signature, required for invite-only events
+    pub invite: Option<String>,
+    // Reserve the ticket with a down payment under the event's installment plan, it cannot be
+    // verified until fully paid
+    pub installments: Option<bool>,
+    // Paid straight to the organiser on top of the price
+    pub tip: Option<Uint128>,
+}
+
+// Parameters for creating a season pass, covering either the listed events or all of the
+// organiser's events starting within the period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CreatePassMsg {
+    pub price: Uint128,
+    pub max_passes: Uint128,
+    pub event_ids: Option<Vec<Uint128>>,
+    // Period in seconds since epoch, the end is exclusive
+    pub period_start: Option<u64>,
+    pub period_end: Option<u64>,
+}
+
+// Parameters for redeeming a season pass for a ticket to one of its events
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct RedeemPassMsg {
+    pub pass_id: Uint128,
+    pub event_id: Uint128,
+    // Hex encoded 32 bytes of entropy
+    pub entropy: String,
+    // Hex or base64 encoded public key the ticket secret is encrypted to
+    pub pk: String,
+    // Type of pk, inferred from its length if not given
+    pub pk_type: Option<PublicKeyType>,
+    // Tier to redeem, defaults to the event's first tier
+    pub tier: Option<String>,
+    // Seat to assign, required for events with reserved seating
+    pub seat: Option<String>,
+}
+
+// Parameters for opening a group order, its tickets are bought for the opener at the price quoted
+// now once contributions cover it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct OpenGroupOrderMsg {
+    pub event_id: Uint128,
+    // Public key for each ticket
+    pub pks: Vec<String>,
+    pub pk_type: Option<PublicKeyType>,
+    // Hex encoded 32 bytes of entropy
+    pub entropy: String,
+    pub tier: Option<String>,
+    // Requested seat for each ticket, required for events with reserved seating
+    pub seats: Option<Vec<String>>,
+    // Hex encoded invite signature, required for invite-only events
+    pub invite: Option<String>,
+    // Time in seconds since epoch by which the order must be fully funded
+    pub deadline: u64,
+}
+
+// Parameters for buying several tickets at once
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct BuyTicketsMsg {
+    pub event_id: Uint128,
+    pub quantity: Uint128,
+    // Public key for each ticket
+    pub pks: Vec<String>,
+    pub pk_type: Option<PublicKeyType>,
+    // Hex encoded 32 bytes of entropy
+    pub entropy: String,
+    pub tier: Option<String>,
+    // Requested seat for each ticket, required for events with reserved seating
+    pub seats: Option<Vec<String>>,
+    pub promo_code: Option<String>,
+    // Hex encoded invite signature, required for invite-only events
+    pub invite: Option<String>,
+}
+
+// Parameters for issuing a free ticket to a guest
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IssueCompTicketMsg {
+    pub event_id: Uint128,
+    pub recipient: Addr,
+    pub pk: String,
+    // Type of pk, inferred from its length if not given
+    pub pk_type: Option<PublicKeyType>,
+    // Hex encoded 32 bytes of entropy
+    pub entropy: String,
+    // Tier to issue, defaults to the event's first tier
+    pub tier: Option<String>,
+    // Seat to assign, required for events with reserved seating
+    pub seat: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Balance {
-        address: Addr
+        address: Addr,
+        key: String
+    },
+    // Balances of several accounts, only the contract owner can query accounts other than its own
+    Balances {
+        addresses: Vec<Addr>,
+        address: Addr,
+        key: String
+    },
+    // An owner's allowance for a spender, for either of them
+    Allowance {
+        owner: Addr,
+        spender: Addr,
+        address: Addr,
+        key: String
+    },
+    // Allowances address has given, ordered by spender
+    AllowancesGiven {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        address: Addr,
+        key: String
+    },
+    // Allowances address has received, ordered by owner
+    AllowancesReceived {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        address: Addr,
+        key: String
     },
     EventSoldOut {
         event_id: Uint128
     },
+    // EventSoldOut for several events at once
+    EventsSoldOut {
+        event_ids: Vec<Uint128>
+    },
+    // Resale listings of an event's tickets in ticket id order, a page at a time
+    Listings {
+        event_id: Uint128,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    // An address's own resale listings in ticket id order, a page at a time
+    SellerListings {
+        start_after: Option<Uint128>,
+        limit: Option<u32>,
+        address: Addr,
+        key: String
+    },
+    // Public details of an event
+    EventInfo {
+        event_id: Uint128
+    },
+    // Public profile of an organiser
+    OrganiserProfile {
+        organiser: Addr
+    },
+    // Public details of events in id order, a page at a time, optionally only those with a status
+    ListEvents {
+        start_after: Option<Uint128>,
+        limit: Option<u32>,
+        status: Option<EventStatus>
+    },
+    // Like ListEvents, but only events whose name or venue contains the text and that carry the
+    // tag, ignoring case
+    SearchEvents {
+        text: Option<String>,
+        tag: Option<String>,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    // An organiser's events and a guest's tickets in id order, a page at a time
     Events {
-        address: Addr
+        address: Addr,
+        key: String,
+        status: Option<EventStatus>,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
     },
     Tickets {
-        address: Addr
+        address: Addr,
+        key: String,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    Pass {
+        pass_id: Uint128
+    },
+    GroupOrder {
+        order_id: Uint128
+    },
+    Passes {
+        address: Addr,
+        key: String
+    },
+    Payouts {
+        event_id: Uint128
+    },
+    // Owner, fees, denoms, counters and version of the deployment
+    ContractInfo {},
+    FeeConfig {},
+    Limits {},
+    Solvency {},
+    ExchangeRate {},
+    ContractStatus {},
+    // SNIP-20 name, symbol, decimals and total supply of sEVNT, the supply being all sEVNT
+    // outstanding against deposits
+    TokenInfo {},
+    InsurancePool {},
+    Staking {},
+    RemainingSeats {
+        event_id: Uint128
+    },
+    // Authenticated by the ticket key's signature over sha256(ticket_id || expiry),
+    // so only secp256k1 and ed25519 keys can request payloads
+    QrPayload {
+        ticket_id: Uint128,
+        expiry: u64,
+        signature: String
+    },
+    // Authenticated by the ticket key's signature over sha256(ticket_id || window), where
+    // window is the block time divided by the code window
+    TicketCode {
+        ticket_id: Uint128,
+        signature: String
+    },
+    PrivateMetadata {
+        event_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    // Revenue of an event, for its organisers
+    EventRevenue {
+        event_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    AttendanceProof {
+        ticket_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    OfflineCode {
+        ticket_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    Installments {
+        ticket_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    // Tickets sold and checked in for an event, and check-ins per bucket of the given seconds,
+    // for its organisers, counted over a page of its tickets in id order
+    CheckInStats {
+        event_id: Uint128,
+        bucket: Option<u64>,
+        start_after: Option<Uint128>,
+        limit: Option<u32>,
+        address: Addr,
+        key: String
+    },
+    // Tickets sold for an event in id order, for its organisers
+    Attendees {
+        event_id: Uint128,
+        start_after: Option<Uint128>,
+        limit: Option<u32>,
+        address: Addr,
+        key: String
+    },
+    // Address's position on an event's waitlist and the waitlist's depth
+    WaitlistPosition {
+        event_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    // Entries of an event's waitlist in order, for its organisers
+    Waitlist {
+        event_id: Uint128,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+        address: Addr,
+        key: String
+    },
+    // Receipts of a ticket's check-ins, for its guest or the event's organisers
+    CheckInReceipts {
+        ticket_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    // Actions taken on a ticket and the blocks they were taken in, for its guest or the event's
+    // organisers
+    TicketHistory {
+        ticket_id: Uint128,
+        address: Addr,
+        key: String
+    },
+    // Public, checks a receipt was issued by this contract
+    VerifyCheckInReceipt {
+        receipt: CheckInReceipt
+    },
+    // Public, checks a proof a guest presents without learning their ticket or address
+    VerifyAttendance {
+        event_id: Uint128,
+        proof: String
+    },
+    WithPermit {
+        permit: Permit,
+        query: QueryWithPermit
     }
 }
 
+// Queries authenticated by a permit instead of an address and viewing key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryWithPermit {
+    Balance {},
+    Balances {
+        addresses: Vec<Addr>
+    },
+    Allowance {
+        owner: Addr,
+        spender: Addr
+    },
+    AllowancesGiven {
+        start_after: Option<Addr>,
+        limit: Option<u32>
+    },
+    AllowancesReceived {
+        start_after: Option<Addr>,
+        limit: Option<u32>
+    },
+    Events {
+        status: Option<EventStatus>,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    Tickets {
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    Passes {},
+    SellerListings {
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    PrivateMetadata {
+        event_id: Uint128
+    },
+    EventRevenue {
+        event_id: Uint128
+    },
+    AttendanceProof {
+        ticket_id: Uint128
+    },
+    OfflineCode {
+        ticket_id: Uint128
+    },
+    CheckInStats {
+        event_id: Uint128,
+        bucket: Option<u64>,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    Attendees {
+        event_id: Uint128,
+        start_after: Option<Uint128>,
+        limit: Option<u32>
+    },
+    WaitlistPosition {
+        event_id: Uint128
+    },
+    // Whether the permit signer holds a valid ticket, for partners given the permit
+    TicketValidity {
+        ticket_id: Uint128
+    },
+    Waitlist {
+        event_id: Uint128,
+        start_after: Option<u32>,
+        limit: Option<u32>
+    },
+    Installments {
+        ticket_id: Uint128
+    },
+    CheckInReceipts {
+        ticket_id: Uint128
+    },
+    TicketHistory {
+        ticket_id: Uint128
+    },
+}
+
+// SNIP-24 query permit, signed offline by the querying account
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Permit {
+    pub params: PermitParams,
+    pub signature: PermitSignature,
+}
+
+// Fields are in alphabetical order, as they are serialized into the signed document
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitParams {
+    pub allowed_tokens: Vec<String>,
+    pub chain_id: String,
+    pub permissions: Vec<Permission>,
+    pub permit_name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitSignature {
+    pub pub_key: PermitPubKey,
+    pub signature: Binary,
+}
+
+// Signer's secp256k1 public key, as tendermint/PubKeySecp256k1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitPubKey {
+    pub r#type: String,
+    pub value: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    Allowance,
+    Balance,
+    History,
+    Owner,
+    // Lets whoever holds the permit check the validity of the signer's tickets, and nothing else
+    TicketValidity,
+}
+
+// Amino sign document a permit's signature covers, fields in alphabetical order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitSignDoc {
+    pub account_number: String,
+    pub chain_id: String,
+    pub fee: PermitFee,
+    pub memo: String,
+    pub msgs: Vec<PermitSignMsg>,
+    pub sequence: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitFee {
+    pub amount: Vec<PermitCoin>,
+    pub gas: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitCoin {
+    pub amount: String,
+    pub denom: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PermitSignMsg {
+    pub r#type: String,
+    pub value: PermitParams,
+}
+
 // Response for EventSoldOut query
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct SoldOutResponse {
     pub sold_out: bool,
+    pub tiers: Vec<TierAvailability>,
+}
+
+// Response for EventsSoldOut query, in the order the events were given
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventsSoldOutResponse {
+    pub events: Vec<EventAvailability>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventAvailability {
+    pub event_id: Uint128,
+    pub sold_out: bool,
+    pub tiers: Vec<TierAvailability>,
+}
+
+// Availability of a single tier in EventSoldOut response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TierAvailability {
+    pub name: String,
+    pub tickets_left: Uint128,
+    pub sold_out: bool,
+}
+
+// Status of an event, in order of precedence
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventStatus {
+    Cancelled,
+    // Past the event's end time, or its start time if it has no end, or completed by the
+    // organiser if it has neither
+    Finished,
+    // Past the event's start time
+    InProgress,
+    Paused,
+    SoldOut,
+    // Past the sale end without selling out
+    SalesEnded,
+    // Before the sale start
+    Upcoming,
+    Presale,
+    OnSale,
+}
+
+// Response for EventInfo query, prices are the current price of the next ticket in sEVNT
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventInfoResponse {
+    pub event_id: Uint128,
+    pub organiser: Addr,
+    pub name: String,
+    pub venue: String,
+    pub tags: Vec<String>,
+    pub status: EventStatus,
+    pub price: Uint128,
+    pub capacity: Uint128,
+    pub tickets_sold: Uint128,
+    pub tiers: Vec<TierInfo>,
+    pub sale_start: Option<u64>,
+    pub sale_end: Option<u64>,
+    pub presale_start: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub refund_deadline: Option<u64>,
+    pub seated: bool,
+    pub max_per_guest: Uint128,
+    pub insured: bool,
+    pub stable_price: bool,
+    pub installments: bool,
+    pub share_guests: bool,
+    pub organiser_profile: Option<OrganiserProfileResponse>,
+}
+
+// Response for OrganiserProfile query, the payout address is kept private
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OrganiserProfileResponse {
+    pub display_name: String,
+    pub contact_uri: String,
+}
+
+// Response for ListEvents query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ListEventsResponse {
+    pub events: Vec<EventInfoResponse>,
+}
+
+// Details of a single tier in EventInfo response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TierInfo {
+    pub name: String,
+    pub price: Uint128,
+    pub capacity: Uint128,
+    pub tickets_sold: Uint128,
+}
+
+// Data returned by CreateEvent
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CreateEventResponse {
+    pub event_id: Uint128,
+}
+
+// Data returned by BuyTicket and IssueCompTicket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketIdResponse {
+    pub ticket_id: Uint128,
+}
+
+// Data returned by CreatePass
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PassIdResponse {
+    pub pass_id: Uint128,
+}
+
+// Data returned by OpenGroupOrder
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GroupOrderIdResponse {
+    pub order_id: Uint128,
+}
+
+// Data returned by BuyTickets, and by ContributeGroupOrder once the order is bought
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketIdsResponse {
+    pub ticket_ids: Vec<Uint128>,
+}
+
+// Callback executed on a registered contract receiving sEVNT by Send
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiverHandleMsg {
+    Receive {
+        sender: Addr,
+        from: Addr,
+        amount: Uint128,
+        memo: Option<String>,
+        msg: Option<Binary>,
+    },
+}
+
+// Data returned by IncreaseAllowance and DecreaseAllowance, and response for Allowance query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AllowanceResponse {
+    pub owner: Addr,
+    pub spender: Addr,
+    pub allowance: Uint128,
+    pub expiration: Option<u64>,
+}
+
+// Response for AllowancesGiven and AllowancesReceived queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AllowancesResponse {
+    pub allowances: Vec<AllowanceResponse>,
+}
+
+// Response for TokenInfo query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TokenInfoResponse {
+    pub token_info: TokenInfo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TokenInfo {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Option<Uint128>,
+}
+
+// Response for TicketValidity query, valid if the ticket exists, belongs to the permit signer,
+// is paid for and not revoked or refunded, and its event is not cancelled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketValidityResponse {
+    pub valid: bool,
+}
+
+// Data returned by VerifyTicket, and for each ticket by VerifyTickets, hex encoded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerifyTicketResponse {
+    pub ticket_id: Uint128,
+    pub ephemeral_pk: String,
+    // Nonce followed by the ciphertext of the secret
+    pub secret_encrypted: String,
+    // One-time nonce that must accompany the secret in VerifyGuest, or be signed in VerifyGuestSignature
+    pub nonce: String,
+    pub challenges: u32,
+}
+
+// Data returned by VerifyTickets
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerifyTicketsResponse {
+    pub tickets: Vec<VerifyTicketResponse>,
+}
+
+// Ticket and hex encoded code, as issued by IssueOfflineCodes and presented at the door
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OfflineCheckin {
+    pub ticket_id: Uint128,
+    pub code: String,
+}
+
+// Data returned by IssueOfflineCodes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OfflineCodesResponse {
+    pub codes: Vec<OfflineCheckin>,
+}
+
+// Data returned by SubmitOfflineCheckins
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OfflineCheckinsResponse {
+    pub checked_in: Vec<Uint128>,
+    pub rejected: Vec<Uint128>,
+}
+
+// Contract's receipt of a check-in, returned by VerifyGuest. The receipt is a hex encoded
+// MAC over the other fields, which only this contract can produce
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CheckInReceipt {
+    pub ticket_id: Uint128,
+    pub event_id: Uint128,
+    pub time: u64,
+    pub verifier: Addr,
+    pub receipt: String,
+}
+
+// Response for CheckInReceipts query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CheckInReceiptsResponse {
+    pub receipts: Vec<CheckInReceipt>,
+}
+
+// Action taken on a ticket in TicketHistory response
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketAction {
+    Purchased,
+    // A verifier was issued a challenge for the ticket
+    Challenged,
+    CheckedIn,
+    Transferred,
+    Revoked,
+    Refunded,
+}
+
+// Response for TicketHistory query, oldest first
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketHistoryResponse {
+    pub history: Vec<TicketHistoryEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketHistoryEntry {
+    pub action: TicketAction,
+    pub height: u64,
+    pub time: u64,
+}
+
+// Response for VerifyCheckInReceipt query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerifyCheckInReceiptResponse {
+    pub valid: bool,
+}
+
+// Data returned by CheckIn
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CheckInResponse {
+    pub code: String,
+    // Time the code stops being accepted, in seconds since epoch
+    pub valid_until: u64,
+}
+
+// Data returned by CreateViewingKey and SetViewingKey
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ViewingKeyResponse {
+    pub key: String,
+}
+
+// Response for PrivateMetadata query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PrivateMetadataResponse {
+    pub metadata: Option<String>,
+}
+
+// Response for AttendanceProof query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttendanceProofResponse {
+    pub event_id: Uint128,
+    // Hex encoded nullifier followed by the contract's attestation over it and the event
+    pub proof: String,
+}
+
+// Response for OfflineCode query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OfflineCodeResponse {
+    pub code: Option<String>,
+}
+
+// Response for Installments query, next_due is none once the ticket is fully paid
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InstallmentsResponse {
+    pub paid: Uint128,
+    pub unpaid: Uint128,
+    pub next_amount: Uint128,
+    pub next_due: Option<u64>,
+}
+
+// Response for VerifyAttendance query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VerifyAttendanceResponse {
+    pub attended: bool,
 }
 
 // Response for Balance query
@@ -60,17 +1312,290 @@ pub struct BalanceResponse {
     pub balance: Uint128,
 }
 
+// Response for Balances query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BalancesResponse {
+    pub balances: Vec<AccountBalance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AccountBalance {
+    pub address: Addr,
+    pub balance: Uint128,
+}
+
 // Response for Events query
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct EventsResponse {
-    pub events: Vec<Uint128>,
-    pub tickets_left: Vec<Uint128>,
+    pub events: Vec<EventSummary>,
+}
+
+// An event in Events response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventSummary {
+    pub event_id: Uint128,
+    pub name: String,
+    pub status: EventStatus,
+    pub tickets_left: Uint128,
+}
+
+// Response for EventRevenue query, revenue is ticket sales less refunds and tips are counted
+// separately
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EventRevenueResponse {
+    pub revenue: Uint128,
+    pub tips: Uint128,
+}
+
+// Response for Payouts query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PayoutsResponse {
+    pub payouts: Vec<PayoutInfo>,
+}
+
+// Payout released to the organiser, after the platform fee
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PayoutInfo {
+    pub amount: Uint128,
+    pub fee: Uint128,
+    pub time: u64,
+}
+
+// Response for InsurancePool query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct InsurancePoolResponse {
+    pub balance: Uint128,
+}
+
+// Response for Staking query, amounts are of the primary denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StakingResponse {
+    pub validator: String,
+    pub stake_bps: u32,
+    pub treasury: Addr,
+    pub staked: Uint128,
+    pub unbonding: Uint128,
+    pub liquid: Uint128,
+}
+
+// Response for FeeConfig query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct FeeConfigResponse {
+    pub rate_bps: u32,
+    pub recipient: Addr,
+}
+
+// Response for ContractInfo query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub version: String,
+    pub owner: Addr,
+    pub fee: FeeConfigResponse,
+    // Accepted native denoms, the first is the primary denom
+    pub denoms: Vec<String>,
+    pub num_events: Uint128,
+    pub num_tickets: Uint128,
+    pub num_passes: Uint128,
+    pub num_group_orders: Uint128,
+}
+
+// Response for Limits query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LimitsResponse {
+    pub min_deposit: Uint128,
+    pub min_withdrawal: Uint128,
+    pub withdrawal_cap: Option<Uint128>,
+}
+
+// Response for Solvency query, backing is the sEVNT value of the native tokens and sSCRT the
+// contract holds for depositors, and ratio is backing over supply
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SolvencyResponse {
+    pub total_supply: Uint128,
+    pub backing: Uint128,
+    pub ratio: Option<Decimal>,
+}
+
+// Response for ExchangeRate query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExchangeRateResponse {
+    pub rate: Uint128,
+    pub decimals: u8,
+    pub pending: Option<PendingExchangeRate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingExchangeRate {
+    pub rate: Uint128,
+    pub decimals: u8,
+    pub effective_at: u64,
+}
+
+// Response for ContractStatus query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ContractStatusResponse {
+    pub level: ContractStatusLevel,
+}
+
+// Response for RemainingSeats query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SeatsResponse {
+    pub seats: Vec<String>,
+}
+
+// Response for QrPayload query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct QrPayloadResponse {
+    // Base64 encoded ticket id, event id, expiry and holder commitment, authenticated by the contract
+    pub payload: String,
+    pub expiry: u64,
+}
+
+// Response for TicketCode query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketCodeResponse {
+    pub code: String,
+    // Time the code stops being valid, in seconds since epoch
+    pub valid_until: u64,
+}
+
+// Response for Pass query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PassResponse {
+    pub pass_id: Uint128,
+    pub organiser: Addr,
+    pub price: Uint128,
+    pub event_ids: Vec<Uint128>,
+    pub period_start: Option<u64>,
+    pub period_end: Option<u64>,
+    pub max_passes: Uint128,
+    pub passes_sold: Uint128,
+}
+
+// Response for GroupOrder query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct GroupOrderResponse {
+    pub order_id: Uint128,
+    pub event_id: Uint128,
+    pub quantity: Uint128,
+    pub target: Uint128,
+    pub funded: Uint128,
+    pub deadline: u64,
+}
+
+// Response for Passes query, the passes an address holds and the events each was redeemed for
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PassesResponse {
+    pub passes: Vec<PassHoldingInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PassHoldingInfo {
+    pub pass_id: Uint128,
+    pub redeemed: Vec<Uint128>,
+}
+
+// Response for CheckInStats query, buckets with check-ins in time order over the page of
+// tickets, and the ticket to page from next, none once every ticket has been counted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CheckInStatsResponse {
+    pub tickets_sold: Uint128,
+    pub checked_in: Uint128,
+    pub check_ins: Uint128,
+    pub buckets: Vec<CheckInBucket>,
+    pub next_start_after: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CheckInBucket {
+    // Start of the bucket, in seconds since epoch
+    pub start: u64,
+    pub check_ins: Uint128,
+}
+
+// Response for Listings and SellerListings queries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ListingsResponse {
+    pub listings: Vec<ListingInfo>,
+}
+
+// A resale listing, face value being what the seller paid for the ticket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ListingInfo {
+    pub ticket_id: Uint128,
+    pub event_id: Uint128,
+    pub tier: String,
+    pub seat: Option<String>,
+    pub price: Uint128,
+    pub face_value: Uint128,
+}
+
+// Response for Attendees query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttendeesResponse {
+    pub attendees: Vec<AttendeeInfo>,
+}
+
+// A ticket in Attendees response, guest is only given if the event shares guests' addresses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttendeeInfo {
+    pub ticket_id: Uint128,
+    pub guest: Option<Addr>,
+    pub tier: String,
+    pub seat: Option<String>,
+    pub state: TicketState,
+    pub checked_in: bool,
+    pub sessions_used: Vec<u32>,
+}
+
+// Response for WaitlistPosition query, position counts from 1 and is none if not waiting
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WaitlistPositionResponse {
+    pub position: Option<u32>,
+    pub depth: u32,
+}
+
+// Response for Waitlist query
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WaitlistResponse {
+    pub entries: Vec<WaitlistEntryInfo>,
+    pub depth: u32,
+}
+
+// An entry in Waitlist response, guest is only given if the event shares guests' addresses
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WaitlistEntryInfo {
+    pub position: u32,
+    pub guest: Option<Addr>,
 }
 
 // Response for Tickets query
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct TicketsResponse {
-    pub tickets: Vec<Uint128>,
-    pub events: Vec<Uint128>,
-    pub states: Vec<Uint128>
+    pub tickets: Vec<TicketInfo>,
+}
+
+// A ticket in Tickets response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketInfo {
+    pub ticket_id: Uint128,
+    pub event_id: Uint128,
+    pub tier: String,
+    pub seat: Option<String>,
+    pub state: TicketState,
+}
+
+// State of a ticket
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketState {
+    Valid,
+    // A verifier has been issued a challenge for the ticket
+    Validating,
+    Used,
+    Revoked,
+    Refunded,
+    // Held until paid for in full
+    Reserved,
 }
\ No newline at end of file
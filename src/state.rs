@@ -1,45 +1,538 @@
-use cosmwasm_std::{StdResult, StdError, CanonicalAddr, Storage};
+use cosmwasm_std::{Binary, StdResult, StdError, CanonicalAddr, Storage, Uint128, to_vec, from_slice};
 use cosmwasm_storage::{
     Singleton, singleton, ReadonlySingleton, singleton_read, 
     PrefixedStorage, ReadonlyPrefixedStorage
 };
 
-use serde::{Serialize, Deserialize};
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
 use sha2::{Sha256, Digest};
-use rand::{RngCore, SeedableRng};
-use rand_chacha::ChaChaRng;
-use bincode;
-use extprim::u128;
+use curve25519_dalek::edwards::CompressedEdwardsY;
 
 // Storage keys
 pub const KEY_CONFIG: &[u8] = b"config";
+pub const KEY_SCHEMA_VERSION: &[u8] = b"schema_version";
 pub const PREFIX_BALANCES: &[u8] = b"balances";
 pub const PREFIX_EVENTS: &[u8] = b"events";
 pub const PREFIX_TICKETS: &[u8] = b"tickets";
 pub const PREFIX_ORGANISERS_EVENTS: &[u8] = b"organisers_events";
 pub const PREFIX_GUESTS_TICKETS: &[u8] = b"guests_tickets";
+pub const PREFIX_EVENT_SEATS: &[u8] = b"event_seats";
+pub const PREFIX_LISTINGS: &[u8] = b"listings";
+pub const PREFIX_WAITLISTS: &[u8] = b"waitlists";
+pub const PREFIX_PROMO_CODES: &[u8] = b"promo_codes";
+pub const PREFIX_ALLOWLISTS: &[u8] = b"allowlists";
+pub const PREFIX_BANNED_GUESTS: &[u8] = b"banned_guests";
+pub const PREFIX_PAYOUTS: &[u8] = b"payouts";
+pub const PREFIX_ORGANISERS: &[u8] = b"organisers";
+pub const PREFIX_ORGANISER_PROFILES: &[u8] = b"organiser_profiles";
+pub const PREFIX_DISPUTES: &[u8] = b"disputes";
+pub const PREFIX_VIEWING_KEYS: &[u8] = b"viewing_keys";
+pub const PREFIX_REVOKED_PERMITS: &[u8] = b"revoked_permits";
+pub const PREFIX_PRIVATE_METADATA: &[u8] = b"private_metadata";
+pub const PREFIX_CHECK_INS: &[u8] = b"check_ins";
+pub const PREFIX_RECEIVERS: &[u8] = b"receivers";
+pub const PREFIX_ALLOWANCES: &[u8] = b"allowances";
+pub const PREFIX_ALLOWANCE_INDEXES: &[u8] = b"allowance_indexes";
+pub const PREFIX_RESERVES: &[u8] = b"reserves";
+pub const PREFIX_PASSES: &[u8] = b"passes";
+pub const PREFIX_FUNDINGS: &[u8] = b"fundings";
+pub const PREFIX_GUESTS_PASSES: &[u8] = b"guests_passes";
+pub const PREFIX_GROUP_ORDERS: &[u8] = b"group_orders";
+pub const PREFIX_TICKET_HISTORY: &[u8] = b"ticket_history";
+pub const PREFIX_EVENTS_TICKETS: &[u8] = b"events_tickets";
+
+// Name and version of the contract crate this deployment was built from
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Native denom accepted when none are configured
+pub const DEFAULT_DENOM: &str = "uscrt";
+
+// SNIP-20 token info of the sEVNT ledger, backed by deposited native tokens at the exchange rate
+pub const TOKEN_NAME: &str = "Secret Event Token";
+pub const TOKEN_SYMBOL: &str = "SEVNT";
+pub const TOKEN_DECIMALS: u8 = 6;
+
+// Prefix of generated viewing keys
+pub const VIEWING_KEY_PREFIX: &str = "api_key_";
+
+// Bech32 prefix of account addresses, used to derive a permit signer's address
+pub const ADDRESS_PREFIX: &str = "secret";
+
+// Default bond organisers must stake to register, in uscrt
+pub const DEFAULT_ORGANISER_BOND: u128 = 1_000_000;
+
+// Maximum decimals of the exchange rate, and seconds before a new rate takes effect while sEVNT
+// is outstanding, giving holders time to withdraw at the old rate
+pub const MAX_RATE_DECIMALS: u8 = 18;
+pub const EXCHANGE_RATE_DELAY: u64 = 86_400;
+
+// Symbols the price oracle is queried for, and seconds after which its rate is too stale to use
+pub const ORACLE_BASE_SYMBOL: &str = "SCRT";
+pub const ORACLE_QUOTE_SYMBOL: &str = "USD";
+pub const MAX_ORACLE_AGE: u64 = 3_600;
+
+// Maximum share of the primary denom's reserve that can be staked, in basis points, and the
+// seconds undelegated tokens take to unbond
+pub const MAX_STAKE_BPS: u32 = 5_000;
+pub const UNBONDING_PERIOD: u64 = 21 * 86_400;
+
+// Maximum payees an event's proceeds can be split among
+pub const MAX_PAYEES: usize = 10;
+
+// Maximum installments a ticket's price can be spread over after the down payment
+pub const MAX_INSTALLMENTS: u32 = 12;
+
+// Page size of paginated queries when none is given, and the largest page that can be requested
+pub const DEFAULT_PAGE_LIMIT: u32 = 10;
+pub const MAX_PAGE_LIMIT: u32 = 30;
+
+// Maximum lengths of an event's public name, venue and each of its tags, and the most tags it
+// can carry
+pub const MAX_NAME_LEN: usize = 64;
+pub const MAX_VENUE_LEN: usize = 128;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_TAGS: usize = 10;
+
+// Maximum length of an organiser's contact URI, their display name shares the event name limit
+pub const MAX_CONTACT_URI_LEN: usize = 256;
+
+// Default and minimum seconds per bucket of check-in statistics
+pub const DEFAULT_STATS_BUCKET: u64 = 300;
+pub const MIN_STATS_BUCKET: u64 = 60;
+
+// Maximum platform fee, in basis points
+pub const MAX_FEE_BPS: u32 = 1_000;
+
+// Default and maximum seconds after every event completes during which guests may open
+// disputes and proceeds stay in escrow, events may hold proceeds for longer
+pub const DEFAULT_DISPUTE_WINDOW: u64 = 3 * 24 * 60 * 60;
+pub const MAX_DISPUTE_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+// Insurance premium charged on each ticket to an insured event, in basis points of its price
+pub const INSURANCE_PREMIUM_BPS: u128 = 200;
+
+// Seconds each rotating ticket code is valid for, and the number of digits in a code
+pub const CODE_WINDOW: u64 = 30;
+pub const CODE_DIGITS: u32 = 6;
+
+// Default seconds a verification challenge stays open
+pub const DEFAULT_CHALLENGE_TTL: u64 = 300;
+
+// Seconds a guest's check-in confirmation stays valid for two-factor events
+pub const TWO_FACTOR_WINDOW: u64 = 120;
+
+// Failed secret submissions before a ticket is locked, and seconds it stays locked
+pub const MAX_VERIFY_ATTEMPTS: u32 = 5;
+pub const VERIFY_LOCKOUT: u64 = 600;
+
+// Contract status levels
+pub const STATUS_NORMAL: u8 = 0;
+pub const STATUS_STOP_PURCHASES: u8 = 1;
+pub const STATUS_STOP_ALL: u8 = 2;
+
+// Ticket states
+pub const TICKET_VALID: u8 = 0;
+pub const TICKET_VALIDATING: u8 = 1;
+pub const TICKET_USED: u8 = 2;
+pub const TICKET_REVOKED: u8 = 3;
+pub const TICKET_REFUNDED: u8 = 4;
+pub const TICKET_RESERVED: u8 = 5;
+
+// Actions recorded in a ticket's history
+pub const ACTION_PURCHASED: u8 = 0;
+pub const ACTION_CHALLENGED: u8 = 1;
+pub const ACTION_CHECKED_IN: u8 = 2;
+pub const ACTION_TRANSFERRED: u8 = 3;
+pub const ACTION_REVOKED: u8 = 4;
+pub const ACTION_REFUNDED: u8 = 5;
+
+// Guest public key types
+pub const KEY_X25519: u8 = 0;
+pub const KEY_ED25519: u8 = 1;
+pub const KEY_SECP256K1: u8 = 2;
+// RSA keys in PEM that tickets were bought with before typed keys, which secrets can no longer
+// be encrypted to
+pub const KEY_RSA: u8 = 3;
 
 // Struct to store contract config
 #[derive(Serialize, Deserialize)]
 pub struct Config {
     owner: CanonicalAddr,
     num_events: u128,
-    num_tickets: u128
+    num_tickets: u128,
+    num_passes: u128,
+    num_group_orders: u128,
+    fee_bps: u32,
+    fee_recipient: CanonicalAddr,
+    dispute_window: u64,
+    status: u8,
+    organiser_bond: u128,
+    // Primary denom held as bonds of registered organisers, which does not back sEVNT
+    bonds: u128,
+    arbiters: Vec<CanonicalAddr>,
+    insurance_pool: u128,
+    prng_seed: [u8; 32],
+    payload_key: [u8; 32],
+    // Address and code hash of the sSCRT contract, and the sSCRT it holds
+    sscrt: Option<(CanonicalAddr, String)>,
+    sscrt_reserve: u128,
+    denoms: Vec<String>,
+    total_supply: u128,
+    exchange_rate: ExchangeRate,
+    // Rate replacing the current one and the time it takes effect at
+    pending_exchange_rate: Option<(ExchangeRate, u64)>,
+    // Address and code hash of the price oracle
+    oracle: Option<(CanonicalAddr, String)>,
+    staking: Option<Staking>,
+    // Smallest sEVNT amounts that can be deposited or withdrawn
+    min_deposit: u128,
+    min_withdrawal: u128,
+    // Most sEVNT that can be withdrawn in one block, and the block height and amount withdrawn
+    // in the latest block with withdrawals
+    withdrawal_cap: Option<u128>,
+    withdrawn: (u64, u128)
 }
 
 impl Config {
-    pub fn new(owner: CanonicalAddr) -> Self {
+    pub fn new(owner: CanonicalAddr, organiser_bond: u128, prng_seed: [u8; 32]) -> Self {
+        // Fixed key authenticating QR payloads, unlike the seed it does not change between calls
+        let mut hasher = Sha256::new_with_prefix(prng_seed);
+        hasher.update(b"qr payload");
+        let payload_key = hasher.finalize().into();
+
         Self {
-            owner: owner,
+            owner: owner.clone(),
             num_events: 0,
-            num_tickets: 0
+            num_tickets: 0,
+            num_passes: 0,
+            num_group_orders: 0,
+            fee_bps: 0,
+            fee_recipient: owner,
+            dispute_window: DEFAULT_DISPUTE_WINDOW,
+            status: STATUS_NORMAL,
+            organiser_bond,
+            bonds: 0,
+            arbiters: vec![],
+            insurance_pool: 0,
+            prng_seed,
+            payload_key,
+            sscrt: None,
+            sscrt_reserve: 0,
+            denoms: vec![DEFAULT_DENOM.to_string()],
+            total_supply: 0,
+            exchange_rate: ExchangeRate { rate: 1, decimals: 0 },
+            pending_exchange_rate: None,
+            oracle: None,
+            staking: None,
+            min_deposit: 0,
+            min_withdrawal: 0,
+            withdrawal_cap: None,
+            withdrawn: (0, 0)
+        }
+    }
+
+    pub fn get_payload_key(&self) -> &[u8; 32] {
+        &self.payload_key
+    }
+
+    // Contract-wide seed all randomness is derived from
+    pub fn get_prng_seed(&self) -> &[u8; 32] {
+        &self.prng_seed
+    }
+
+    // Fold new entropy into the seed
+    pub fn add_entropy(&mut self, entropy: &[u8]) {
+        let mut hasher = Sha256::new_with_prefix(self.prng_seed);
+        hasher.update(entropy);
+        self.prng_seed = hasher.finalize().into();
+    }
+
+    // Derive a viewing key for an address from the current seed and their entropy
+    pub fn generate_viewing_key(&self, address: &CanonicalAddr, entropy: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new_with_prefix(self.prng_seed);
+        hasher.update(b"viewing key");
+        hasher.update(address.as_slice());
+        hasher.update(entropy);
+        hasher.finalize().into()
+    }
+
+    // Derive the seed a ticket's rotating codes are generated from, chained from its previous
+    // seed so every rotation yields a new one
+    pub fn generate_code_seed(&self, ticket_id: u128, previous: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new_with_prefix(self.prng_seed);
+        hasher.update(b"ticket code");
+        hasher.update(ticket_id.to_be_bytes());
+        hasher.update(previous);
+        hasher.finalize().into()
+    }
+
+    // Derive the one-time nonce binding a ticket's challenge issuance to its check-in
+    pub fn generate_nonce(&self, ticket_id: u128, challenge: u32) -> u64 {
+        let mut hasher = Sha256::new_with_prefix(self.prng_seed);
+        hasher.update(b"challenge nonce");
+        hasher.update(ticket_id.to_be_bytes());
+        hasher.update(challenge.to_be_bytes());
+        let digest = hasher.finalize();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+
+    // Derive a ticket's one-time offline check-in code from the current seed
+    pub fn generate_offline_code(&self, ticket_id: u128) -> u64 {
+        let mut hasher = Sha256::new_with_prefix(self.prng_seed);
+        hasher.update(b"offline code");
+        hasher.update(ticket_id.to_be_bytes());
+        let digest = hasher.finalize();
+        u64::from_be_bytes(digest[..8].try_into().unwrap())
+    }
+
+    // Derive the short code a guest shows the organiser when checking themselves in
+    pub fn generate_checkin_code(&self, ticket_id: u128) -> u32 {
+        let mut hasher = Sha256::new_with_prefix(self.prng_seed);
+        hasher.update(b"checkin code");
+        hasher.update(ticket_id.to_be_bytes());
+        let digest = hasher.finalize();
+        u32::from_be_bytes(digest[..4].try_into().unwrap()) % 10u32.pow(CODE_DIGITS)
+    }
+
+    // Premiums collected to refund holders of cancelled insured events
+    pub fn get_insurance_pool(&self) -> u128 {
+        self.insurance_pool
+    }
+
+    pub fn add_insurance_premium(&mut self, premium: u128) {
+        self.insurance_pool += premium;
+    }
+
+    // Take up to amount from the insurance pool, returning the amount taken
+    pub fn take_insurance(&mut self, amount: u128) -> u128 {
+        let taken = amount.min(self.insurance_pool);
+        self.insurance_pool -= taken;
+        taken
+    }
+
+    pub fn set_arbiters(&mut self, arbiters: Vec<CanonicalAddr>) {
+        self.arbiters = arbiters;
+    }
+
+    // Check if address may resolve disputes, as the owner or an arbiter
+    pub fn can_arbitrate(&self, address: &CanonicalAddr) -> bool {
+        self.owner == *address || self.arbiters.contains(address)
+    }
+
+    // Aggregate sEVNT issued against deposits and not yet withdrawn, the contract's liability
+    pub fn get_total_supply(&self) -> u128 {
+        self.total_supply
+    }
+
+    pub fn mint(&mut self, amount: u128) {
+        self.total_supply += amount;
+    }
+
+    pub fn burn(&mut self, amount: u128) {
+        self.total_supply -= amount;
+    }
+
+    // Rate native tokens are exchanged for sEVNT at the given time
+    pub fn get_exchange_rate(&self, now: u64) -> ExchangeRate {
+        match self.pending_exchange_rate {
+            Some((rate, effective_at)) if now >= effective_at => rate,
+            _ => self.exchange_rate,
+        }
+    }
+
+    pub fn get_pending_exchange_rate(&self, now: u64) -> Option<(ExchangeRate, u64)> {
+        self.pending_exchange_rate.filter(|(_, effective_at)| now < *effective_at)
+    }
+
+    // Schedule a new rate, taking effect immediately if no sEVNT is outstanding and otherwise
+    // after a delay, so it cannot change the value of a withdrawal already in flight
+    pub fn set_exchange_rate(&mut self, rate: ExchangeRate, now: u64) -> u64 {
+        self.exchange_rate = self.get_exchange_rate(now);
+        if self.total_supply == 0 {
+            self.exchange_rate = rate;
+            self.pending_exchange_rate = None;
+            now
+        } else {
+            let effective_at = now + EXCHANGE_RATE_DELAY;
+            self.pending_exchange_rate = Some((rate, effective_at));
+            effective_at
+        }
+    }
+
+    // Native denoms deposits are accepted in, the first is used for organiser bonds and as the
+    // default for withdrawals
+    pub fn get_denoms(&self) -> &Vec<String> {
+        &self.denoms
+    }
+
+    pub fn set_denoms(&mut self, denoms: Vec<String>) -> StdResult<()> {
+        if denoms.is_empty() {
+            return Err(StdError::generic_err("At least one denom must be accepted"));
+        }
+        self.denoms = denoms;
+        Ok(())
+    }
+
+    pub fn get_primary_denom(&self) -> &str {
+        &self.denoms[0]
+    }
+
+    pub fn accepts_denom(&self, denom: &str) -> bool {
+        self.denoms.iter().any(|accepted| accepted == denom)
+    }
+
+    // sSCRT contract whose Receive callbacks are credited as deposits, if configured
+    pub fn get_sscrt(&self) -> Option<&(CanonicalAddr, String)> {
+        self.sscrt.as_ref()
+    }
+
+    pub fn set_sscrt(&mut self, sscrt: Option<(CanonicalAddr, String)>) {
+        self.sscrt = sscrt;
+    }
+
+    pub fn get_sscrt_reserve(&self) -> u128 {
+        self.sscrt_reserve
+    }
+
+    pub fn set_sscrt_reserve(&mut self, sscrt_reserve: u128) {
+        self.sscrt_reserve = sscrt_reserve;
+    }
+
+    // Oracle pricing SCRT in USD for stable priced events, if configured
+    pub fn get_oracle(&self) -> Option<&(CanonicalAddr, String)> {
+        self.oracle.as_ref()
+    }
+
+    pub fn set_oracle(&mut self, oracle: Option<(CanonicalAddr, String)>) {
+        self.oracle = oracle;
+    }
+
+    // Delegation of idle deposits, if configured
+    pub fn get_staking(&self) -> Option<&Staking> {
+        self.staking.as_ref()
+    }
+
+    pub fn get_staking_mut(&mut self) -> Option<&mut Staking> {
+        self.staking.as_mut()
+    }
+
+    pub fn set_staking(&mut self, staking: Option<Staking>) {
+        self.staking = staking;
+    }
+
+    // Tokens of a denom's reserve not delegated or unbonding, which can be paid out
+    pub fn liquid_reserve(&self, denom: &str, reserve: u128, now: u64) -> u128 {
+        match &self.staking {
+            Some(staking) if denom == self.get_primary_denom() => reserve.saturating_sub(staking.get_locked(now)),
+            _ => reserve,
+        }
+    }
+
+    // Bond organisers must stake to register, in uscrt
+    pub fn get_organiser_bond(&self) -> u128 {
+        self.organiser_bond
+    }
+
+    pub fn get_bonds(&self) -> u128 {
+        self.bonds
+    }
+
+    pub fn add_bond(&mut self, bond: u128) {
+        self.bonds += bond;
+    }
+
+    pub fn remove_bond(&mut self, bond: u128) {
+        self.bonds -= bond;
+    }
+
+    pub fn get_status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn set_status(&mut self, status: u8) {
+        self.status = status;
+    }
+
+    // Platform fee taken from organiser payouts, in basis points
+    pub fn get_fee_bps(&self) -> u32 {
+        self.fee_bps
+    }
+
+    pub fn get_fee_recipient(&self) -> &CanonicalAddr {
+        &self.fee_recipient
+    }
+
+    pub fn set_fee_config(&mut self, fee_bps: u32, fee_recipient: CanonicalAddr) -> StdResult<()> {
+        if fee_bps > MAX_FEE_BPS {
+            return Err(StdError::generic_err(format!("Fee cannot exceed {} basis points", MAX_FEE_BPS)));
+        }
+        self.fee_bps = fee_bps;
+        self.fee_recipient = fee_recipient;
+        Ok(())
+    }
+
+    // Minimum seconds after an event completes before its proceeds are released
+    pub fn get_dispute_window(&self) -> u64 {
+        self.dispute_window
+    }
+
+    pub fn set_dispute_window(&mut self, dispute_window: u64) -> StdResult<()> {
+        if dispute_window == 0 || dispute_window > MAX_DISPUTE_WINDOW {
+            return Err(StdError::generic_err(format!(
+                "Dispute window must be between 1 and {} seconds", MAX_DISPUTE_WINDOW
+            )));
         }
+        self.dispute_window = dispute_window;
+        Ok(())
     }
 
     pub fn get_owner(&self) -> &CanonicalAddr {
         &self.owner
     }
 
+    pub fn get_min_deposit(&self) -> u128 {
+        self.min_deposit
+    }
+
+    pub fn get_min_withdrawal(&self) -> u128 {
+        self.min_withdrawal
+    }
+
+    pub fn get_withdrawal_cap(&self) -> Option<u128> {
+        self.withdrawal_cap
+    }
+
+    pub fn set_limits(&mut self, min_deposit: u128, min_withdrawal: u128, withdrawal_cap: Option<u128>) -> StdResult<()> {
+        if withdrawal_cap == Some(0) {
+            return Err(StdError::generic_err("Withdrawal cap must be at least 1"));
+        }
+        if matches!(withdrawal_cap, Some(cap) if cap < min_withdrawal) {
+            return Err(StdError::generic_err("Withdrawal cap cannot be below the minimum withdrawal"));
+        }
+        self.min_deposit = min_deposit;
+        self.min_withdrawal = min_withdrawal;
+        self.withdrawal_cap = withdrawal_cap;
+        Ok(())
+    }
+
+    // Record a withdrawal at the given block height, failing if it takes the block's
+    // withdrawals over the cap
+    pub fn record_withdrawal(&mut self, height: u64, amount: u128) -> StdResult<()> {
+        let (withdrawn_height, withdrawn) = self.withdrawn;
+        let withdrawn = if withdrawn_height == height { withdrawn } else { 0 };
+        if let Some(cap) = self.withdrawal_cap {
+            if withdrawn + amount > cap {
+                return Err(StdError::generic_err(format!(
+                    "Withdrawal exceeds the cap for this block: available={}, required={}",
+                    cap - withdrawn, amount
+                )));
+            }
+        }
+        self.withdrawn = (height, withdrawn + amount);
+        Ok(())
+    }
+
     pub fn get_num_events(&self) -> u128 {
         self.num_events
     }
@@ -48,6 +541,14 @@ impl Config {
         self.num_tickets
     }
 
+    pub fn get_num_passes(&self) -> u128 {
+        self.num_passes
+    }
+
+    pub fn get_num_group_orders(&self) -> u128 {
+        self.num_group_orders
+    }
+
     pub fn get_next_event_id(&mut self) -> u128 {
         self.num_events += 1;
         self.num_events
@@ -58,6 +559,130 @@ impl Config {
         self.num_tickets
     }
 
+    pub fn get_next_pass_id(&mut self) -> u128 {
+        self.num_passes += 1;
+        self.num_passes
+    }
+
+    pub fn get_next_group_order_id(&mut self) -> u128 {
+        self.num_group_orders += 1;
+        self.num_group_orders
+    }
+
+}
+
+// Struct to store the delegation of part of the primary denom's reserve to a validator, whose
+// rewards are withdrawn straight to a treasury
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Staking {
+    validator: String,
+    stake_bps: u32,
+    treasury: CanonicalAddr,
+    staked: u128,
+    // Undelegated amounts and the time each finishes unbonding
+    unbonding: Vec<(u128, u64)>
+}
+
+impl Staking {
+    pub fn new(validator: String, stake_bps: u32, treasury: CanonicalAddr) -> StdResult<Self> {
+        if stake_bps > MAX_STAKE_BPS {
+            return Err(StdError::generic_err(format!("Cannot stake more than {} basis points of deposits", MAX_STAKE_BPS)));
+        }
+        Ok(Self { validator, stake_bps, treasury, staked: 0, unbonding: vec![] })
+    }
+
+    pub fn get_validator(&self) -> &str {
+        &self.validator
+    }
+
+    pub fn get_stake_bps(&self) -> u32 {
+        self.stake_bps
+    }
+
+    // Update the target share, keeping what is already delegated and unbonding
+    pub fn set_stake_bps(&mut self, stake_bps: u32) -> StdResult<()> {
+        if stake_bps > MAX_STAKE_BPS {
+            return Err(StdError::generic_err(format!("Cannot stake more than {} basis points of deposits", MAX_STAKE_BPS)));
+        }
+        self.stake_bps = stake_bps;
+        Ok(())
+    }
+
+    pub fn get_treasury(&self) -> &CanonicalAddr {
+        &self.treasury
+    }
+
+    pub fn set_treasury(&mut self, treasury: CanonicalAddr) {
+        self.treasury = treasury;
+    }
+
+    pub fn get_staked(&self) -> u128 {
+        self.staked
+    }
+
+    // Amount still unbonding at the given time
+    pub fn get_unbonding(&self, now: u64) -> u128 {
+        self.unbonding.iter().filter(|(_, release)| *release > now).map(|(amount, _)| amount).sum()
+    }
+
+    // Amount delegated or unbonding, which cannot be paid out
+    pub fn get_locked(&self, now: u64) -> u128 {
+        self.staked + self.get_unbonding(now)
+    }
+
+    pub fn delegate(&mut self, amount: u128) {
+        self.staked += amount;
+    }
+
+    // Undelegate an amount, dropping any unbonding that has completed
+    pub fn undelegate(&mut self, amount: u128, now: u64) {
+        self.unbonding.retain(|(_, release)| *release > now);
+        self.staked -= amount;
+        self.unbonding.push((amount, now + UNBONDING_PERIOD));
+    }
+}
+
+// Struct to store an exchange rate, the sEVNT minted per 10^decimals native tokens
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ExchangeRate {
+    rate: u128,
+    decimals: u8
+}
+
+impl ExchangeRate {
+    pub fn new(rate: u128, decimals: u8) -> StdResult<Self> {
+        if rate == 0 {
+            return Err(StdError::generic_err("Exchange rate must be positive"));
+        }
+        if decimals > MAX_RATE_DECIMALS {
+            return Err(StdError::generic_err(format!("Exchange rate cannot exceed {} decimals", MAX_RATE_DECIMALS)));
+        }
+        Ok(Self { rate, decimals })
+    }
+
+    pub fn get_rate(&self) -> u128 {
+        self.rate
+    }
+
+    pub fn get_decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    // sEVNT minted for native tokens, rounded down
+    pub fn to_tokens(&self, native: u128) -> StdResult<u128> {
+        native
+            .checked_mul(self.rate)
+            .map(|scaled| scaled / 10u128.pow(self.decimals as u32))
+            .ok_or_else(|| StdError::generic_err("Amount is too large to exchange"))
+    }
+
+    // Native tokens paid out for sEVNT, rounded down
+    pub fn to_native(&self, tokens: u128) -> StdResult<u128> {
+        tokens
+            .checked_mul(10u128.pow(self.decimals as u32))
+            .map(|scaled| scaled / self.rate)
+            .ok_or_else(|| StdError::generic_err("Amount is too large to exchange"))
+    }
 }
 
 // Get config singleton storage structure
@@ -70,6 +695,156 @@ pub fn get_config_readonly(storage: &dyn Storage) -> ReadonlySingleton<Config> {
     singleton_read(storage, KEY_CONFIG)
 }
 
+// Version of the storage layout, bumped with a migration whenever stored data must be upgraded.
+// Contracts instantiated before versioning have no stored version and are at version 0
+pub const SCHEMA_VERSION: u32 = 2;
+
+// Get schema version singleton storage structure
+pub fn get_schema_version(storage: &mut dyn Storage) -> Singleton<'_, u32> {
+    singleton(storage, KEY_SCHEMA_VERSION)
+}
+
+// Get READONLY schema version singleton storage structure
+pub fn get_schema_version_readonly(storage: &dyn Storage) -> ReadonlySingleton<'_, u32> {
+    singleton_read(storage, KEY_SCHEMA_VERSION)
+}
+
+// Rewrite every entry under a prefix keyed by id, from 1 to the number created, decoding each in
+// an older layout and upgrading it to the current one. For migrations that change the fields of
+// stored structs such as events and tickets
+pub fn upgrade_entries<Old, New: Serialize>(
+    storage: &mut dyn Storage,
+    prefix: &[u8],
+    count: u128,
+    decode: impl Fn(&[u8]) -> StdResult<Old>,
+    upgrade: impl Fn(Old) -> New,
+) -> StdResult<()> {
+    let mut entries = PrefixedStorage::new(storage, prefix);
+    for id in 1..=count {
+        let old_bytes = match entries.get(&id.to_be_bytes()) {
+            Some(old_bytes) => old_bytes,
+            None => continue,
+        };
+        entries.set(&id.to_be_bytes(), &serialize(&upgrade(decode(&old_bytes)?))?);
+    }
+    Ok(())
+}
+
+// Config as stored before schema versioning, which was already JSON
+#[derive(Serialize, Deserialize)]
+pub struct LegacyConfig {
+    owner: CanonicalAddr,
+    num_events: u128,
+    num_tickets: u128
+}
+
+// Get READONLY config singleton storage structure, read in its layout before schema versioning
+pub fn get_legacy_config_readonly(storage: &dyn Storage) -> ReadonlySingleton<'_, LegacyConfig> {
+    singleton_read(storage, KEY_CONFIG)
+}
+
+impl LegacyConfig {
+    pub fn get_num_events(&self) -> u128 {
+        self.num_events
+    }
+
+    pub fn get_num_tickets(&self) -> u128 {
+        self.num_tickets
+    }
+
+    // Upgrade to the current config, keeping the owner and ids issued
+    pub fn upgrade(self, prng_seed: [u8; 32], total_supply: u128) -> Config {
+        let mut config = Config::new(self.owner, DEFAULT_ORGANISER_BOND, prng_seed);
+        config.num_events = self.num_events;
+        config.num_tickets = self.num_tickets;
+        config.total_supply = total_supply;
+        config
+    }
+}
+
+// Event as stored before schema versioning, bincode encoded with a single price and capacity
+pub struct LegacyEvent {
+    id: u128,
+    organiser: CanonicalAddr,
+    price: u128,
+    max_tickets: u128,
+    tickets_sold: u128,
+    seed: [u8; 32]
+}
+
+impl LegacyEvent {
+    pub fn decode(bytes: &[u8]) -> StdResult<Self> {
+        let mut reader = LegacyReader::new(bytes);
+        let event = LegacyEvent {
+            id: reader.read_u128()?,
+            organiser: reader.read_addr()?,
+            price: reader.read_u128()?,
+            max_tickets: reader.read_u128()?,
+            tickets_sold: reader.read_u128()?,
+            seed: reader.read_bytes32()?
+        };
+        reader.finish()?;
+        Ok(event)
+    }
+
+    // Upgrade to an event with its price and capacity as the default tier. Sales were paid
+    // straight to the organiser, so nothing is held in escrow
+    pub fn upgrade(self) -> Event {
+        let mut event = Event::new(self.id, self.organiser, self.price, self.max_tickets);
+        event.tiers[0].tickets_sold = self.tickets_sold;
+        event.revenue = self.price.saturating_mul(self.tickets_sold);
+        event.seed = self.seed;
+        event
+    }
+}
+
+// Ticket as stored before schema versioning, bincode encoded with an RSA key
+pub struct LegacyTicket {
+    id: u128,
+    guest: CanonicalAddr,
+    event_id: u128,
+    state: u8,
+    pk: String
+}
+
+impl LegacyTicket {
+    pub fn decode(bytes: &[u8]) -> StdResult<Self> {
+        let mut reader = LegacyReader::new(bytes);
+        let id = reader.read_u128()?;
+        let guest = reader.read_addr()?;
+        let event_id = reader.read_u128()?;
+        // Tickets being validated return to valid, as their secret does not carry over and a new
+        // challenge must be issued
+        let state = match reader.read_u8()? {
+            0 | 1 => TICKET_VALID,
+            2 => TICKET_USED,
+            _ => return Err(StdError::generic_err("Corrupted legacy entry. Unknown ticket state.")),
+        };
+        reader.read_u64()?;
+        let pk = reader.read_string()?;
+        reader.finish()?;
+        Ok(LegacyTicket { id, guest, event_id, state, pk })
+    }
+
+    pub fn get_id(&self) -> u128 {
+        self.id
+    }
+
+    pub fn get_event_id(&self) -> u128 {
+        self.event_id
+    }
+
+    // Upgrade to a ticket of the default tier bought at the given price, with the given seeds
+    pub fn upgrade(self, price: u128, seed: [u8; 32], code_seed: [u8; 32]) -> Ticket {
+        let pk = GuestKey { key_type: KEY_RSA, key: self.pk.into_bytes() };
+        let mut ticket = Ticket::new(self.id, self.event_id, self.guest, pk, DEFAULT_TIER.to_string(), price);
+        ticket.state = self.state;
+        ticket.seed = seed;
+        ticket.code_seed = code_seed;
+        ticket
+    }
+}
+
 // Struct to handle READONLY interaction with balances 
 pub struct ReadonlyBalances<'a> {
     storage: ReadonlyPrefixedStorage<'a>
@@ -85,388 +860,3750 @@ impl<'a> ReadonlyBalances<'a> {
     }
 
     // Read balance of an account
-    pub fn read_account_balance(&self, account: &CanonicalAddr) -> u128 {
+    pub fn read_account_balance(&self, account: &CanonicalAddr) -> StdResult<u128> {
         let account_bytes = account.as_slice();
         let result = self.storage.get(account_bytes);
         match result {
-            Some(balance_bytes) => slice_to_u128(&balance_bytes).unwrap(),
-            None => 0,
+            Some(balance_bytes) => slice_to_u128(&balance_bytes),
+            None => Ok(0),
+        }
+    }
+}
+
+// Struct to handle interaction with balances 
+pub struct Balances<'a> {
+    storage: PrefixedStorage<'a>,
+}
+
+impl<'a> Balances<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_BALANCES),
+        }
+    }
+
+    // Set balance of an account
+    pub fn set_account_balance(& mut self, account: &CanonicalAddr, amount: u128) {
+        self.storage.set(account.as_slice(), &amount.to_be_bytes());
+    }
+
+    // Read balance of an account
+    pub fn read_account_balance(&self, account: &CanonicalAddr) -> StdResult<u128> {
+        let account_bytes = account.as_slice();
+        let result = self.storage.get(account_bytes);
+        match result {
+            Some(balance_bytes) => slice_to_u128(&balance_bytes),
+            None => Ok(0),
+        }
+    }
+}
+
+// Default tier created when an event is not given explicit tiers
+pub const DEFAULT_TIER: &str = "general";
+
+// Step of an early-bird price schedule
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceStep {
+    price: u128,
+    until_sold: Option<u128>,
+    until_time: Option<u64>
+}
+
+impl PriceStep {
+    pub fn new(price: u128, until_sold: Option<u128>, until_time: Option<u64>) -> Self {
+        PriceStep {
+            price,
+            until_sold,
+            until_time
+        }
+    }
+
+    pub fn get_price(&self) -> u128 {
+        self.price
+    }
+
+    // Check if the step applies given tickets sold and time (seconds)
+    pub fn applies(&self, sold: u128, now: u64) -> bool {
+        let before_sold = match self.until_sold {
+            Some(until_sold) => sold < until_sold,
+            None => true,
+        };
+        let before_time = match self.until_time {
+            Some(until_time) => now < until_time,
+            None => true,
+        };
+        before_sold && before_time
+    }
+}
+
+// Session of a multi-day event, in seconds since epoch
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    start: u64,
+    end: u64
+}
+
+impl Session {
+    pub fn new(start: u64, end: u64) -> Self {
+        Session {
+            start,
+            end
+        }
+    }
+
+    pub fn get_start(&self) -> u64 {
+        self.start
+    }
+
+    pub fn get_end(&self) -> u64 {
+        self.end
+    }
+}
+
+// Payee receiving a share of an event's proceeds, in basis points
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Payee {
+    address: CanonicalAddr,
+    bps: u32
+}
+
+impl Payee {
+    pub fn new(address: CanonicalAddr, bps: u32) -> Self {
+        Payee {
+            address,
+            bps
+        }
+    }
+
+    pub fn get_address(&self) -> &CanonicalAddr {
+        &self.address
+    }
+
+    pub fn get_bps(&self) -> u32 {
+        self.bps
+    }
+}
+
+// Installment plan letting guests reserve a ticket with a down payment, in basis points of its
+// price, and pay the rest in equal installments due every interval seconds
+#[derive(Clone, Serialize, Deserialize)]
+pub struct InstallmentPlan {
+    down_payment_bps: u32,
+    installments: u32,
+    interval: u64
+}
+
+impl InstallmentPlan {
+    pub fn new(down_payment_bps: u32, installments: u32, interval: u64) -> StdResult<Self> {
+        if down_payment_bps >= 10_000 {
+            return Err(StdError::generic_err("Down payment must be less than 10000 basis points"));
+        }
+        if installments == 0 || installments > MAX_INSTALLMENTS {
+            return Err(StdError::generic_err(format!(
+                "Installments must be between 1 and {}",
+                MAX_INSTALLMENTS
+            )));
+        }
+        if interval == 0 {
+            return Err(StdError::generic_err("Installment interval must be at least 1 second"));
+        }
+        Ok(InstallmentPlan {
+            down_payment_bps,
+            installments,
+            interval
+        })
+    }
+
+    pub fn get_down_payment_bps(&self) -> u32 {
+        self.down_payment_bps
+    }
+
+    pub fn get_installments(&self) -> u32 {
+        self.installments
+    }
+
+    pub fn get_interval(&self) -> u64 {
+        self.interval
+    }
+
+    // Down payment reserving a ticket at the given price
+    pub fn down_payment(&self, price: u128) -> StdResult<u128> {
+        bps_of(price, self.down_payment_bps)
+            .ok_or_else(|| StdError::generic_err("Price is too large to take a down payment of"))
+    }
+}
+
+// Dutch auction, decaying the price each block from a start price to a floor
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DutchAuction {
+    start_price: u128,
+    floor_price: u128,
+    start_height: u64,
+    decay_per_block: u128
+}
+
+impl DutchAuction {
+    pub fn new(start_price: u128, floor_price: u128, start_height: u64, decay_per_block: u128) -> StdResult<Self> {
+        if floor_price > start_price {
+            return Err(StdError::generic_err("Floor price cannot exceed start price"));
+        }
+        Ok(DutchAuction {
+            start_price,
+            floor_price,
+            start_height,
+            decay_per_block
+        })
+    }
+
+    // Price at the given block height
+    pub fn price_at(&self, height: u64) -> u128 {
+        let blocks = height.saturating_sub(self.start_height) as u128;
+        let decay = blocks.saturating_mul(self.decay_per_block);
+        self.start_price.saturating_sub(decay).max(self.floor_price)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Tier {
+    name: String,
+    price: u128,
+    max_tickets: u128,
+    tickets_sold: u128,
+    schedule: Vec<PriceStep>,
+    auction: Option<DutchAuction>
+}
+
+impl Tier {
+    pub fn new(name: String, price: u128, max_tickets: u128) -> Self {
+        Tier {
+            name,
+            price,
+            max_tickets,
+            tickets_sold: 0,
+            schedule: vec![],
+            auction: None
+        }
+    }
+
+    pub fn set_schedule(&mut self, schedule: Vec<PriceStep>) {
+        self.schedule = schedule;
+    }
+
+    pub fn set_auction(&mut self, auction: DutchAuction) {
+        self.auction = Some(auction);
+    }
+
+    // Price of a ticket after the given number have been sold, at the given time (seconds)
+    // and block height. A dutch auction takes precedence over the price schedule
+    pub fn price_at(&self, sold: u128, now: u64, height: u64) -> u128 {
+        if let Some(auction) = &self.auction {
+            return auction.price_at(height);
+        }
+        match self.schedule.iter().find(|step| step.applies(sold, now)) {
+            Some(step) => step.price,
+            None => self.price,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_price(&self) -> u128 {
+        self.price
+    }
+
+    pub fn get_max_tickets(&self) -> u128 {
+        self.max_tickets
+    }
+
+    pub fn get_tickets_sold(&self) -> u128 {
+        self.tickets_sold
+    }
+
+    pub fn get_tickets_left(&self) -> u128 {
+        self.max_tickets.saturating_sub(self.tickets_sold)
+    }
+
+    pub fn is_sold_out(&self) -> bool {
+        self.tickets_sold >= self.max_tickets
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Event {
+    id: u128,
+    organiser: CanonicalAddr,
+    name: String,
+    venue: String,
+    tags: Vec<String>,
+    co_organisers: Vec<CanonicalAddr>,
+    verifiers: Vec<CanonicalAddr>,
+    tiers: Vec<Tier>,
+    sale_start: Option<u64>,
+    sale_end: Option<u64>,
+    presale_start: Option<u64>,
+    start_time: Option<u64>,
+    end_time: Option<u64>,
+    // When the organiser marked an event without scheduled times as completed
+    completed_at: Option<u64>,
+    checkin_window: u64,
+    challenge_ttl: u64,
+    sessions: Vec<Session>,
+    refund_deadline: Option<u64>,
+    paused: bool,
+    seats: Option<Vec<String>>,
+    max_per_guest: u128,
+    max_resale_bps: u32,
+    royalty_bps: u32,
+    invite_key: Option<Vec<u8>>,
+    escrow: u128,
+    payout_delay: u64,
+    disputed: bool,
+    insured: bool,
+    cancelled: bool,
+    two_factor: bool,
+    stable_price: bool,
+    share_guests: bool,
+    payees: Vec<Payee>,
+    installment_plan: Option<InstallmentPlan>,
+    revenue: u128,
+    tips: u128,
+    seed: [u8; 32]
+}
+
+impl Event {
+    pub fn new(id: u128, organiser: CanonicalAddr, price: u128, max_tickets: u128) -> Self {
+        Event {
+            id,
+            organiser,
+            name: String::new(),
+            venue: String::new(),
+            tags: vec![],
+            co_organisers: vec![],
+            verifiers: vec![],
+            tiers: vec![Tier::new(DEFAULT_TIER.to_string(), price, max_tickets)],
+            sale_start: None,
+            sale_end: None,
+            presale_start: None,
+            start_time: None,
+            end_time: None,
+            completed_at: None,
+            checkin_window: 0,
+            challenge_ttl: DEFAULT_CHALLENGE_TTL,
+            sessions: vec![],
+            refund_deadline: None,
+            paused: false,
+            seats: None,
+            max_per_guest: 1,
+            max_resale_bps: 10_000,
+            royalty_bps: 0,
+            invite_key: None,
+            escrow: 0,
+            payout_delay: 0,
+            disputed: false,
+            insured: false,
+            cancelled: false,
+            two_factor: false,
+            stable_price: false,
+            share_guests: false,
+            payees: vec![],
+            installment_plan: None,
+            revenue: 0,
+            tips: 0,
+            seed: [0; 32]
+        }
+    }
+
+    // Replace the event's tiers, only allowed before any tickets are sold
+    pub fn set_tiers(&mut self, tiers: Vec<Tier>) -> StdResult<()> {
+        if tiers.is_empty() {
+            return Err(StdError::generic_err("Event must have at least one tier"));
+        }
+        for (i, tier) in tiers.iter().enumerate() {
+            if tier.name.is_empty() {
+                return Err(StdError::generic_err("Tier name cannot be empty"));
+            }
+            if tiers[..i].iter().any(|other| other.name == tier.name) {
+                return Err(StdError::generic_err(format!("Duplicate tier name: {}", tier.name)));
+            }
+        }
+        if self.get_tickets_sold() > 0 {
+            return Err(StdError::generic_err("Cannot replace tiers after tickets have been sold"));
+        }
+        self.tiers = tiers;
+        Ok(())
+    }
+
+    pub fn get_tiers(&self) -> &Vec<Tier> {
+        &self.tiers
+    }
+
+    pub fn get_tier(&self, index: usize) -> &Tier {
+        &self.tiers[index]
+    }
+
+    pub fn set_schedule(&mut self, tier: usize, schedule: Vec<PriceStep>) {
+        self.tiers[tier].set_schedule(schedule);
+    }
+
+    pub fn set_auction(&mut self, tier: usize, auction: DutchAuction) {
+        self.tiers[tier].set_auction(auction);
+    }
+
+    // Find the index of a tier by name, defaulting to the first tier
+    pub fn find_tier(&self, name: Option<&str>) -> StdResult<usize> {
+        match name {
+            None => Ok(0),
+            Some(name) => match self.tiers.iter().position(|tier| tier.name == name) {
+                Some(index) => Ok(index),
+                None => Err(StdError::generic_err(format!("Tier does not exist: {}", name))),
+            },
+        }
+    }
+
+    pub fn set_sale_window(&mut self, sale_start: Option<u64>, sale_end: Option<u64>) -> StdResult<()> {
+        if let (Some(start), Some(end)) = (sale_start, sale_end) {
+            if start >= end {
+                return Err(StdError::generic_err("Sale start must be before sale end"));
+            }
+        }
+        self.sale_start = sale_start;
+        self.sale_end = sale_end;
+        Ok(())
+    }
+
+    // Set the start of the allowlist presale, which runs until the public sale starts
+    pub fn set_presale_start(&mut self, presale_start: Option<u64>) -> StdResult<()> {
+        if let Some(presale_start) = presale_start {
+            match self.sale_start {
+                Some(sale_start) if presale_start < sale_start => {}
+                Some(_) => return Err(StdError::generic_err("Presale start must be before sale start")),
+                None => return Err(StdError::generic_err("Presale requires a sale start")),
+            }
+        }
+        self.presale_start = presale_start;
+        Ok(())
+    }
+
+    pub fn get_presale_start(&self) -> Option<u64> {
+        self.presale_start
+    }
+
+    pub fn get_sale_start(&self) -> Option<u64> {
+        self.sale_start
+    }
+
+    pub fn get_sale_end(&self) -> Option<u64> {
+        self.sale_end
+    }
+
+    pub fn set_event_times(&mut self, start_time: Option<u64>, end_time: Option<u64>, checkin_window: u64) -> StdResult<()> {
+        if let (Some(start), Some(end)) = (start_time, end_time) {
+            if start > end {
+                return Err(StdError::generic_err("Event start must not be after event end"));
+            }
+        }
+        if end_time.is_some() && start_time.is_none() {
+            return Err(StdError::generic_err("Event end given without an event start"));
+        }
+        self.start_time = start_time;
+        self.end_time = end_time;
+        self.checkin_window = checkin_window;
+        Ok(())
+    }
+
+    // Set the deadline for guest refunds, guests cannot refund if not given
+    pub fn set_refund_deadline(&mut self, refund_deadline: Option<u64>) {
+        self.refund_deadline = refund_deadline;
+    }
+
+    pub fn get_refund_deadline(&self) -> Option<u64> {
+        self.refund_deadline
+    }
+
+    pub fn check_refund_open(&self, now: u64) -> StdResult<()> {
+        match self.refund_deadline {
+            Some(deadline) if now < deadline => Ok(()),
+            Some(_) => Err(StdError::generic_err("Refund deadline has passed")),
+            None => Err(StdError::generic_err("Event does not allow refunds")),
+        }
+    }
+
+    pub fn get_start_time(&self) -> Option<u64> {
+        self.start_time
+    }
+
+    pub fn get_end_time(&self) -> Option<u64> {
+        self.end_time
+    }
+
+    pub fn get_checkin_window(&self) -> u64 {
+        self.checkin_window
+    }
+
+    // Seconds a verification challenge stays open before it expires
+    pub fn get_challenge_ttl(&self) -> u64 {
+        self.challenge_ttl
+    }
+
+    pub fn set_challenge_ttl(&mut self, challenge_ttl: u64) -> StdResult<()> {
+        if challenge_ttl == 0 {
+            return Err(StdError::generic_err("Challenge TTL must be at least 1 second"));
+        }
+        self.challenge_ttl = challenge_ttl;
+        Ok(())
+    }
+
+    // Check that tickets can be checked in at the given time (seconds)
+    // Set the sessions of a multi-day event, which must be in order and not overlap
+    pub fn set_sessions(&mut self, sessions: Vec<Session>) -> StdResult<()> {
+        for (i, session) in sessions.iter().enumerate() {
+            if session.start >= session.end {
+                return Err(StdError::generic_err("Session start must be before session end"));
+            }
+            if i > 0 && session.start < sessions[i - 1].end {
+                return Err(StdError::generic_err("Sessions must be in order and not overlap"));
+            }
+        }
+        self.sessions = sessions;
+        Ok(())
+    }
+
+    pub fn get_sessions(&self) -> &Vec<Session> {
+        &self.sessions
+    }
+
+    // Payees splitting the event's proceeds, which must add up to 10000 basis points
+    pub fn set_payees(&mut self, payees: Vec<Payee>) -> StdResult<()> {
+        if payees.len() > MAX_PAYEES {
+            return Err(StdError::generic_err(format!("Proceeds cannot be split among more than {} payees", MAX_PAYEES)));
+        }
+        if payees.iter().any(|payee| payee.bps == 0) {
+            return Err(StdError::generic_err("Each payee must receive a share of the proceeds"));
+        }
+        if payees.iter().enumerate().any(|(i, payee)| payees[..i].iter().any(|other| other.address == payee.address)) {
+            return Err(StdError::generic_err("Payees must be unique"));
+        }
+        if !payees.is_empty() && payees.iter().map(|payee| payee.bps as u64).sum::<u64>() != 10_000 {
+            return Err(StdError::generic_err("Payee shares must add up to 10000 basis points"));
+        }
+        self.payees = payees;
+        Ok(())
+    }
+
+    pub fn get_payees(&self) -> &Vec<Payee> {
+        &self.payees
+    }
+
+    // Plan guests may pay for tickets in installments under, if offered
+    pub fn get_installment_plan(&self) -> Option<&InstallmentPlan> {
+        self.installment_plan.as_ref()
+    }
+
+    pub fn set_installment_plan(&mut self, installment_plan: Option<InstallmentPlan>) {
+        self.installment_plan = installment_plan;
+    }
+
+    // Split proceeds among the payees, or give them all to the organiser if there are none. Any
+    // remainder from rounding goes to the first payee
+    pub fn split_proceeds(&self, amount: u128) -> StdResult<Vec<(CanonicalAddr, u128)>> {
+        if self.payees.is_empty() {
+            return Ok(vec![(self.organiser.clone(), amount)]);
+        }
+        let mut shares = self.payees
+            .iter()
+            .map(|payee| match bps_of(amount, payee.bps) {
+                Some(share) => Ok((payee.address.clone(), share)),
+                None => Err(StdError::generic_err("Proceeds are too large to split")),
+            })
+            .collect::<StdResult<Vec<(CanonicalAddr, u128)>>>()?;
+        let distributed: u128 = shares.iter().map(|(_, share)| share).sum();
+        shares[0].1 += amount - distributed;
+        Ok(shares)
+    }
+
+    // Index of the session open for check-in, None if event has no sessions
+    pub fn current_session(&self, now: u64) -> StdResult<Option<usize>> {
+        if self.sessions.is_empty() {
+            return Ok(None);
+        }
+        match self.sessions.iter().position(|session| {
+            now >= session.start.saturating_sub(self.checkin_window)
+                && now <= session.end.saturating_add(self.checkin_window)
+        }) {
+            Some(index) => Ok(Some(index)),
+            None => Err(StdError::generic_err("No session is open for check-in")),
+        }
+    }
+
+    pub fn check_checkin_open(&self, now: u64) -> StdResult<()> {
+        if self.cancelled {
+            return Err(StdError::generic_err("Event has been cancelled"));
+        }
+        if !self.sessions.is_empty() {
+            return self.current_session(now).map(|_| ());
+        }
+        let start = match self.start_time {
+            Some(start) => start,
+            None => return Ok(())
+        };
+        let end = self.end_time.unwrap_or(start);
+        if now < start.saturating_sub(self.checkin_window) {
+            return Err(StdError::generic_err("Check-in has not opened yet"));
+        }
+        if now > end.saturating_add(self.checkin_window) {
+            return Err(StdError::generic_err("Check-in has closed"));
+        }
+        Ok(())
+    }
+
+    pub fn set_seats(&mut self, seats: Vec<String>) -> StdResult<()> {
+        for (i, seat) in seats.iter().enumerate() {
+            if seats[..i].contains(seat) {
+                return Err(StdError::generic_err(format!("Duplicate seat: {}", seat)));
+            }
+        }
+        self.seats = Some(seats);
+        Ok(())
+    }
+
+    // Seat map of the event, None for general admission
+    pub fn get_seats(&self) -> Option<&Vec<String>> {
+        self.seats.as_ref()
+    }
+
+    pub fn get_max_per_guest(&self) -> u128 {
+        self.max_per_guest
+    }
+
+    pub fn set_max_per_guest(&mut self, max_per_guest: u128) -> StdResult<()> {
+        if max_per_guest == 0 {
+            return Err(StdError::generic_err("Per guest ticket limit must be at least 1"));
+        }
+        self.max_per_guest = max_per_guest;
+        Ok(())
+    }
+
+    // Resale price cap in basis points of face value
+    pub fn get_max_resale_bps(&self) -> u32 {
+        self.max_resale_bps
+    }
+
+    pub fn set_max_resale_bps(&mut self, max_resale_bps: u32) {
+        self.max_resale_bps = max_resale_bps;
+    }
+
+    // Organiser's cut of resale proceeds in basis points
+    pub fn get_royalty_bps(&self) -> u32 {
+        self.royalty_bps
+    }
+
+    pub fn set_royalty_bps(&mut self, royalty_bps: u32) -> StdResult<()> {
+        if royalty_bps > 10_000 {
+            return Err(StdError::generic_err("Royalty cannot exceed 10000 basis points"));
+        }
+        self.royalty_bps = royalty_bps;
+        Ok(())
+    }
+
+    // Make the event invite-only, with invites signed by the given secp256k1 public key
+    pub fn set_invite_key(&mut self, invite_key: Vec<u8>) -> StdResult<()> {
+        if invite_key.len() != 33 && invite_key.len() != 65 {
+            return Err(StdError::generic_err("Invite key must be a 33 or 65 byte secp256k1 public key"));
+        }
+        self.invite_key = Some(invite_key);
+        Ok(())
+    }
+
+    pub fn get_invite_key(&self) -> Option<&[u8]> {
+        self.invite_key.as_deref()
+    }
+
+    // Sale proceeds held until the event completes
+    pub fn get_escrow(&self) -> u128 {
+        self.escrow
+    }
+
+    pub fn add_escrow(&mut self, amount: u128) {
+        self.escrow += amount;
+    }
+
+    // Take up to amount from escrow, returning the amount taken
+    pub fn take_escrow(&mut self, amount: u128) -> u128 {
+        let taken = amount.min(self.escrow);
+        self.escrow -= taken;
+        taken
+    }
+
+    // Ticket sales less refunds, excluding tips
+    pub fn get_revenue(&self) -> u128 {
+        self.revenue
+    }
+
+    pub fn add_revenue(&mut self, amount: u128) {
+        self.revenue += amount;
+    }
+
+    pub fn remove_revenue(&mut self, amount: u128) {
+        self.revenue = self.revenue.saturating_sub(amount);
+    }
+
+    // Tips guests paid the organiser on top of ticket prices
+    pub fn get_tips(&self) -> u128 {
+        self.tips
+    }
+
+    pub fn add_tip(&mut self, amount: u128) {
+        self.tips += amount;
+    }
+
+    // Set seconds after the event ends before escrowed proceeds are released
+    pub fn set_payout_delay(&mut self, payout_delay: u64) {
+        self.payout_delay = payout_delay;
+    }
+
+    pub fn get_payout_delay(&self) -> u64 {
+        self.payout_delay
+    }
+
+    // Mark an event without scheduled times as completed, which it otherwise never is
+    pub fn complete(&mut self, now: u64) -> StdResult<()> {
+        if self.end_time.or(self.start_time).is_some() {
+            return Err(StdError::generic_err("Event completes at its scheduled time"));
+        }
+        if self.completed_at.is_some() {
+            return Err(StdError::generic_err("Event has already completed"));
+        }
+        self.completed_at = Some(now);
+        Ok(())
+    }
+
+    // Time the event completes: its end, its start if it has no end, or when the organiser
+    // completed it if it has neither
+    pub fn get_completion_time(&self) -> Option<u64> {
+        self.end_time.or(self.start_time).or(self.completed_at)
+    }
+
+    // Seconds after the event completes during which disputes may be opened, the events payout
+    // delay but at least the contract's dispute window
+    pub fn get_dispute_window(&self, min_window: u64) -> u64 {
+        self.payout_delay.max(min_window)
+    }
+
+    // Check escrowed proceeds can be released, once the event has completed without open disputes
+    pub fn check_payout_open(&self, now: u64, min_window: u64) -> StdResult<()> {
+        if self.disputed {
+            return Err(StdError::generic_err("Proceeds are frozen while disputes are open"));
+        }
+        let end = match self.get_completion_time() {
+            Some(end) => end,
+            None => return Err(StdError::generic_err("Proceeds are held in escrow until the event completes")),
+        };
+        if now < end.saturating_add(self.get_dispute_window(min_window)) {
+            return Err(StdError::generic_err("Proceeds are held in escrow until the event completes"));
+        }
+        Ok(())
+    }
+
+    // Check disputes can be opened, after the event ends and before proceeds are released
+    pub fn check_dispute_open(&self, now: u64, min_window: u64) -> StdResult<()> {
+        let end = match self.get_completion_time() {
+            Some(end) => end,
+            None => return Err(StdError::generic_err("Disputes can only be opened after the event")),
+        };
+        if now < end {
+            return Err(StdError::generic_err("Disputes can only be opened after the event"));
+        }
+        if now >= end.saturating_add(self.get_dispute_window(min_window)) {
+            return Err(StdError::generic_err("Dispute window has closed"));
+        }
+        Ok(())
+    }
+
+    pub fn is_disputed(&self) -> bool {
+        self.disputed
+    }
+
+    pub fn set_disputed(&mut self, disputed: bool) {
+        self.disputed = disputed;
+    }
+
+    pub fn is_insured(&self) -> bool {
+        self.insured
+    }
+
+    pub fn set_insured(&mut self, insured: bool) {
+        self.insured = insured;
+    }
+
+    // Premium charged on a ticket of the given price, nothing if the event is not insured. Split
+    // so no price overflows
+    pub fn insurance_premium(&self, price: u128) -> u128 {
+        match self.insured {
+            true => price / 10_000 * INSURANCE_PREMIUM_BPS + price % 10_000 * INSURANCE_PREMIUM_BPS / 10_000,
+            false => 0
+        }
+    }
+
+    // Seed the event's ticket seeds are derived from
+    pub fn get_seed(&self) -> &[u8; 32] {
+        &self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: [u8; 32]) {
+        self.seed = seed;
+    }
+
+    // Whether check-in also requires the guest to confirm from their address
+    pub fn is_two_factor(&self) -> bool {
+        self.two_factor
+    }
+
+    pub fn set_two_factor(&mut self, two_factor: bool) {
+        self.two_factor = two_factor;
+    }
+
+    // Whether prices are in USD cents, converted to sEVNT at the oracle rate when tickets are bought
+    pub fn is_stable_price(&self) -> bool {
+        self.stable_price
+    }
+
+    pub fn set_stable_price(&mut self, stable_price: bool) {
+        self.stable_price = stable_price;
+    }
+
+    // Whether organisers can see guests' addresses in the attendee list
+    pub fn is_share_guests(&self) -> bool {
+        self.share_guests
+    }
+
+    pub fn set_share_guests(&mut self, share_guests: bool) {
+        self.share_guests = share_guests;
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    pub fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    // Check ticket sales are open, allowing allowlisted guests in during the presale
+    pub fn check_sale_open(&self, now: u64, allowlisted: bool) -> StdResult<()> {
+        if self.cancelled {
+            return Err(StdError::generic_err("Event has been cancelled"));
+        }
+        if self.completed_at.is_some() {
+            return Err(StdError::generic_err("Event has completed"));
+        }
+        if self.paused {
+            return Err(StdError::generic_err("Ticket sales are paused"));
+        }
+        if let Some(start) = self.sale_start {
+            if now < start {
+                if !matches!(self.presale_start, Some(presale_start) if now >= presale_start) {
+                    return Err(StdError::generic_err("Ticket sales have not started yet"));
+                }
+                if !allowlisted {
+                    return Err(StdError::generic_err("Only allowlisted guests may buy during the presale"));
+                }
+            }
+        }
+        if let Some(end) = self.sale_end {
+            if now >= end {
+                return Err(StdError::generic_err("Ticket sales have ended"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_id(&self) -> u128 {
+        self.id
+    }
+
+    pub fn get_organiser(&self) -> &CanonicalAddr {
+        &self.organiser
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_venue(&self) -> &str {
+        &self.venue
+    }
+
+    pub fn get_tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    // Set the event's public details, storing tags lowercase and once each
+    pub fn set_details(&mut self, name: String, venue: String, tags: Vec<String>) -> StdResult<()> {
+        if name.len() > MAX_NAME_LEN {
+            return Err(StdError::generic_err(format!("Event name cannot exceed {} bytes", MAX_NAME_LEN)));
+        }
+        if venue.len() > MAX_VENUE_LEN {
+            return Err(StdError::generic_err(format!("Event venue cannot exceed {} bytes", MAX_VENUE_LEN)));
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(StdError::generic_err(format!("Events cannot have more than {} tags", MAX_TAGS)));
+        }
+        let mut tags: Vec<String> = tags.into_iter().map(|tag| tag.to_lowercase()).collect();
+        if tags.iter().any(|tag| tag.is_empty() || tag.len() > MAX_TAG_LEN) {
+            return Err(StdError::generic_err(format!("Tags must be between 1 and {} bytes", MAX_TAG_LEN)));
+        }
+        tags.sort_unstable();
+        tags.dedup();
+        self.name = name;
+        self.venue = venue;
+        self.tags = tags;
+        Ok(())
+    }
+
+    // Check if the name or venue contains the text and the event carries the tag, ignoring case
+    pub fn matches_search(&self, text: Option<&str>, tag: Option<&str>) -> bool {
+        let text_matches = match text {
+            Some(text) => {
+                let text = text.to_lowercase();
+                self.name.to_lowercase().contains(&text) || self.venue.to_lowercase().contains(&text)
+            }
+            None => true,
+        };
+        let tag_matches = match tag {
+            Some(tag) => self.tags.contains(&tag.to_lowercase()),
+            None => true,
+        };
+        text_matches && tag_matches
+    }
+
+    pub fn get_co_organisers(&self) -> &Vec<CanonicalAddr> {
+        &self.co_organisers
+    }
+
+    pub fn add_co_organiser(&mut self, address: CanonicalAddr) -> StdResult<()> {
+        if self.can_manage(&address) {
+            return Err(StdError::generic_err("Address already manages this event"));
+        }
+        self.co_organisers.push(address);
+        Ok(())
+    }
+
+    pub fn remove_co_organiser(&mut self, address: &CanonicalAddr) -> StdResult<()> {
+        if !self.co_organisers.contains(address) {
+            return Err(StdError::generic_err("Address is not a co-organiser of this event"));
+        }
+        self.co_organisers.retain(|co_organiser| co_organiser != address);
+        Ok(())
+    }
+
+    // Check if address is the organiser or a co-organiser
+    pub fn can_manage(&self, address: &CanonicalAddr) -> bool {
+        self.organiser == *address || self.co_organisers.contains(address)
+    }
+
+    pub fn get_verifiers(&self) -> &Vec<CanonicalAddr> {
+        &self.verifiers
+    }
+
+    pub fn add_verifier(&mut self, address: CanonicalAddr) -> StdResult<()> {
+        if self.can_verify(&address) {
+            return Err(StdError::generic_err("Address can already verify tickets for this event"));
+        }
+        self.verifiers.push(address);
+        Ok(())
+    }
+
+    pub fn remove_verifier(&mut self, address: &CanonicalAddr) -> StdResult<()> {
+        if !self.verifiers.contains(address) {
+            return Err(StdError::generic_err("Address is not a verifier of this event"));
+        }
+        self.verifiers.retain(|verifier| verifier != address);
+        Ok(())
+    }
+
+    // Check if address may verify tickets, as an organiser or a verifier
+    pub fn can_verify(&self, address: &CanonicalAddr) -> bool {
+        self.can_manage(address) || self.verifiers.contains(address)
+    }
+
+    // Price of the default (first) tier
+    pub fn get_price(&self) -> u128 {
+        self.tiers[0].price
+    }
+
+    pub fn get_max_tickets(&self) -> u128 {
+        self.tiers.iter().map(|tier| tier.max_tickets).sum()
+    }
+
+    pub fn get_tickets_sold(&self) -> u128 {
+        self.tiers.iter().map(|tier| tier.tickets_sold).sum()
+    }
+
+    pub fn set_max_tickets(&mut self, tier: usize, max_tickets: u128) -> StdResult<()> {
+        let tier = &mut self.tiers[tier];
+        if max_tickets < tier.tickets_sold {
+            return Err(StdError::generic_err(format!(
+                "Capacity cannot be lower than tickets sold: sold={}, requested={}",
+                tier.tickets_sold, max_tickets
+            )));
+        }
+        tier.max_tickets = max_tickets;
+        Ok(())
+    }
+
+    pub fn get_tickets_left(&self) -> u128 {
+        self.tiers.iter().map(|tier| tier.get_tickets_left()).sum()
+    }
+
+    pub fn is_sold_out(&self) -> bool {
+        self.tiers.iter().all(|tier| tier.is_sold_out())
+    }
+
+    pub fn ticket_sold(& mut self, tier: usize) {
+        self.tiers[tier].tickets_sold += 1;
+    }
+
+    pub fn ticket_refunded(&mut self, tier: usize) {
+        self.tiers[tier].tickets_sold -= 1;
+    }
+
+}
+
+// Struct to handle interaction with events
+pub struct Events<'a> {
+    storage: PrefixedStorage<'a>,
+}
+
+impl<'a> Events<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_EVENTS),
+        }
+    }
+
+    // Store event
+    pub fn store_event(& mut self, event_id: u128, event: &Event) -> StdResult<()> {
+        self.storage.set(&event_id.to_be_bytes(), &serialize(event)?);
+        Ok(())
+    }
+
+    // Try load an event
+    pub fn may_load_event(&self, event_id: u128) -> StdResult<Option<Event>> {
+        let id_bytes = event_id.to_be_bytes();
+        match self.storage.get(&id_bytes) {
+            Some(event_bytes) => deserialize(&event_bytes).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    // Load an event that must exist, such as one referenced by another stored entry
+    pub fn load_event(&self, event_id: u128) -> StdResult<Event> {
+        match self.may_load_event(event_id)? {
+            Some(event) => Ok(event),
+            None => Err(StdError::generic_err(format!("Event {} does not exist", event_id))),
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with events 
+pub struct ReadonlyEvents<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyEvents<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_EVENTS)
+        }
+    }
+
+    // Try load an event
+    pub fn may_load_event(&self, event_id: u128) -> StdResult<Option<Event>> {
+        let id_bytes = event_id.to_be_bytes();
+        match self.storage.get(&id_bytes) {
+            Some(event_bytes) => deserialize(&event_bytes).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    // Load an event that must exist, such as one referenced by another stored entry
+    pub fn load_event(&self, event_id: u128) -> StdResult<Event> {
+        match self.may_load_event(event_id)? {
+            Some(event) => Ok(event),
+            None => Err(StdError::generic_err(format!("Event {} does not exist", event_id))),
+        }
+    }
+}
+
+// Public key a ticket's secret is encrypted to
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct GuestKey {
+    key_type: u8,
+    key: Vec<u8>
+}
+
+impl GuestKey {
+    // Decode a hex or base64 public key of the given type, inferring secp256k1 or x25519 from its length
+    pub fn parse(pk: &str, key_type: Option<u8>) -> StdResult<Self> {
+        let key = match hex::decode(pk) {
+            Ok(key) => key,
+            Err(_) => match Binary::from_base64(pk) {
+                Ok(key) => key.to_vec(),
+                Err(_) => return Err(StdError::generic_err("Public key is not valid hex or base64")),
+            },
+        };
+        let key_type = match (key_type, key.len()) {
+            (Some(key_type), _) => key_type,
+            (None, 32) => KEY_X25519,
+            (None, 33) | (None, 65) => KEY_SECP256K1,
+            (None, _) => return Err(StdError::generic_err("Public key has an unrecognised length")),
+        };
+
+        // Ensure key is a valid point for its type
+        let valid = match key_type {
+            KEY_X25519 => key.len() == 32,
+            KEY_ED25519 => match CompressedEdwardsY::from_slice(&key) {
+                Ok(point) => point.decompress().is_some(),
+                Err(_) => false,
+            },
+            KEY_SECP256K1 => k256::PublicKey::from_sec1_bytes(&key).is_ok(),
+            _ => false,
+        };
+        if !valid {
+            return Err(StdError::generic_err("Public key is not valid for its type"));
+        }
+        Ok(GuestKey { key_type, key })
+    }
+
+    pub fn get_key_type(&self) -> u8 {
+        self.key_type
+    }
+
+    pub fn get_key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+// Remainder of a reserved ticket's price, paid in installments of amount due every interval
+// seconds from next_due
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Installments {
+    down_payment: u128,
+    unpaid: u128,
+    amount: u128,
+    next_due: u64,
+    interval: u64
+}
+
+impl Installments {
+    pub fn get_down_payment(&self) -> u128 {
+        self.down_payment
+    }
+
+    pub fn get_unpaid(&self) -> u128 {
+        self.unpaid
+    }
+
+    // Amount of the next installment, the last may be smaller than the rest
+    pub fn get_next_amount(&self) -> u128 {
+        self.amount.min(self.unpaid)
+    }
+
+    pub fn get_next_due(&self) -> u64 {
+        self.next_due
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Ticket {
+    id: u128,
+    guest: CanonicalAddr,
+    event_id: u128,
+    state: u8,
+    secret: u64, 
+    pk: GuestKey,
+    tier: String,
+    seat: Option<String>,
+    price: u128,
+    sessions_used: Vec<usize>,
+    challenge_height: u64,
+    challenge_time: u64,
+    challenges: u32,
+    failed_attempts: u32,
+    locked_until: u64,
+    last_signed_challenge: Vec<u8>,
+    code_seed: [u8; 32],
+    nonce: u64,
+    confirmed_at: u64,
+    offline_code: u64,
+    seed: [u8; 32],
+    checkin_code: Option<u32>,
+    checkin_requested_at: u64,
+    installments: Option<Installments>
+}
+
+impl Ticket {
+    pub fn new(id: u128, event_id: u128, guest: CanonicalAddr, pk: GuestKey, tier: String, price: u128) -> Self {
+        Ticket {
+            id, 
+            event_id, 
+            guest,
+            state: TICKET_VALID,
+            secret: 0,
+            pk,
+            tier,
+            seat: None,
+            price,
+            sessions_used: vec![],
+            challenge_height: 0,
+            challenge_time: 0,
+            challenges: 0,
+            failed_attempts: 0,
+            locked_until: 0,
+            last_signed_challenge: vec![],
+            code_seed: [0; 32],
+            nonce: 0,
+            confirmed_at: 0,
+            offline_code: 0,
+            seed: [0; 32],
+            checkin_code: None,
+            checkin_requested_at: 0,
+            installments: None
+        }
+    }
+
+    // Sessions of a multi-day event the ticket has been checked in to
+    pub fn get_sessions_used(&self) -> &Vec<usize> {
+        &self.sessions_used
+    }
+
+    pub fn get_seat(&self) -> Option<&str> {
+        self.seat.as_deref()
+    }
+
+    pub fn set_seat(&mut self, seat: Option<String>) {
+        self.seat = seat;
+    }
+
+    pub fn get_id(&self) -> u128 {
+        self.id
+    }
+    
+    pub fn get_event_id(&self) -> u128 {
+        self.event_id
+    }
+
+    pub fn get_guest(&self) -> &CanonicalAddr {
+        &self.guest
+    }
+
+    pub fn get_state(&self) -> u8 {
+        self.state
+    }
+
+    pub fn get_pk(&self) -> &GuestKey {
+        &self.pk
+    }
+
+    pub fn get_tier(&self) -> &str {
+        &self.tier
+    }
+
+    // Amount paid for the ticket, which is what it refunds
+    pub fn get_price(&self) -> u128 {
+        self.price
+    }
+
+    // Reassign ticket to a new guest, discarding everything the previous guest could know
+    pub fn transfer(&mut self, guest: CanonicalAddr, pk: GuestKey, code_seed: [u8; 32]) {
+        self.guest = guest;
+        self.pk = pk;
+        self.secret = 0;
+        self.nonce = 0;
+        self.offline_code = 0;
+        self.checkin_code = None;
+        self.code_seed = code_seed;
+        self.failed_attempts = 0;
+        self.last_signed_challenge = vec![];
+    }
+
+    pub fn revoke(&mut self) {
+        self.secret = 0;
+        self.nonce = 0;
+        self.offline_code = 0;
+        self.checkin_code = None;
+        self.state = TICKET_REVOKED;
+    }
+
+    pub fn refund(&mut self) {
+        self.secret = 0;
+        self.nonce = 0;
+        self.offline_code = 0;
+        self.checkin_code = None;
+        self.state = TICKET_REFUNDED;
+    }
+
+    // Reserve the ticket under an installment plan, so its price only counts the down payment
+    // until the rest is paid and it cannot be verified before then
+    pub fn reserve(&mut self, plan: &InstallmentPlan, now: u64) -> StdResult<()> {
+        let down_payment = plan.down_payment(self.price)?;
+        let unpaid = self.price - down_payment;
+        if unpaid == 0 {
+            return Ok(());
+        }
+        let installments = plan.get_installments() as u128;
+        self.price = down_payment;
+        self.installments = Some(Installments {
+            down_payment,
+            unpaid,
+            amount: unpaid.div_ceil(installments),
+            next_due: now.saturating_add(plan.get_interval()),
+            interval: plan.get_interval(),
+        });
+        self.state = TICKET_RESERVED;
+        Ok(())
+    }
+
+    // Installments still to be paid on a reserved ticket
+    pub fn get_installments(&self) -> Option<&Installments> {
+        self.installments.as_ref()
+    }
+
+    // Pay the next installment, returning its amount. The ticket becomes valid once fully paid
+    pub fn pay_installment(&mut self) -> StdResult<u128> {
+        let installments = match self.installments.as_mut() {
+            Some(installments) => installments,
+            None => return Err(StdError::generic_err("Ticket has no installments due")),
+        };
+        let amount = installments.get_next_amount();
+        installments.unpaid -= amount;
+        installments.next_due = installments.next_due.saturating_add(installments.interval);
+        self.price += amount;
+        if installments.unpaid == 0 {
+            self.installments = None;
+            self.state = TICKET_VALID;
+        }
+        Ok(amount)
+    }
+
+    // Whether an installment was not paid by its due date
+    pub fn is_overdue(&self, now: u64) -> bool {
+        matches!(&self.installments, Some(installments) if now > installments.next_due)
+    }
+
+    // Begin validation with a freshly generated secret and nonce, replacing any previous
+    // challenge and recording when it was issued
+    pub fn start_validation(&mut self, secret: u64, nonce: u64, height: u64, time: u64) -> u64 {
+        self.state = TICKET_VALIDATING;
+        self.secret = secret;
+        self.nonce = nonce;
+        self.confirmed_at = 0;
+        self.challenge_height = height;
+        self.challenge_time = time;
+        self.challenges += 1;
+        self.secret
+    }
+
+    // Record a wrong secret submission, locking the ticket for a cooldown after too many.
+    // Returns the number of failed attempts since the last lockout
+    pub fn record_failed_attempt(&mut self, now: u64) -> u32 {
+        self.failed_attempts += 1;
+        let failed_attempts = self.failed_attempts;
+        if self.failed_attempts >= MAX_VERIFY_ATTEMPTS {
+            self.failed_attempts = 0;
+            self.locked_until = now.saturating_add(VERIFY_LOCKOUT);
+        }
+        failed_attempts
+    }
+
+    // Ensure ticket is not locked out after failed secret submissions
+    pub fn check_not_locked(&self, now: u64) -> StdResult<()> {
+        if now < self.locked_until {
+            return Err(StdError::generic_err(format!(
+                "Ticket is locked after too many failed verification attempts, retry in {} seconds",
+                self.locked_until - now
+            )));
+        }
+        Ok(())
+    }
+
+    // Seed the ticket's challenge secrets are derived from
+    pub fn get_seed(&self) -> &[u8; 32] {
+        &self.seed
+    }
+
+    pub fn set_seed(&mut self, seed: [u8; 32]) {
+        self.seed = seed;
+    }
+
+    pub fn get_code_seed(&self) -> &[u8; 32] {
+        &self.code_seed
+    }
+
+    pub fn set_code_seed(&mut self, code_seed: [u8; 32]) {
+        self.code_seed = code_seed;
+    }
+
+    // Rotating code for the given time window
+    pub fn code_at(&self, window: u64) -> u32 {
+        let mut hasher = Sha256::new_with_prefix(self.code_seed);
+        hasher.update(window.to_be_bytes());
+        let digest = hasher.finalize();
+        u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) % 10u32.pow(CODE_DIGITS)
+    }
+
+    pub fn get_nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    // Check a nonce against the current challenge's, which is consumed on check-in
+    pub fn matches_nonce(&self, nonce: u64) -> bool {
+        self.nonce != 0 && self.nonce == nonce
+    }
+
+    pub fn matches_secret(&self, secret: u64) -> bool {
+        self.secret == secret
+    }
+
+    // Number of verification challenges issued for this ticket
+    pub fn get_challenges(&self) -> u32 {
+        self.challenges
+    }
+
+    // Block height and time the current verification challenge was issued
+    pub fn get_challenge_height(&self) -> u64 {
+        self.challenge_height
+    }
+
+    pub fn get_challenge_time(&self) -> u64 {
+        self.challenge_time
+    }
+
+    // Check if a verification challenge is older than the ttl (seconds)
+    pub fn is_challenge_expired(&self, now: u64, ttl: u64) -> bool {
+        self.state == TICKET_VALIDATING && now >= self.challenge_time.saturating_add(ttl)
+    }
+
+    // One-time code for checking in while offline, zero if none has been issued
+    pub fn get_offline_code(&self) -> u64 {
+        self.offline_code
+    }
+
+    pub fn set_offline_code(&mut self, offline_code: u64) {
+        self.offline_code = offline_code;
+    }
+
+    // Check in with an offline code, consuming it, only while the ticket can still be used
+    pub fn try_verify_offline(&mut self, code: u64) -> StdResult<()> {
+        if self.state != TICKET_VALID && self.state != TICKET_VALIDATING {
+            return Err(StdError::generic_err("Ticket cannot be checked in"));
+        }
+        if self.offline_code == 0 || self.offline_code != code {
+            return Err(StdError::generic_err("Offline code does not match"));
+        }
+        self.offline_code = 0;
+        self.check_in(None, 0)
+    }
+
+    // Record a guest-initiated check-in awaiting the organiser's acknowledgement, replacing
+    // any earlier one
+    pub fn request_check_in(&mut self, code: u32, now: u64) {
+        self.checkin_code = Some(code);
+        self.checkin_requested_at = now;
+    }
+
+    // Whether a guest-initiated check-in is pending and has not expired after the ttl (seconds)
+    pub fn has_pending_check_in(&self, now: u64, ttl: u64) -> bool {
+        self.checkin_code.is_some() && now < self.checkin_requested_at.saturating_add(ttl)
+    }
+
+    pub fn matches_checkin_code(&self, code: u32) -> bool {
+        self.checkin_code == Some(code)
+    }
+
+    // Check in on the organiser's acknowledgement of a guest-initiated check-in
+    pub fn try_acknowledge_check_in(&mut self, session: Option<usize>, num_sessions: usize) -> StdResult<()> {
+        if self.checkin_code.is_none() {
+            return Err(StdError::generic_err("Guest has not requested check-in"));
+        }
+        self.check_in(session, num_sessions)
+    }
+
+    // Record the guest confirming the current challenge from their address
+    pub fn confirm(&mut self, now: u64) -> StdResult<()> {
+        if self.state != TICKET_VALIDATING {
+            return Err(StdError::generic_err("Ticket is not being validated"));
+        }
+        self.confirmed_at = now;
+        Ok(())
+    }
+
+    // Whether the guest confirmed the current challenge recently enough
+    pub fn is_confirmed(&self, now: u64) -> bool {
+        self.confirmed_at != 0
+            && self.confirmed_at >= self.challenge_time
+            && now < self.confirmed_at.saturating_add(TWO_FACTOR_WINDOW)
+    }
+
+    pub fn get_secret(&self) -> u64 {
+        self.secret
+    }
+
+    pub fn try_verify(&mut self, secret: u64) -> StdResult<()> {
+        self.check_secret(secret)?;
+        self.check_in(None, 0)
+    }
+
+    // Verify for one session of a multi-day event, only using up the ticket after the last session
+    pub fn try_verify_session(&mut self, secret: u64, session: usize, num_sessions: usize) -> StdResult<()> {
+        if self.sessions_used.contains(&session) {
+            return Err(StdError::generic_err("Ticket has already been used for this session"));
+        }
+        self.check_secret(secret)?;
+        self.check_in(Some(session), num_sessions)
+    }
+
+    // Verify with the guest's signature over the current challenge's nonce, consuming it
+    pub fn try_verify_nonce_signed(&mut self, session: Option<usize>, num_sessions: usize) -> StdResult<()> {
+        if self.state != TICKET_VALIDATING || self.nonce == 0 {
+            return Err(StdError::generic_err("Validation of ticket not initiated yet"));
+        }
+        self.check_in(session, num_sessions)
+    }
+
+    // Verify with a challenge the guest has signed, which cannot be reused
+    pub fn try_verify_signed(&mut self, challenge: &[u8], session: Option<usize>, num_sessions: usize) -> StdResult<()> {
+        if self.last_signed_challenge == challenge {
+            return Err(StdError::generic_err("Challenge has already been used for this ticket"));
+        }
+        self.last_signed_challenge = challenge.to_vec();
+        self.check_in(session, num_sessions)
+    }
+
+    // Mark ticket checked in, for one session of a multi-day event or for good
+    fn check_in(&mut self, session: Option<usize>, num_sessions: usize) -> StdResult<()> {
+        self.failed_attempts = 0;
+        self.nonce = 0;
+        self.offline_code = 0;
+        self.confirmed_at = 0;
+        self.checkin_code = None;
+        match session {
+            Some(session) => {
+                if self.sessions_used.contains(&session) {
+                    return Err(StdError::generic_err("Ticket has already been used for this session"));
+                }
+                self.sessions_used.push(session);
+                if self.sessions_used.len() >= num_sessions {
+                    self.secret = 0;
+                    self.state = TICKET_USED;
+                } else {
+                    self.state = TICKET_VALID;
+                }
+            }
+            None => {
+                self.secret = 0;
+                self.state = TICKET_USED;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_secret(&self, secret: u64) -> StdResult<()> {
+        if self.secret != secret {
+            return Err(StdError::generic_err("Secret does not match"));
+        }
+        Ok(())
+    }
+}
+
+// Struct to handle interaction with tickets
+pub struct Tickets<'a> {
+    storage: PrefixedStorage<'a>,
+}
+
+impl<'a> Tickets<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_TICKETS),
+        }
+    }
+
+    // Store ticket
+    pub fn store_ticket(& mut self, ticket_id: u128, ticket: &Ticket) -> StdResult<()> {
+        self.storage.set(&ticket_id.to_be_bytes(), &serialize(ticket)?);
+        Ok(())
+    }
+
+    // Try load a ticket
+    pub fn may_load_ticket(&self, ticket_id: u128) -> StdResult<Option<Ticket>> {
+        let id_bytes = ticket_id.to_be_bytes();
+        match self.storage.get(&id_bytes) {
+            Some(ticket_bytes) => deserialize(&ticket_bytes).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    // Load a ticket that must exist, such as one referenced by another stored entry
+    pub fn load_ticket(&self, ticket_id: u128) -> StdResult<Ticket> {
+        match self.may_load_ticket(ticket_id)? {
+            Some(ticket) => Ok(ticket),
+            None => Err(StdError::generic_err(format!("Ticket {} does not exist", ticket_id))),
+        }
+    }
+
+    // Delete a ticket?
+}
+
+// Struct to handle READONLY interaction with events 
+pub struct ReadonlyTickets<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyTickets<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_TICKETS)
+        }
+    }
+
+    // Try load a ticket
+    pub fn may_load_ticket(&self, ticket_id: u128) -> StdResult<Option<Ticket>> {
+        let id_bytes = ticket_id.to_be_bytes();
+        match self.storage.get(&id_bytes) {
+            Some(ticket_bytes) => deserialize(&ticket_bytes).map(Some),
+            None => Ok(None)
+        }
+    }
+
+    // Load a ticket that must exist, such as one referenced by another stored entry
+    pub fn load_ticket(&self, ticket_id: u128) -> StdResult<Ticket> {
+        match self.may_load_ticket(ticket_id)? {
+            Some(ticket) => Ok(ticket),
+            None => Err(StdError::generic_err(format!("Ticket {} does not exist", ticket_id))),
+        }
+    }
+}
+
+// Organisers events and guests tickets are append stores, one id per index under the owner
+// alongside the list's length, so adding to a list costs the same however long it is
+
+// Struct to handle interaction with organisers events
+pub struct OrganisersEvents<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> OrganisersEvents<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_ORGANISERS_EVENTS)
+        }
+    }
+
+    // Add an event to the end of an organisers events
+    pub fn add_event(&mut self, organiser: &CanonicalAddr, event_id: u128) -> StdResult<()> {
+        list_push(&mut self.storage, organiser.as_slice(), event_id)?;
+        Ok(())
+    }
+
+    // Remove and return an organisers events stored in the legacy layout, the whole list in one
+    // entry keyed by the address string
+    pub fn take_legacy_events(&mut self, organiser: &CanonicalAddr) -> StdResult<Vec<u128>> {
+        take_legacy_list(&mut self.storage, organiser)
+    }
+}
+
+// Struct to handle READONLY interaction with organisers events
+pub struct ReadonlyOrganisersEvents<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyOrganisersEvents<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ORGANISERS_EVENTS)
+        }
+    }
+
+    // Load an organisers events
+    pub fn load_events(&self, organiser: &CanonicalAddr) -> StdResult<Vec<u128>> {
+        self.iter_events(organiser, 0)?.collect()
+    }
+
+    // Iterate over an organisers events after an id, reading each only as it is reached
+    pub fn iter_events(
+        &self,
+        organiser: &CanonicalAddr,
+        start_after: u128,
+    ) -> StdResult<impl Iterator<Item = StdResult<u128>> + '_> {
+        list_iter_after(&self.storage, organiser.as_slice().to_vec(), start_after)
+    }
+}
+
+// Struct to handle interaction with the tickets minted for each event
+pub struct EventsTickets<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> EventsTickets<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_EVENTS_TICKETS)
+        }
+    }
+
+    // Add a newly minted ticket to its events tickets
+    pub fn add_ticket(&mut self, event_id: u128, ticket_id: u128) -> StdResult<()> {
+        list_push(&mut self.storage, &event_id.to_be_bytes(), ticket_id)?;
+        Ok(())
+    }
+}
+
+// Struct to handle READONLY interaction with the tickets minted for each event
+pub struct ReadonlyEventsTickets<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyEventsTickets<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_EVENTS_TICKETS)
+        }
+    }
+
+    // Iterate over an events tickets after an id, including refunded and revoked tickets
+    pub fn iter_tickets(
+        &self,
+        event_id: u128,
+        start_after: u128,
+    ) -> StdResult<impl Iterator<Item = StdResult<u128>> + '_> {
+        list_iter_after(&self.storage, event_id.to_be_bytes().to_vec(), start_after)
+    }
+}
+
+// Struct to handle interaction with guests tickets, which also stores each ticket's index so it
+// can be removed by moving the last ticket into its place
+pub struct GuestsTickets<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> GuestsTickets<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_GUESTS_TICKETS)
+        }
+    }
+
+    // Add a ticket to a guests tickets
+    pub fn add_ticket(&mut self, guest: &CanonicalAddr, ticket_id: u128) -> StdResult<()> {
+        let owner = guest.as_slice();
+        let index = list_push(&mut self.storage, owner, ticket_id)?;
+        self.storage.set(&list_key(owner, &position_suffix(ticket_id)), &index.to_be_bytes());
+        Ok(())
+    }
+
+    // Remove a ticket from a guests tickets, if they hold it
+    pub fn remove_ticket(&mut self, guest: &CanonicalAddr, ticket_id: u128) -> StdResult<()> {
+        let owner = guest.as_slice();
+        let position_key = list_key(owner, &position_suffix(ticket_id));
+        let index = match self.storage.get(&position_key) {
+            Some(index_bytes) => slice_to_u32(&index_bytes)?,
+            None => return Ok(()),
+        };
+        let last = list_len(&self.storage, owner)? - 1;
+        if index != last {
+            let moved = list_get(&self.storage, owner, last)?;
+            self.storage.set(&list_key(owner, &item_suffix(index)), &moved.to_be_bytes());
+            self.storage.set(&list_key(owner, &position_suffix(moved)), &index.to_be_bytes());
+        }
+        self.storage.remove(&list_key(owner, &item_suffix(last)));
+        self.storage.remove(&position_key);
+        self.storage.set(&list_key(owner, b"len"), &last.to_be_bytes());
+        Ok(())
+    }
+
+    // Load a guests tickets
+    pub fn load_tickets(&self, guest: &CanonicalAddr) -> StdResult<Vec<u128>> {
+        list_load(&self.storage, guest.as_slice())
+    }
+
+    // Remove and return a guests tickets stored in the legacy layout, the whole list in one entry
+    // keyed by the address string
+    pub fn take_legacy_tickets(&mut self, guest: &CanonicalAddr) -> StdResult<Vec<u128>> {
+        take_legacy_list(&mut self.storage, guest)
+    }
+}
+
+// Struct to handle READONLY interaction with guests tickets
+pub struct ReadonlyGuestsTickets<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyGuestsTickets<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_GUESTS_TICKETS)
+        }
+    }
+
+    // Load a guests tickets, in no particular order as removals reorder them
+    pub fn load_tickets(&self, guest: &CanonicalAddr) -> StdResult<Vec<u128>> {
+        list_load(&self.storage, guest.as_slice())
+    }
+
+    // Iterate over a guests tickets, reading each only as it is reached
+    pub fn iter_tickets(&self, guest: &CanonicalAddr) -> StdResult<impl Iterator<Item = StdResult<u128>> + '_> {
+        let owner = guest.as_slice().to_vec();
+        let len = list_len(&self.storage, &owner)?;
+        Ok((0..len).map(move |index| list_get(&self.storage, &owner, index)))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Listing {
+    ticket_id: u128,
+    event_id: u128,
+    seller: CanonicalAddr,
+    price: u128
+}
+
+impl Listing {
+    pub fn new(ticket_id: u128, event_id: u128, seller: CanonicalAddr, price: u128) -> Self {
+        Listing {
+            ticket_id,
+            event_id,
+            seller,
+            price
+        }
+    }
+
+    pub fn get_ticket_id(&self) -> u128 {
+        self.ticket_id
+    }
+
+    pub fn get_event_id(&self) -> u128 {
+        self.event_id
+    }
+
+    pub fn get_seller(&self) -> &CanonicalAddr {
+        &self.seller
+    }
+
+    pub fn get_price(&self) -> u128 {
+        self.price
+    }
+}
+
+// Struct to handle interaction with resale listings
+pub struct Listings<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Listings<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_LISTINGS)
+        }
+    }
+
+    // Store listing
+    pub fn store_listing(&mut self, ticket_id: u128, listing: &Listing) -> StdResult<()> {
+        self.storage.set(&ticket_id.to_be_bytes(), &serialize(listing)?);
+        Ok(())
+    }
+
+    // Remove listing
+    pub fn remove_listing(&mut self, ticket_id: u128) {
+        self.storage.remove(&ticket_id.to_be_bytes());
+    }
+
+    // Try load a listing
+    pub fn may_load_listing(&self, ticket_id: u128) -> StdResult<Option<Listing>> {
+        self.storage
+            .get(&ticket_id.to_be_bytes())
+            .map(|listing_bytes| deserialize(&listing_bytes))
+            .transpose()
+    }
+}
+
+// Struct to handle READONLY interaction with resale listings
+pub struct ReadonlyListings<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyListings<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_LISTINGS)
+        }
+    }
+
+    // Try load a listing
+    pub fn may_load_listing(&self, ticket_id: u128) -> StdResult<Option<Listing>> {
+        self.storage
+            .get(&ticket_id.to_be_bytes())
+            .map(|listing_bytes| deserialize(&listing_bytes))
+            .transpose()
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WaitlistEntry {
+    guest: CanonicalAddr,
+    pk: GuestKey,
+    entropy: [u8; 32]
+}
+
+impl WaitlistEntry {
+    pub fn new(guest: CanonicalAddr, pk: GuestKey, entropy: [u8; 32]) -> Self {
+        WaitlistEntry {
+            guest,
+            pk,
+            entropy
+        }
+    }
+
+    pub fn get_guest(&self) -> &CanonicalAddr {
+        &self.guest
+    }
+
+    pub fn get_pk(&self) -> &GuestKey {
+        &self.pk
+    }
+
+    pub fn get_entropy(&self) -> &[u8; 32] {
+        &self.entropy
+    }
+}
+
+// Struct to handle interaction with event waitlists
+pub struct Waitlists<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Waitlists<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_WAITLISTS)
+        }
+    }
+
+    // Store an events waitlist
+    pub fn store_waitlist(&mut self, event_id: u128, waitlist: &Vec<WaitlistEntry>) -> StdResult<()> {
+        self.storage.set(&event_id.to_be_bytes(), &serialize(waitlist)?);
+        Ok(())
+    }
+
+    // Load an events waitlist, in order
+    pub fn load_waitlist(&self, event_id: u128) -> StdResult<Vec<WaitlistEntry>> {
+        match self.storage.get(&event_id.to_be_bytes()) {
+            Some(waitlist_bytes) => deserialize(&waitlist_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with event waitlists
+pub struct ReadonlyWaitlists<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyWaitlists<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_WAITLISTS)
+        }
+    }
+
+    // Load an events waitlist, in order
+    pub fn load_waitlist(&self, event_id: u128) -> StdResult<Vec<WaitlistEntry>> {
+        match self.storage.get(&event_id.to_be_bytes()) {
+            Some(waitlist_bytes) => deserialize(&waitlist_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Struct to handle interaction with event presale allowlists
+pub struct Allowlists<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Allowlists<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_ALLOWLISTS)
+        }
+    }
+
+    // Store an events allowlist, replacing any existing one
+    pub fn store_allowlist(&mut self, event_id: u128, allowlist: &Vec<CanonicalAddr>) -> StdResult<()> {
+        self.storage.set(&event_id.to_be_bytes(), &serialize(allowlist)?);
+        Ok(())
+    }
+}
+
+// Struct to handle READONLY interaction with event presale allowlists
+pub struct ReadonlyAllowlists<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyAllowlists<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ALLOWLISTS)
+        }
+    }
+
+    // Load an events allowlist
+    pub fn load_allowlist(&self, event_id: u128) -> StdResult<Vec<CanonicalAddr>> {
+        match self.storage.get(&event_id.to_be_bytes()) {
+            Some(allowlist_bytes) => deserialize(&allowlist_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PromoCode {
+    discount_percent: u8,
+    uses_left: u32
+}
+
+impl PromoCode {
+    pub fn new(discount_percent: u8, uses_left: u32) -> Self {
+        PromoCode {
+            discount_percent,
+            uses_left
+        }
+    }
+
+    pub fn get_discount_percent(&self) -> u8 {
+        self.discount_percent
+    }
+
+    pub fn get_uses_left(&self) -> u32 {
+        self.uses_left
+    }
+
+    // Use the code for a number of tickets
+    pub fn redeem(&mut self, quantity: u128) -> StdResult<()> {
+        if (self.uses_left as u128) < quantity {
+            return Err(StdError::generic_err("Promo code has no uses left"));
+        }
+        self.uses_left -= quantity as u32;
+        Ok(())
+    }
+
+    // Discounted price
+    pub fn apply(&self, price: u128) -> u128 {
+        price - price * self.discount_percent as u128 / 100
+    }
+}
+
+// Struct to handle interaction with promo codes
+pub struct PromoCodes<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> PromoCodes<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_PROMO_CODES)
+        }
+    }
+
+    // Store promo code under the hash of the code
+    pub fn store_promo_code(&mut self, event_id: u128, code_hash: &[u8; 32], promo_code: &PromoCode) -> StdResult<()> {
+        self.storage.set(&event_key(event_id, code_hash), &serialize(promo_code)?);
+        Ok(())
+    }
+
+    // Try load a promo code by the hash of the code
+    pub fn may_load_promo_code(&self, event_id: u128, code_hash: &[u8; 32]) -> StdResult<Option<PromoCode>> {
+        self.storage
+            .get(&event_key(event_id, code_hash))
+            .map(|promo_bytes| deserialize(&promo_bytes))
+            .transpose()
+    }
+}
+
+// Struct to handle interaction with taken seats
+pub struct EventSeats<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> EventSeats<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_EVENT_SEATS)
+        }
+    }
+
+    // Assign a seat to a ticket
+    pub fn take_seat(&mut self, event_id: u128, seat: &str, ticket_id: u128) {
+        self.storage.set(&event_key(event_id, seat.as_bytes()), &ticket_id.to_be_bytes());
+    }
+
+    // Release a seat
+    pub fn free_seat(&mut self, event_id: u128, seat: &str) {
+        self.storage.remove(&event_key(event_id, seat.as_bytes()));
+    }
+
+    // Get the ticket holding a seat
+    pub fn may_load_seat(&self, event_id: u128, seat: &str) -> StdResult<Option<u128>> {
+        self.storage.get(&event_key(event_id, seat.as_bytes())).map(|bytes| slice_to_u128(&bytes)).transpose()
+    }
+}
+
+// Struct to handle READONLY interaction with taken seats
+pub struct ReadonlyEventSeats<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyEventSeats<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_EVENT_SEATS)
+        }
+    }
+
+    // Get the ticket holding a seat
+    pub fn may_load_seat(&self, event_id: u128, seat: &str) -> StdResult<Option<u128>> {
+        self.storage.get(&event_key(event_id, seat.as_bytes())).map(|bytes| slice_to_u128(&bytes)).transpose()
+    }
+}
+
+// Struct to handle interaction with guests banned from events
+pub struct BannedGuests<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> BannedGuests<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_BANNED_GUESTS)
+        }
+    }
+
+    // Ban a guest from an event
+    pub fn ban_guest(&mut self, event_id: u128, guest: &CanonicalAddr) {
+        self.storage.set(&event_key(event_id, guest.as_slice()), &[1]);
+    }
+
+    // Lift a guests ban from an event
+    pub fn unban_guest(&mut self, event_id: u128, guest: &CanonicalAddr) {
+        self.storage.remove(&event_key(event_id, guest.as_slice()));
+    }
+}
+
+// Struct to handle READONLY interaction with guests banned from events
+pub struct ReadonlyBannedGuests<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyBannedGuests<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_BANNED_GUESTS)
+        }
+    }
+
+    // Check if a guest is banned from an event
+    pub fn is_banned(&self, event_id: u128, guest: &CanonicalAddr) -> bool {
+        self.storage.get(&event_key(event_id, guest.as_slice())).is_some()
+    }
+}
+
+// Registered organiser and the bond they have staked
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Organiser {
+    bond: u128,
+    slashed: bool
+}
+
+impl Organiser {
+    pub fn new(bond: u128) -> Self {
+        Organiser {
+            bond,
+            slashed: false
+        }
+    }
+
+    pub fn get_bond(&self) -> u128 {
+        self.bond
+    }
+
+    pub fn is_slashed(&self) -> bool {
+        self.slashed
+    }
+
+    // Forfeit the bond, returning the amount slashed
+    pub fn slash(&mut self) -> u128 {
+        let bond = self.bond;
+        self.bond = 0;
+        self.slashed = true;
+        bond
+    }
+}
+
+// Struct to handle interaction with registered organisers
+pub struct Organisers<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Organisers<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_ORGANISERS)
+        }
+    }
+
+    // Store a registered organiser
+    pub fn store_organiser(&mut self, address: &CanonicalAddr, organiser: &Organiser) -> StdResult<()> {
+        self.storage.set(address.as_slice(), &serialize(organiser)?);
+        Ok(())
+    }
+
+    // Remove a registered organiser
+    pub fn remove_organiser(&mut self, address: &CanonicalAddr) {
+        self.storage.remove(address.as_slice());
+    }
+
+    // Get a registered organiser
+    pub fn may_load_organiser(&self, address: &CanonicalAddr) -> StdResult<Option<Organiser>> {
+        self.storage.get(address.as_slice()).map(|bytes| deserialize(&bytes)).transpose()
+    }
+}
+
+// Struct to handle READONLY interaction with registered organisers
+pub struct ReadonlyOrganisers<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyOrganisers<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ORGANISERS)
+        }
+    }
+
+    // Get a registered organiser
+    pub fn may_load_organiser(&self, address: &CanonicalAddr) -> StdResult<Option<Organiser>> {
+        self.storage.get(address.as_slice()).map(|bytes| deserialize(&bytes)).transpose()
+    }
+}
+
+// Organiser's public profile and the address their event payouts are credited to
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OrganiserProfile {
+    display_name: String,
+    contact_uri: String,
+    payout_address: Option<CanonicalAddr>
+}
+
+impl OrganiserProfile {
+    pub fn new(display_name: String, contact_uri: String, payout_address: Option<CanonicalAddr>) -> StdResult<Self> {
+        if display_name.len() > MAX_NAME_LEN {
+            return Err(StdError::generic_err(format!("Display name cannot exceed {} bytes", MAX_NAME_LEN)));
+        }
+        if contact_uri.len() > MAX_CONTACT_URI_LEN {
+            return Err(StdError::generic_err(format!("Contact URI cannot exceed {} bytes", MAX_CONTACT_URI_LEN)));
+        }
+        Ok(OrganiserProfile {
+            display_name,
+            contact_uri,
+            payout_address
+        })
+    }
+
+    pub fn get_display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    pub fn get_contact_uri(&self) -> &str {
+        &self.contact_uri
+    }
+
+    pub fn get_payout_address(&self) -> Option<&CanonicalAddr> {
+        self.payout_address.as_ref()
+    }
+}
+
+// Struct to handle interaction with organiser profiles
+pub struct OrganiserProfiles<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> OrganiserProfiles<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_ORGANISER_PROFILES)
+        }
+    }
+
+    // Store an organiser's profile
+    pub fn store_profile(&mut self, address: &CanonicalAddr, profile: &OrganiserProfile) -> StdResult<()> {
+        self.storage.set(address.as_slice(), &serialize(profile)?);
+        Ok(())
+    }
+}
+
+// Struct to handle READONLY interaction with organiser profiles
+pub struct ReadonlyOrganiserProfiles<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyOrganiserProfiles<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ORGANISER_PROFILES)
+        }
+    }
+
+    // Get an organiser's profile, if they have set one
+    pub fn may_load_profile(&self, address: &CanonicalAddr) -> StdResult<Option<OrganiserProfile>> {
+        self.storage.get(address.as_slice()).map(|bytes| deserialize(&bytes)).transpose()
+    }
+}
+
+// Dispute raised by a ticket holder against an event
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Dispute {
+    ticket_id: u128,
+    reason: String
+}
+
+impl Dispute {
+    pub fn new(ticket_id: u128, reason: String) -> Self {
+        Dispute {
+            ticket_id,
+            reason
+        }
+    }
+
+    pub fn get_ticket_id(&self) -> u128 {
+        self.ticket_id
+    }
+
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+// Struct to handle interaction with open event disputes
+pub struct Disputes<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Disputes<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_DISPUTES)
+        }
+    }
+
+    // Store an events open disputes
+    pub fn store_disputes(&mut self, event_id: u128, disputes: &Vec<Dispute>) -> StdResult<()> {
+        self.storage.set(&event_id.to_be_bytes(), &serialize(disputes)?);
+        Ok(())
+    }
+
+    // Load an events open disputes
+    pub fn load_disputes(&self, event_id: u128) -> StdResult<Vec<Dispute>> {
+        match self.storage.get(&event_id.to_be_bytes()) {
+            Some(disputes_bytes) => deserialize(&disputes_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Struct to handle interaction with events' private metadata
+pub struct PrivateMetadata<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> PrivateMetadata<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_PRIVATE_METADATA)
+        }
+    }
+
+    // Store an events private metadata, removing it if empty
+    pub fn store_metadata(&mut self, event_id: u128, metadata: &str) {
+        if metadata.is_empty() {
+            self.storage.remove(&event_id.to_be_bytes());
+        } else {
+            self.storage.set(&event_id.to_be_bytes(), metadata.as_bytes());
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with events' private metadata
+pub struct ReadonlyPrivateMetadata<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyPrivateMetadata<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_PRIVATE_METADATA)
+        }
+    }
+
+    // Load an events private metadata, if set
+    pub fn may_load_metadata(&self, event_id: u128) -> StdResult<Option<String>> {
+        self.storage
+            .get(&event_id.to_be_bytes())
+            .map(|metadata_bytes| {
+                String::from_utf8(metadata_bytes).map_err(|_| StdError::invalid_utf8("Corrupted private metadata found"))
+            })
+            .transpose()
+    }
+}
+
+// Record of a ticket being checked in, kept for post-event disputes
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CheckIn {
+    ticket_id: u128,
+    event_id: u128,
+    time: u64,
+    verifier: CanonicalAddr
+}
+
+impl CheckIn {
+    pub fn new(ticket_id: u128, event_id: u128, time: u64, verifier: CanonicalAddr) -> Self {
+        CheckIn {
+            ticket_id,
+            event_id,
+            time,
+            verifier
+        }
+    }
+
+    pub fn get_ticket_id(&self) -> u128 {
+        self.ticket_id
+    }
+
+    pub fn get_event_id(&self) -> u128 {
+        self.event_id
+    }
+
+    pub fn get_time(&self) -> u64 {
+        self.time
+    }
+
+    pub fn get_verifier(&self) -> &CanonicalAddr {
+        &self.verifier
+    }
+}
+
+// Struct to handle interaction with tickets' check-in records
+pub struct CheckIns<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> CheckIns<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_CHECK_INS)
+        }
+    }
+
+    // Append a check-in to a tickets records
+    pub fn add_check_in(&mut self, check_in: CheckIn) -> StdResult<()> {
+        let key = check_in.get_ticket_id().to_be_bytes();
+        let mut check_ins: Vec<CheckIn> = match self.storage.get(&key) {
+            Some(check_ins_bytes) => deserialize(&check_ins_bytes)?,
+            None => vec![]
+        };
+        check_ins.push(check_in);
+        self.storage.set(&key, &serialize(&check_ins)?);
+        Ok(())
+    }
+}
+
+// Struct to handle READONLY interaction with tickets' check-in records
+pub struct ReadonlyCheckIns<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyCheckIns<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_CHECK_INS)
+        }
+    }
+
+    // Load a tickets check-ins, in order
+    pub fn load_check_ins(&self, ticket_id: u128) -> StdResult<Vec<CheckIn>> {
+        match self.storage.get(&ticket_id.to_be_bytes()) {
+            Some(check_ins_bytes) => deserialize(&check_ins_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Record of an action taken on a ticket, and the block it was taken in
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TicketRecord {
+    action: u8,
+    height: u64,
+    time: u64
+}
+
+impl TicketRecord {
+    pub fn new(action: u8, height: u64, time: u64) -> Self {
+        TicketRecord {
+            action,
+            height,
+            time
+        }
+    }
+
+    pub fn get_action(&self) -> u8 {
+        self.action
+    }
+
+    pub fn get_height(&self) -> u64 {
+        self.height
+    }
+
+    pub fn get_time(&self) -> u64 {
+        self.time
+    }
+}
+
+// Struct to handle interaction with tickets' histories
+pub struct TicketHistories<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> TicketHistories<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_TICKET_HISTORY)
+        }
+    }
+
+    // Append a record to a tickets history
+    pub fn add_record(&mut self, ticket_id: u128, record: TicketRecord) -> StdResult<()> {
+        let key = ticket_id.to_be_bytes();
+        let mut history: Vec<TicketRecord> = match self.storage.get(&key) {
+            Some(history_bytes) => deserialize(&history_bytes)?,
+            None => vec![]
+        };
+        history.push(record);
+        self.storage.set(&key, &serialize(&history)?);
+        Ok(())
+    }
+}
+
+// Struct to handle READONLY interaction with tickets' histories
+pub struct ReadonlyTicketHistories<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyTicketHistories<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_TICKET_HISTORY)
+        }
+    }
+
+    // Load a tickets history, oldest first
+    pub fn load_history(&self, ticket_id: u128) -> StdResult<Vec<TicketRecord>> {
+        match self.storage.get(&ticket_id.to_be_bytes()) {
+            Some(history_bytes) => deserialize(&history_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Record of proceeds released from an events escrow to its organiser
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Payout {
+    amount: u128,
+    fee: u128,
+    time: u64
+}
+
+impl Payout {
+    pub fn new(amount: u128, fee: u128, time: u64) -> Self {
+        Payout {
+            amount,
+            fee,
+            time
+        }
+    }
+
+    pub fn get_amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn get_fee(&self) -> u128 {
+        self.fee
+    }
+
+    pub fn get_time(&self) -> u64 {
+        self.time
+    }
+}
+
+// Struct to handle interaction with event payouts
+pub struct Payouts<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Payouts<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_PAYOUTS)
+        }
+    }
+
+    // Store an events payouts
+    pub fn store_payouts(&mut self, event_id: u128, payouts: &Vec<Payout>) -> StdResult<()> {
+        self.storage.set(&event_id.to_be_bytes(), &serialize(payouts)?);
+        Ok(())
+    }
+
+    // Load an events payouts, in order
+    pub fn load_payouts(&self, event_id: u128) -> StdResult<Vec<Payout>> {
+        match self.storage.get(&event_id.to_be_bytes()) {
+            Some(payouts_bytes) => deserialize(&payouts_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with event payouts
+pub struct ReadonlyPayouts<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyPayouts<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_PAYOUTS)
+        }
+    }
+
+    // Load an events payouts, in order
+    pub fn load_payouts(&self, event_id: u128) -> StdResult<Vec<Payout>> {
+        match self.storage.get(&event_id.to_be_bytes()) {
+            Some(payouts_bytes) => deserialize(&payouts_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Amount of an owner's sEVNT a spender may transfer, until an optional expiry (seconds)
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Allowance {
+    amount: u128,
+    expiration: Option<u64>
+}
+
+impl Allowance {
+    pub fn new(amount: u128, expiration: Option<u64>) -> Self {
+        Allowance {
+            amount,
+            expiration
+        }
+    }
+
+    pub fn get_amount(&self) -> u128 {
+        self.amount
+    }
+
+    pub fn get_expiration(&self) -> Option<u64> {
+        self.expiration
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        matches!(self.expiration, Some(expiration) if now >= expiration)
+    }
+
+    // Spend from the allowance, failing if it has expired or is insufficient
+    pub fn spend(&mut self, amount: u128, now: u64) -> StdResult<()> {
+        if self.is_expired(now) {
+            return Err(StdError::generic_err("Allowance has expired"));
+        }
+        if self.amount < amount {
+            return Err(StdError::generic_err(format!(
+                "Insufficient allowance: allowance={}, required={}",
+                self.amount, amount
+            )));
+        }
+        self.amount -= amount;
+        Ok(())
+    }
+}
+
+// Struct to handle interaction with allowances, keyed by owner and spender
+pub struct Allowances<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Allowances<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_ALLOWANCES)
+        }
+    }
+
+    // Store an owner's allowance for a spender
+    pub fn store_allowance(&mut self, owner: &CanonicalAddr, spender: &CanonicalAddr, allowance: &Allowance) -> StdResult<()> {
+        self.storage.set(&allowance_key(owner, spender), &serialize(allowance)?);
+        Ok(())
+    }
+
+    // Load an owner's allowance for a spender, empty if none was given
+    pub fn load_allowance(&self, owner: &CanonicalAddr, spender: &CanonicalAddr) -> StdResult<Allowance> {
+        match self.storage.get(&allowance_key(owner, spender)) {
+            Some(allowance_bytes) => deserialize(&allowance_bytes),
+            None => Ok(Allowance::default())
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with allowances
+pub struct ReadonlyAllowances<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyAllowances<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ALLOWANCES)
+        }
+    }
+
+    // Load an owner's allowance for a spender, empty if none was given
+    pub fn load_allowance(&self, owner: &CanonicalAddr, spender: &CanonicalAddr) -> StdResult<Allowance> {
+        match self.storage.get(&allowance_key(owner, spender)) {
+            Some(allowance_bytes) => deserialize(&allowance_bytes),
+            None => Ok(Allowance::default())
+        }
+    }
+}
+
+// Struct to handle interaction with the spenders each owner has given allowances to, and the
+// owners each spender has received allowances from
+pub struct AllowanceIndexes<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> AllowanceIndexes<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_ALLOWANCE_INDEXES)
+        }
+    }
+
+    // Index an owner's allowance for a spender if not already indexed
+    pub fn add_allowance(&mut self, owner: &CanonicalAddr, spender: &CanonicalAddr) -> StdResult<()> {
+        let given_key = allowance_index_key(b"given", owner);
+        let mut spenders: Vec<CanonicalAddr> = self.load(&given_key)?;
+        if spenders.contains(spender) {
+            return Ok(());
+        }
+        spenders.push(spender.clone());
+        self.storage.set(&given_key, &serialize(&spenders)?);
+
+        let received_key = allowance_index_key(b"received", spender);
+        let mut owners: Vec<CanonicalAddr> = self.load(&received_key)?;
+        owners.push(owner.clone());
+        self.storage.set(&received_key, &serialize(&owners)?);
+        Ok(())
+    }
+
+    fn load(&self, key: &[u8]) -> StdResult<Vec<CanonicalAddr>> {
+        match self.storage.get(key) {
+            Some(addresses_bytes) => deserialize(&addresses_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with allowance indexes
+pub struct ReadonlyAllowanceIndexes<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyAllowanceIndexes<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ALLOWANCE_INDEXES)
+        }
+    }
+
+    // Load the spenders an owner has given allowances to
+    pub fn load_spenders(&self, owner: &CanonicalAddr) -> StdResult<Vec<CanonicalAddr>> {
+        self.load(&allowance_index_key(b"given", owner))
+    }
+
+    // Load the owners a spender has received allowances from
+    pub fn load_owners(&self, spender: &CanonicalAddr) -> StdResult<Vec<CanonicalAddr>> {
+        self.load(&allowance_index_key(b"received", spender))
+    }
+
+    fn load(&self, key: &[u8]) -> StdResult<Vec<CanonicalAddr>> {
+        match self.storage.get(key) {
+            Some(addresses_bytes) => deserialize(&addresses_bytes),
+            None => Ok(vec![])
+        }
+    }
+}
+
+// Struct to handle interaction with the amount of each native denom backing sEVNT
+pub struct Reserves<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Reserves<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_RESERVES)
+        }
+    }
+
+    // Set amount of a denom held
+    pub fn set_reserve(&mut self, denom: &str, amount: u128) {
+        self.storage.set(denom.as_bytes(), &amount.to_be_bytes());
+    }
+
+    // Read amount of a denom held
+    pub fn read_reserve(&self, denom: &str) -> StdResult<u128> {
+        match self.storage.get(denom.as_bytes()) {
+            Some(amount_bytes) => slice_to_u128(&amount_bytes),
+            None => Ok(0),
+        }
+    }
+}
+
+// Struct to handle READONLY interaction with the amount of each native denom backing sEVNT
+pub struct ReadonlyReserves<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyReserves<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_RESERVES)
+        }
+    }
+
+    // Read amount of a denom held
+    pub fn read_reserve(&self, denom: &str) -> StdResult<u128> {
+        match self.storage.get(denom.as_bytes()) {
+            Some(amount_bytes) => slice_to_u128(&amount_bytes),
+            None => Ok(0),
+        }
+    }
+}
+
+// Struct to handle interaction with the code hashes contracts registered to receive sEVNT with
+pub struct Receivers<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Receivers<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_RECEIVERS)
+        }
+    }
+
+    // Set a contract's code hash, replacing any previous one
+    pub fn set_code_hash(&mut self, contract: &CanonicalAddr, code_hash: &str) {
+        self.storage.set(contract.as_slice(), code_hash.as_bytes());
+    }
+}
+
+// Struct to handle READONLY interaction with the code hashes of registered receivers
+pub struct ReadonlyReceivers<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
+
+impl<'a> ReadonlyReceivers<'a> {
+
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_RECEIVERS)
+        }
+    }
+
+    // Load a contract's code hash, if it registered to receive
+    pub fn may_load_code_hash(&self, contract: &CanonicalAddr) -> StdResult<Option<String>> {
+        self.storage
+            .get(contract.as_slice())
+            .map(|code_hash_bytes| {
+                String::from_utf8(code_hash_bytes).map_err(|_| StdError::invalid_utf8("Corrupted code hash found"))
+            })
+            .transpose()
+    }
+}
+
+// Season pass covering several of an organiser's events, either those listed or all those
+// starting within a period
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pass {
+    id: u128,
+    organiser: CanonicalAddr,
+    price: u128,
+    event_ids: Vec<u128>,
+    period: Option<(u64, u64)>,
+    max_passes: u128,
+    passes_sold: u128
+}
+
+impl Pass {
+    pub fn new(id: u128, organiser: CanonicalAddr, price: u128, max_passes: u128) -> Self {
+        Pass {
+            id,
+            organiser,
+            price,
+            event_ids: vec![],
+            period: None,
+            max_passes,
+            passes_sold: 0
+        }
+    }
+
+    pub fn get_id(&self) -> u128 {
+        self.id
+    }
+
+    pub fn get_organiser(&self) -> &CanonicalAddr {
+        &self.organiser
+    }
+
+    pub fn get_price(&self) -> u128 {
+        self.price
+    }
+
+    pub fn get_event_ids(&self) -> &Vec<u128> {
+        &self.event_ids
+    }
+
+    pub fn set_event_ids(&mut self, event_ids: Vec<u128>) -> StdResult<()> {
+        if event_ids.is_empty() {
+            return Err(StdError::generic_err("A pass must cover at least one event"));
+        }
+        if event_ids.iter().enumerate().any(|(i, event_id)| event_ids[..i].contains(event_id)) {
+            return Err(StdError::generic_err("Pass events must be unique"));
+        }
+        self.event_ids = event_ids;
+        Ok(())
+    }
+
+    // Period covered events start in, in seconds since epoch
+    pub fn get_period(&self) -> Option<(u64, u64)> {
+        self.period
+    }
+
+    pub fn set_period(&mut self, start: u64, end: u64) -> StdResult<()> {
+        if start >= end {
+            return Err(StdError::generic_err("Pass period start must be before its end"));
+        }
+        self.period = Some((start, end));
+        Ok(())
+    }
+
+    // Whether the pass can be redeemed for an event
+    pub fn covers(&self, event: &Event) -> bool {
+        if *event.get_organiser() != self.organiser {
+            return false;
+        }
+        match (self.period, event.get_start_time()) {
+            (Some((start, end)), Some(start_time)) => start <= start_time && start_time < end,
+            (Some(_), None) => false,
+            (None, _) => self.event_ids.contains(&event.get_id()),
+        }
+    }
+
+    pub fn get_max_passes(&self) -> u128 {
+        self.max_passes
+    }
+
+    pub fn get_passes_sold(&self) -> u128 {
+        self.passes_sold
+    }
+
+    pub fn is_sold_out(&self) -> bool {
+        self.passes_sold >= self.max_passes
+    }
+
+    pub fn pass_sold(&mut self) {
+        self.passes_sold += 1;
+    }
+}
+
+// Pass held by a guest and the events it has been redeemed for
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PassHolding {
+    pass_id: u128,
+    redeemed: Vec<u128>
+}
+
+impl PassHolding {
+    pub fn new(pass_id: u128) -> Self {
+        PassHolding {
+            pass_id,
+            redeemed: vec![]
+        }
+    }
+
+    pub fn get_pass_id(&self) -> u128 {
+        self.pass_id
+    }
+
+    pub fn get_redeemed(&self) -> &Vec<u128> {
+        &self.redeemed
+    }
+
+    pub fn redeem(&mut self, event_id: u128) -> StdResult<()> {
+        if self.redeemed.contains(&event_id) {
+            return Err(StdError::generic_err("Pass has already been redeemed for this event"));
         }
+        self.redeemed.push(event_id);
+        Ok(())
     }
 }
 
-// Struct to handle interaction with balances 
-pub struct Balances<'a> {
-    storage: PrefixedStorage<'a>,
+// Struct to handle interaction with passes
+pub struct Passes<'a> {
+    storage: PrefixedStorage<'a>
 }
 
-impl<'a> Balances<'a> {
+impl<'a> Passes<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
         Self {
-            storage: PrefixedStorage::new(storage, PREFIX_BALANCES),
+            storage: PrefixedStorage::new(storage, PREFIX_PASSES)
         }
     }
 
-    // Set balance of an account
-    pub fn set_account_balance(& mut self, account: &CanonicalAddr, amount: u128) {
-        self.storage.set(account.as_slice(), &amount.to_be_bytes());
+    // Store a pass
+    pub fn store_pass(&mut self, pass_id: u128, pass: &Pass) -> StdResult<()> {
+        self.storage.set(&pass_id.to_be_bytes(), &serialize(pass)?);
+        Ok(())
     }
 
-    // Read balance of an account
-    pub fn read_account_balance(&self, account: &CanonicalAddr) -> u128 {
-        let account_bytes = account.as_slice();
-        let result = self.storage.get(account_bytes);
-        match result {
-            Some(balance_bytes) => slice_to_u128(&balance_bytes).unwrap(),
-            None => 0,
-        }
+    // Get a pass
+    pub fn may_load_pass(&self, pass_id: u128) -> StdResult<Option<Pass>> {
+        self.storage.get(&pass_id.to_be_bytes()).map(|bytes| deserialize(&bytes)).transpose()
     }
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Event {
-    id: u128,
-    organiser: CanonicalAddr,
-    price: u128,
-    max_tickets: u128,
-    tickets_sold: u128,
-    seed:  [u8; 32]
+// Struct to handle READONLY interaction with passes
+pub struct ReadonlyPasses<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
 }
 
-impl Event {
-    pub fn new(id: u128, organiser: CanonicalAddr, price: u128, max_tickets: u128, entropy: u128) -> Self {
-
-        // Create seed
-        let mut hasher = Sha256::new();
-        hasher.update(entropy.to_be_bytes().as_slice());
-        let seed = hasher.finalize().into();
+impl<'a> ReadonlyPasses<'a> {
 
-        Event {
-            id,
-            organiser,
-            price,
-            max_tickets,
-            tickets_sold: 0,
-            seed
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_PASSES)
         }
     }
 
-    pub fn get_id(&self) -> u128 {
-        self.id
-    }
-
-    pub fn get_organiser(&self) -> &CanonicalAddr {
-        &self.organiser
+    // Get a pass
+    pub fn may_load_pass(&self, pass_id: u128) -> StdResult<Option<Pass>> {
+        self.storage.get(&pass_id.to_be_bytes()).map(|bytes| deserialize(&bytes)).transpose()
     }
+}
 
-    pub fn get_seed(&self) -> [u8; 32] {
-        self.seed
-    }
+// Struct to handle interaction with guests passes
+pub struct GuestsPasses<'a> {
+    storage: PrefixedStorage<'a>
+}
 
-    pub fn get_price(&self) -> u128 {
-        self.price
-    }
+impl<'a> GuestsPasses<'a> {
 
-    pub fn get_max_tickets(&self) -> u128 {
-        self.max_tickets
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
+        Self {
+            storage: PrefixedStorage::new(storage, PREFIX_GUESTS_PASSES)
+        }
     }
 
-    pub fn get_tickets_sold(&self) -> u128 {
-        self.tickets_sold
+    // Store a guest's passes
+    pub fn store_passes(&mut self, guest: &CanonicalAddr, passes: &Vec<PassHolding>) -> StdResult<()> {
+        self.storage.set(guest.as_slice(), &serialize(passes)?);
+        Ok(())
     }
 
-    pub fn get_tickets_left(&self) -> u128 {
-        self.max_tickets - self.tickets_sold
+    // Load a guest's passes
+    pub fn load_passes(&self, guest: &CanonicalAddr) -> StdResult<Vec<PassHolding>> {
+        match self.storage.get(guest.as_slice()) {
+            Some(passes_bytes) => deserialize(&passes_bytes),
+            None => Ok(vec![])
+        }
     }
+}
 
-    pub fn is_sold_out(&self) -> bool {
-        self.tickets_sold >= self.max_tickets
-    }
+// Struct to handle READONLY interaction with guests passes
+pub struct ReadonlyGuestsPasses<'a> {
+    storage: ReadonlyPrefixedStorage<'a>
+}
 
-    pub fn ticket_sold(& mut self, entropy: u128) {
-        self.tickets_sold += 1;
+impl<'a> ReadonlyGuestsPasses<'a> {
 
-        // Update seed
-        let mut hasher = Sha256::new_with_prefix(&self.seed);
-        hasher.update(entropy.to_be_bytes().as_slice());
-        self.seed = hasher.finalize().into();
+    // Retrieve prefixed storage
+    pub fn from_storage(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_GUESTS_PASSES)
+        }
     }
 
-    pub fn generate_secret(&self, ticket_id: u128::u128) -> u64 {
-        let mut rng = ChaChaRng::from_seed(self.seed);
-        rng.set_stream(ticket_id.low64());
-        rng.next_u64()
+    // Load a guest's passes
+    pub fn load_passes(&self, guest: &CanonicalAddr) -> StdResult<Vec<PassHolding>> {
+        match self.storage.get(guest.as_slice()) {
+            Some(passes_bytes) => deserialize(&passes_bytes),
+            None => Ok(vec![])
+        }
     }
 }
 
-// Struct to handle interaction with events
-pub struct Events<'a> {
-    storage: PrefixedStorage<'a>,
+// Asset deposited to fund a balance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Asset {
+    Native(String),
+    Sscrt
 }
 
-impl<'a> Events<'a> {
+// Struct to handle interaction with the assets each account deposited, in sEVNT and oldest first,
+// so withdrawals can pay back the same assets
+pub struct Fundings<'a> {
+    storage: PrefixedStorage<'a>
+}
+
+impl<'a> Fundings<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
         Self {
-            storage: PrefixedStorage::new(storage, PREFIX_EVENTS),
+            storage: PrefixedStorage::new(storage, PREFIX_FUNDINGS)
         }
     }
 
-    // Store event
-    pub fn store_event(& mut self, event_id: u128, event: &Event) {
-        self.storage.set(&event_id.to_be_bytes(), &bincode::serialize(event).unwrap());
+    // Store an account's funding
+    pub fn store_funding(&mut self, account: &CanonicalAddr, funding: &Vec<(Asset, u128)>) -> StdResult<()> {
+        if funding.is_empty() {
+            self.storage.remove(account.as_slice());
+        } else {
+            self.storage.set(account.as_slice(), &serialize(funding)?);
+        }
+        Ok(())
     }
 
-    // Try load an event
-    pub fn may_load_event(&self, event_id: u128) -> Option<Event> {
-        let id_bytes = event_id.to_be_bytes();
-        match self.storage.get(&id_bytes) {
-            Some(event_bytes) => Option::Some(bincode::deserialize(&event_bytes).unwrap()),
-            None => None
+    // Load an account's funding
+    pub fn load_funding(&self, account: &CanonicalAddr) -> StdResult<Vec<(Asset, u128)>> {
+        match self.storage.get(account.as_slice()) {
+            Some(funding_bytes) => deserialize(&funding_bytes),
+            None => Ok(vec![])
         }
     }
+
+    // Record an account depositing an asset
+    pub fn add_funding(&mut self, account: &CanonicalAddr, asset: Asset, amount: u128) -> StdResult<()> {
+        let mut funding = self.load_funding(account)?;
+        match funding.iter_mut().find(|(funded, _)| *funded == asset) {
+            Some((_, funded_amount)) => *funded_amount += amount,
+            None => funding.push((asset, amount)),
+        }
+        self.store_funding(account, &funding)
+    }
 }
 
-// Struct to handle READONLY interaction with events 
-pub struct ReadonlyEvents<'a> {
+// Struct to handle READONLY interaction with the assets each account deposited
+pub struct ReadonlyFundings<'a> {
     storage: ReadonlyPrefixedStorage<'a>
 }
 
-impl<'a> ReadonlyEvents<'a> {
+impl<'a> ReadonlyFundings<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a dyn Storage) -> Self {
         Self {
-            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_EVENTS)
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_FUNDINGS)
         }
     }
 
-    // Try load an event
-    pub fn may_load_event(&self, event_id: u128) -> Option<Event> {
-        let id_bytes = event_id.to_be_bytes();
-        match self.storage.get(&id_bytes) {
-            Some(event_bytes) => Option::Some(bincode::deserialize(&event_bytes).unwrap()),
-            None => None
+    // Load an account's funding
+    pub fn load_funding(&self, account: &CanonicalAddr) -> StdResult<Vec<(Asset, u128)>> {
+        match self.storage.get(account.as_slice()) {
+            Some(funding_bytes) => deserialize(&funding_bytes),
+            None => Ok(vec![])
         }
     }
 }
 
+// Order for several tickets opened by one guest and paid for by contributions from any
+// address, bought once fully funded
 #[derive(Clone, Serialize, Deserialize)]
-pub struct Ticket {
+pub struct GroupOrder {
     id: u128,
-    guest: CanonicalAddr,
+    opener: CanonicalAddr,
     event_id: u128,
-    state: u8,
-    secret: u64, 
-    pk: String
+    tier: Option<String>,
+    invite: Option<String>,
+    entropy: [u8; 32],
+    // Public key and requested seat of each ticket
+    holders: Vec<(GuestKey, Option<String>)>,
+    target: u128,
+    deadline: u64,
+    contributions: Vec<(CanonicalAddr, u128)>
 }
 
-impl Ticket {
-    pub fn new(id: u128, event_id: u128, guest: CanonicalAddr, secret: u64, pk: String) -> Self {
-        Ticket {
-            id, 
-            event_id, 
-            guest,
-            state: 0,
-            secret,
-            pk
+impl GroupOrder {
+    pub fn new(
+        id: u128,
+        opener: CanonicalAddr,
+        event_id: u128,
+        holders: Vec<(GuestKey, Option<String>)>,
+        target: u128,
+        deadline: u64,
+    ) -> Self {
+        GroupOrder {
+            id,
+            opener,
+            event_id,
+            tier: None,
+            invite: None,
+            entropy: [0u8; 32],
+            holders,
+            target,
+            deadline,
+            contributions: vec![]
         }
     }
 
     pub fn get_id(&self) -> u128 {
         self.id
     }
-    
+
+    pub fn get_opener(&self) -> &CanonicalAddr {
+        &self.opener
+    }
+
     pub fn get_event_id(&self) -> u128 {
         self.event_id
     }
 
-    pub fn get_guest(&self) -> &CanonicalAddr {
-        &self.guest
+    pub fn get_tier(&self) -> Option<&str> {
+        self.tier.as_deref()
     }
 
-    pub fn get_state(&self) -> u8 {
-        self.state
+    pub fn set_tier(&mut self, tier: Option<String>) {
+        self.tier = tier;
     }
 
-    pub fn get_pk(&self) -> String {
-        self.pk.clone()
+    pub fn get_invite(&self) -> Option<&str> {
+        self.invite.as_deref()
     }
 
-    pub fn start_validation(&mut self) -> u64 {
-        self.state = 1;
-        self.secret
+    pub fn set_invite(&mut self, invite: Option<String>) {
+        self.invite = invite;
     }
 
-    pub fn try_verify(&mut self, secret: u64) -> StdResult<()> {
-        self.secret = u64::from_str_radix("63F3A89C45DE97FA", 16).unwrap();
-        if self.secret != secret {
-            return Err(StdError::generic_err("Secret does not match"));
+    pub fn get_entropy(&self) -> &[u8; 32] {
+        &self.entropy
+    }
+
+    pub fn set_entropy(&mut self, entropy: [u8; 32]) {
+        self.entropy = entropy;
+    }
+
+    pub fn get_holders(&self) -> &Vec<(GuestKey, Option<String>)> {
+        &self.holders
+    }
+
+    // Amount of sEVNT the order must raise
+    pub fn get_target(&self) -> u128 {
+        self.target
+    }
+
+    // Time in seconds since epoch after which the order can no longer be funded
+    pub fn get_deadline(&self) -> u64 {
+        self.deadline
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.deadline
+    }
+
+    pub fn get_contributions(&self) -> &Vec<(CanonicalAddr, u128)> {
+        &self.contributions
+    }
+
+    pub fn get_funded(&self) -> u128 {
+        self.contributions.iter().map(|(_, amount)| amount).sum()
+    }
+
+    pub fn is_funded(&self) -> bool {
+        self.get_funded() >= self.target
+    }
+
+    // Record a contribution, which may not take the order past its target
+    pub fn contribute(&mut self, contributor: &CanonicalAddr, amount: u128) -> StdResult<()> {
+        let remaining = self.target - self.get_funded();
+        if amount == 0 || amount > remaining {
+            return Err(StdError::generic_err(format!(
+                "Contribution must be between 1 and the remaining amount: remaining={}",
+                remaining
+            )));
+        }
+        match self.contributions.iter_mut().find(|(address, _)| address == contributor) {
+            Some((_, contributed)) => *contributed += amount,
+            None => self.contributions.push((contributor.clone(), amount)),
         }
-        
-        self.secret = 0;
-        self.state = 2;
         Ok(())
     }
 }
 
-// Struct to handle interaction with tickets
-pub struct Tickets<'a> {
-    storage: PrefixedStorage<'a>,
+// Struct to handle interaction with group orders
+pub struct GroupOrders<'a> {
+    storage: PrefixedStorage<'a>
 }
 
-impl<'a> Tickets<'a> {
+impl<'a> GroupOrders<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
         Self {
-            storage: PrefixedStorage::new(storage, PREFIX_TICKETS),
+            storage: PrefixedStorage::new(storage, PREFIX_GROUP_ORDERS)
         }
     }
 
-    // Store ticket
-    pub fn store_ticket(& mut self, ticket_id: u128, ticket: &Ticket) {
-        self.storage.set(&ticket_id.to_be_bytes(), &bincode::serialize(ticket).unwrap());
+    // Store a group order
+    pub fn store_group_order(&mut self, order_id: u128, order: &GroupOrder) -> StdResult<()> {
+        self.storage.set(&order_id.to_be_bytes(), &serialize(order)?);
+        Ok(())
     }
 
-    // Try load a ticket
-    pub fn may_load_ticket(&self, ticket_id: u128) -> Option<Ticket> {
-        let id_bytes = ticket_id.to_be_bytes();
-        match self.storage.get(&id_bytes) {
-            Some(ticket_bytes) => Option::Some(bincode::deserialize(&ticket_bytes).unwrap()),
-            None => None
-        }
+    // Remove a group order once bought or cancelled
+    pub fn remove_group_order(&mut self, order_id: u128) {
+        self.storage.remove(&order_id.to_be_bytes());
     }
 
-    // Delete a ticket?
+    // Get a group order
+    pub fn may_load_group_order(&self, order_id: u128) -> StdResult<Option<GroupOrder>> {
+        self.storage.get(&order_id.to_be_bytes()).map(|bytes| deserialize(&bytes)).transpose()
+    }
 }
 
-// Struct to handle READONLY interaction with events 
-pub struct ReadonlyTickets<'a> {
+// Struct to handle READONLY interaction with group orders
+pub struct ReadonlyGroupOrders<'a> {
     storage: ReadonlyPrefixedStorage<'a>
 }
 
-impl<'a> ReadonlyTickets<'a> {
+impl<'a> ReadonlyGroupOrders<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a dyn Storage) -> Self {
         Self {
-            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_TICKETS)
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_GROUP_ORDERS)
         }
     }
 
-    // Try load a ticket
-    pub fn may_load_ticket(&self, ticket_id: u128) -> Option<Ticket> {
-        let id_bytes = ticket_id.to_be_bytes();
-        match self.storage.get(&id_bytes) {
-            Some(ticket_bytes) => Option::Some(bincode::deserialize(&ticket_bytes).unwrap()),
-            None => None
-        }
+    // Get a group order
+    pub fn may_load_group_order(&self, order_id: u128) -> StdResult<Option<GroupOrder>> {
+        self.storage.get(&order_id.to_be_bytes()).map(|bytes| deserialize(&bytes)).transpose()
     }
 }
 
-// Struct to handle interaction with organisers events
-pub struct OrganisersEvents<'a> {
+// Struct to handle interaction with viewing keys, stored hashed
+pub struct ViewingKeys<'a> {
     storage: PrefixedStorage<'a>
 }
 
-impl<'a> OrganisersEvents<'a> {
+impl<'a> ViewingKeys<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
         Self {
-            storage: PrefixedStorage::new(storage, PREFIX_ORGANISERS_EVENTS)
+            storage: PrefixedStorage::new(storage, PREFIX_VIEWING_KEYS)
         }
     }
 
-    // Store events
-    pub fn store_events(& mut self, organiser: &CanonicalAddr, events: &Vec<u128>) {
-        self.storage.set(&organiser.to_string().as_bytes(), &bincode::serialize(events).unwrap());
-    }    
-
-    // Load an organisers events
-    pub fn load_events(&self, organiser: &CanonicalAddr) -> Vec<u128> {
-        match self.storage.get(&organiser.to_string().as_bytes()) {
-            Some(events_bytes) => bincode::deserialize(&events_bytes).unwrap(),
-            None => vec![]
-        }
+    // Set an address' viewing key, replacing any previous one
+    pub fn set_viewing_key(&mut self, address: &CanonicalAddr, key: &str) {
+        self.storage.set(address.as_slice(), &Sha256::digest(key.as_bytes()));
     }
 }
 
-// Struct to handle READONLY interaction with organisers events
-pub struct ReadonlyOrganisersEvents<'a> {
+// Struct to handle READONLY interaction with viewing keys
+pub struct ReadonlyViewingKeys<'a> {
     storage: ReadonlyPrefixedStorage<'a>
 }
 
-impl<'a> ReadonlyOrganisersEvents<'a> {
+impl<'a> ReadonlyViewingKeys<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a dyn Storage) -> Self {
         Self {
-            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_ORGANISERS_EVENTS)
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_VIEWING_KEYS)
         }
     }
 
-    // Load an organisers events
-    pub fn load_events(&self, organiser: &CanonicalAddr) -> Vec<u128> {
-        match self.storage.get(&organiser.to_string().as_bytes()) {
-            Some(events_bytes) => bincode::deserialize(&events_bytes).unwrap(),
-            None => vec![]
-        }
+    // Check a key against an address' viewing key
+    pub fn check_viewing_key(&self, address: &CanonicalAddr, key: &str) -> bool {
+        let key_hash = Sha256::digest(key.as_bytes());
+        let (stored, is_set) = match self.storage.get(address.as_slice()) {
+            Some(stored) => (stored, true),
+            None => (vec![0; 32], false),
+        };
+        ct_eq(&stored, &key_hash) && is_set
     }
 }
 
-// Struct to handle interaction with guests tickets
-pub struct GuestsTickets<'a> {
+// Struct to handle interaction with permits their signers have revoked
+pub struct RevokedPermits<'a> {
     storage: PrefixedStorage<'a>
 }
 
-impl<'a> GuestsTickets<'a> {
+impl<'a> RevokedPermits<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a mut dyn Storage) -> Self {
         Self {
-            storage: PrefixedStorage::new(storage, PREFIX_GUESTS_TICKETS)
+            storage: PrefixedStorage::new(storage, PREFIX_REVOKED_PERMITS)
         }
     }
 
-    // Store tickets
-    pub fn store_tickets(& mut self, guest: &CanonicalAddr, tickets: &Vec<u128>) {
-        self.storage.set(&guest.to_string().as_bytes(), &bincode::serialize(tickets).unwrap());
-    }    
-
-    // Load an guests tickets
-    pub fn load_tickets(&self, guest: &CanonicalAddr) -> Vec<u128> {
-        match self.storage.get(&guest.to_string().as_bytes()) {
-            Some(tickets_bytes) => bincode::deserialize(&tickets_bytes).unwrap(),
-            None => vec![]
-        }
+    // Revoke an address' permit by name
+    pub fn revoke(&mut self, address: &CanonicalAddr, permit_name: &str) {
+        self.storage.set(&permit_key(address, permit_name), &[1]);
     }
 }
 
-// Struct to handle READONLY interaction with organisers events
-pub struct ReadonlyGuestsTickets<'a> {
+// Struct to handle READONLY interaction with revoked permits
+pub struct ReadonlyRevokedPermits<'a> {
     storage: ReadonlyPrefixedStorage<'a>
 }
 
-impl<'a> ReadonlyGuestsTickets<'a> {
+impl<'a> ReadonlyRevokedPermits<'a> {
 
     // Retrieve prefixed storage
     pub fn from_storage(storage: &'a dyn Storage) -> Self {
         Self {
-            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_GUESTS_TICKETS)
+            storage: ReadonlyPrefixedStorage::new(storage, PREFIX_REVOKED_PERMITS)
         }
     }
 
-    // Load an guests tickets
-    pub fn load_tickets(&self, guest: &CanonicalAddr) -> Vec<u128> {
-        match self.storage.get(&guest.to_string().as_bytes()) {
-            Some(tickets_bytes) => bincode::deserialize(&tickets_bytes).unwrap(),
-            None => vec![]
+    // Check if an address has revoked a permit
+    pub fn is_revoked(&self, address: &CanonicalAddr, permit_name: &str) -> bool {
+        self.storage.get(&permit_key(address, permit_name)).is_some()
+    }
+}
+
+// Helper function to build a key for an address' permit, length prefixing the address so
+// names cannot collide across addresses
+fn permit_key(address: &CanonicalAddr, permit_name: &str) -> Vec<u8> {
+    let mut key = vec![address.len() as u8];
+    key.extend_from_slice(address.as_slice());
+    key.extend_from_slice(permit_name.as_bytes());
+    key
+}
+
+// Helper function to build a key for an owner's allowance to a spender, length prefixing the
+// owner so pairs cannot collide
+fn allowance_key(owner: &CanonicalAddr, spender: &CanonicalAddr) -> Vec<u8> {
+    let mut key = vec![owner.len() as u8];
+    key.extend_from_slice(owner.as_slice());
+    key.extend_from_slice(spender.as_slice());
+    key
+}
+
+// Helper function to build a key for an address's allowance index
+fn allowance_index_key(direction: &[u8], address: &CanonicalAddr) -> Vec<u8> {
+    [direction, b"/", address.as_slice()].concat()
+}
+
+// Helper function to build a key for an entry of an owner's append store, length prefixing the
+// owner so entries of different owners cannot collide
+fn list_key(owner: &[u8], suffix: &[u8]) -> Vec<u8> {
+    [&[owner.len() as u8], owner, suffix].concat()
+}
+
+fn item_suffix(index: u32) -> Vec<u8> {
+    [b"item".as_slice(), &index.to_be_bytes()].concat()
+}
+
+fn position_suffix(id: u128) -> Vec<u8> {
+    [b"pos".as_slice(), &id.to_be_bytes()].concat()
+}
+
+// Number of ids in an owner's append store
+fn list_len(storage: &dyn Storage, owner: &[u8]) -> StdResult<u32> {
+    match storage.get(&list_key(owner, b"len")) {
+        Some(len_bytes) => slice_to_u32(&len_bytes),
+        None => Ok(0)
+    }
+}
+
+// Id at an index of an owner's append store
+fn list_get(storage: &dyn Storage, owner: &[u8], index: u32) -> StdResult<u128> {
+    match storage.get(&list_key(owner, &item_suffix(index))) {
+        Some(item_bytes) => slice_to_u128(&item_bytes),
+        None => Err(StdError::generic_err(format!(
+            "Corrupted data found. Append store is missing index {}.",
+            index
+        ))),
+    }
+}
+
+// Append an id to an owner's append store, returning its index
+fn list_push(storage: &mut dyn Storage, owner: &[u8], id: u128) -> StdResult<u32> {
+    let index = list_len(storage, owner)?;
+    storage.set(&list_key(owner, &item_suffix(index)), &id.to_be_bytes());
+    storage.set(&list_key(owner, b"len"), &(index + 1).to_be_bytes());
+    Ok(index)
+}
+
+// Ids after a cursor in an owner's append store, read only as they are reached. Only for stores
+// appended to in ascending order, so the start can be found by binary search
+fn list_iter_after(
+    storage: &dyn Storage,
+    owner: Vec<u8>,
+    start_after: u128,
+) -> StdResult<impl Iterator<Item = StdResult<u128>> + '_> {
+    let len = list_len(storage, &owner)?;
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if list_get(storage, &owner, mid)? <= start_after {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Ok((low..len).map(move |index| list_get(storage, &owner, index)))
+}
+
+// Remove and return a list of ids stored whole under an address string, as organisers events and
+// guests tickets were before append stores. Address strings are ASCII, so cannot collide with
+// append store keys, which start with the address length
+fn take_legacy_list(storage: &mut dyn Storage, address: &CanonicalAddr) -> StdResult<Vec<u128>> {
+    let key = address.to_string().into_bytes();
+    match storage.get(&key) {
+        Some(list_bytes) => {
+            storage.remove(&key);
+            let mut reader = LegacyReader::new(&list_bytes);
+            let list = (0..reader.read_len()?).map(|_| reader.read_u128()).collect::<StdResult<_>>()?;
+            reader.finish()?;
+            Ok(list)
+        }
+        None => Ok(vec![])
+    }
+}
+
+// Every id in an owner's append store
+fn list_load(storage: &dyn Storage, owner: &[u8]) -> StdResult<Vec<u128>> {
+    (0..list_len(storage, owner)?).map(|index| list_get(storage, owner, index)).collect()
+}
+
+// Helper function to encode a stored value as JSON, which unlike a packed binary encoding names
+// its fields and fails with an error rather than a panic
+fn serialize<T: Serialize>(value: &T) -> StdResult<Vec<u8>> {
+    to_vec(value)
+}
+
+// Helper function to decode a stored JSON value. Entries written before the switch to JSON are
+// bincode encoded and only read by migrations, through LegacyReader
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> StdResult<T> {
+    from_slice(bytes)
+}
+
+// Reader for entries written before the switch to JSON, in bincode's default encoding: integers
+// and lengths fixed width little endian, fields in order with nothing naming them
+struct LegacyReader<'a> {
+    bytes: &'a [u8]
+}
+
+impl<'a> LegacyReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> StdResult<&'a [u8]> {
+        if self.bytes.len() < len {
+            return Err(StdError::generic_err("Corrupted legacy entry. Unexpected end of data."));
+        }
+        let (taken, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn read_u8(&mut self) -> StdResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u64(&mut self) -> StdResult<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn read_u128(&mut self) -> StdResult<u128> {
+        Ok(u128::from_le_bytes(self.take(16)?.try_into().unwrap()))
+    }
+
+    fn read_bytes32(&mut self) -> StdResult<[u8; 32]> {
+        Ok(self.take(32)?.try_into().unwrap())
+    }
+
+    // Length of a following sequence or string, which cannot be longer than the data left
+    fn read_len(&mut self) -> StdResult<usize> {
+        match usize::try_from(self.read_u64()?) {
+            Ok(len) if len <= self.bytes.len() => Ok(len),
+            _ => Err(StdError::generic_err("Corrupted legacy entry. Length exceeds data.")),
+        }
+    }
+
+    fn read_string(&mut self) -> StdResult<String> {
+        let len = self.read_len()?;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(StdError::invalid_utf8)
+    }
+
+    // Addresses were encoded as their base64 string
+    fn read_addr(&mut self) -> StdResult<CanonicalAddr> {
+        Ok(CanonicalAddr(Binary::from_base64(&self.read_string()?)?))
+    }
+
+    // Ensure the whole entry was read
+    fn finish(self) -> StdResult<()> {
+        if !self.bytes.is_empty() {
+            return Err(StdError::generic_err("Corrupted legacy entry. Unexpected trailing data."));
         }
+        Ok(())
+    }
+}
+
+// Helper function to build a key scoped to an event
+fn event_key(event_id: u128, suffix: &[u8]) -> Vec<u8> {
+    let mut key = event_id.to_be_bytes().to_vec();
+    key.extend_from_slice(suffix);
+    key
+}
+
+// Compare two byte strings in constant time, so a mismatch leaks nothing about where it occurs
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b.iter()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// Share of an amount in basis points, rounding down, None if it overflows
+pub fn bps_of(amount: u128, bps: u32) -> Option<u128> {
+    Uint128::from(amount).checked_multiply_ratio(bps, 10_000u128).ok().map(|share| share.u128())
+}
+
+// Helper function to convert slice of u8 to u32
+fn slice_to_u32(data: &[u8]) -> StdResult<u32> {
+    match <[u8; 4]>::try_from(data) {
+        Ok(bytes) => Ok(u32::from_be_bytes(bytes)),
+        Err(_) => Err(StdError::generic_err(
+            "Corrupted data found. 4 byte expected.",
+        )),
     }
 }
 